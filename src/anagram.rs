@@ -0,0 +1,172 @@
+use crate::dictionary::{Cursor, Dictionary};
+use crate::score::{LetterValues, Scoring, boggle_score, length_bonus};
+use crate::solver::{SolveOptions, Sort};
+use crate::trie::Payload;
+use std::cmp::Reverse;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anagram {
+    pub word: String,
+    pub score: u32, // Letter values and the length bonus, without bonus tiles.
+    pub payload: Option<Payload>,
+}
+
+// Every word that can be made from letters, in any order, each letter used
+// at most as often as it's given, as with a Scrabble rack. Of opts only
+// min_len, max_len, scoring, sort and limit apply; the rest are about the
+// board. Sorted by path, there being no paths, results come in alphabetical
+// order.
+pub fn solve_anagram<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    letters: &[char],
+    opts: &SolveOptions<W, H>,
+) -> Vec<Anagram> {
+    let mut letters = letters.to_vec();
+    letters.sort_unstable();
+    let mut counts: Vec<(char, usize)> = vec![];
+    for ch in letters {
+        match counts.last_mut() {
+            Some((last, count)) if *last == ch => *count += 1,
+            _ => counts.push((ch, 1)),
+        }
+    }
+    let mut walk = Walk {
+        counts,
+        opts,
+        word: String::new(),
+        len: 0,
+        out: vec![],
+    };
+    walk.descend(dict.root());
+
+    let mut results = walk.out;
+    match opts.sort {
+        Sort::ByPath | Sort::Alphabetical => {}
+        Sort::ByScore => results.sort_by_key(|result| Reverse(result.score)),
+        Sort::ByLength => results.sort_by_key(|result| Reverse(result.word.chars().count())),
+        Sort::ByFrequency => results.sort_by_key(|result| {
+            let frequency = result
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.frequency);
+            Reverse((frequency, result.score))
+        }),
+    }
+    if let Some(limit) = opts.limit {
+        results.truncate(limit);
+    }
+    results
+}
+
+// The trie walked by what's left of each letter instead of by adjacency.
+// Each step takes a different letter, so each word is found once.
+struct Walk<'a, const W: usize, const H: usize> {
+    counts: Vec<(char, usize)>, // Sorted by char, each char once.
+    opts: &'a SolveOptions<W, H>,
+    word: String,
+    len: usize, // Of word, in chars.
+    out: Vec<Anagram>,
+}
+
+impl<const W: usize, const H: usize> Walk<'_, W, H> {
+    fn descend<C: Cursor>(&mut self, cursor: C) {
+        for i in 0..self.counts.len() {
+            let (ch, count) = self.counts[i];
+            let Some(node) = (count > 0).then(|| cursor.descend(ch)).flatten() else {
+                continue;
+            };
+            self.counts[i].1 -= 1;
+            self.word.push(ch);
+            self.len += 1;
+            if node.is_terminal() && self.len >= self.opts.min_len {
+                self.out.push(Anagram {
+                    word: self.word.clone(),
+                    score: score(
+                        self.opts.scoring,
+                        &self.opts.letter_values,
+                        &self.word,
+                        self.len,
+                    ),
+                    payload: node.payload(),
+                });
+            }
+            if self.opts.max_len.is_none_or(|max| self.len < max) {
+                self.descend(node);
+            }
+            self.len -= 1;
+            self.word.pop();
+            self.counts[i].1 += 1;
+        }
+    }
+}
+
+fn score(scoring: Scoring, values: &LetterValues, word: &str, len: usize) -> u32 {
+    match scoring {
+        Scoring::Ruzzle => {
+            word.chars().map(|ch| values.letter_value(ch)).sum::<u32>() + length_bonus(len)
+        }
+        Scoring::Boggle => boggle_score(len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::TrieNode;
+
+    fn words(results: &[Anagram]) -> Vec<&str> {
+        results.iter().map(|result| result.word.as_str()).collect()
+    }
+
+    #[test]
+    fn counts_repeated_letters() {
+        let mut root = TrieNode::new_root();
+        for word in ["setter", "street", "teeter", "tree", "see", "tax", "e"] {
+            root.add_word(word);
+        }
+        let letters: Vec<char> = "setter".chars().collect();
+        let opts = SolveOptions::<4, 4>::default();
+
+        // "teeter" needs a third 'e', "tax" an 'a' and an 'x'.
+        let results = solve_anagram(&root, &letters, &opts);
+        assert_eq!(words(&results), ["see", "setter", "street", "tree"]);
+        // s 1 + e 1 + e 1; six letters of 1 point each, and 10 for length.
+        assert_eq!(results[0].score, 3);
+        assert_eq!(results[1].score, 16);
+
+        let opts = SolveOptions {
+            sort: Sort::ByScore,
+            limit: Some(3),
+            ..opts
+        };
+        let best = solve_anagram(&root, &letters, &opts);
+        assert_eq!(words(&best), ["setter", "street", "tree"]);
+
+        let opts = SolveOptions {
+            min_len: 1,
+            max_len: Some(3),
+            scoring: Scoring::Boggle,
+            sort: Sort::ByPath,
+            limit: None,
+            ..opts
+        };
+        let short = solve_anagram(&root, &letters, &opts);
+        assert_eq!(words(&short), ["e", "see"]);
+        assert_eq!(short[1].score, 1);
+
+        root.add_word_with(
+            "tree",
+            Payload {
+                frequency: Some(70),
+                definition: None,
+            },
+        );
+        let opts = SolveOptions {
+            sort: Sort::ByFrequency,
+            ..SolveOptions::<4, 4>::default()
+        };
+        let common = solve_anagram(&root, &letters, &opts);
+        assert_eq!(words(&common), ["tree", "setter", "street", "see"]);
+        assert_eq!(common[0].payload.as_ref().unwrap().frequency, Some(70));
+    }
+}
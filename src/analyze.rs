@@ -0,0 +1,114 @@
+use crate::board::Board;
+use crate::solver::{SolveOptions, SolveResult, solve_best_words};
+use crate::tile::Tile;
+use crate::trie::{TrieNode, normalize};
+
+// How a list of words someone found compares with the full solution.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MissedReport<const W: usize = 4, const H: usize = 4> {
+    pub found: Vec<SolveResult<W, H>>, // The valid words of the list, by their best paths.
+    pub missed: Vec<SolveResult<W, H>>, // Every other word of the solution.
+    pub not_on_board: Vec<String>,
+    pub not_in_dictionary: Vec<String>, // On the board, but not words.
+    pub too_short: Vec<String>,         // Shorter than opts.min_len.
+    // Words on the board, but left out by the other options, e.g. max_len,
+    // must_include or allowed.
+    pub excluded: Vec<String>,
+    pub found_score: u32,
+    pub missed_score: u32,
+}
+
+// Sorts the words of found into the report's lists, each word once and
+// compared lowercase. found and missed keep the order of
+// solve_best_words(), so sort by score for the best missed words first;
+// opts.limit is ignored, so that every word is accounted for.
+pub fn analyze<const W: usize, const H: usize>(
+    found: &[String],
+    root: &TrieNode,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> MissedReport<W, H> {
+    let opts = SolveOptions {
+        limit: None,
+        ..opts.clone()
+    };
+    let mut words: Vec<String> = found
+        .iter()
+        .map(|word| normalize(word.trim()).into_owned())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.sort();
+    words.dedup();
+
+    let mut report = MissedReport::default();
+    let (found, missed) = solve_best_words(root, board, &opts)
+        .into_iter()
+        .partition(|result| words.binary_search(&result.word).is_ok());
+    report.found = found;
+    report.missed = missed;
+    for word in words {
+        if report.found.iter().any(|result| result.word == word) {
+            continue;
+        }
+        if board.find_paths_for_word(&word).is_empty() {
+            report.not_on_board.push(word);
+        } else if !root.contains_word(&word) {
+            report.not_in_dictionary.push(word);
+        } else if word.chars().count() < opts.min_len {
+            report.too_short.push(word);
+        } else {
+            report.excluded.push(word);
+        }
+    }
+    report.found_score = report.found.iter().map(|result| result.score).sum();
+    report.missed_score = report.missed.iter().map(|result| result.score).sum();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::RuzzleBoard;
+    use crate::solver::Sort;
+
+    #[test]
+    fn sorts_found_words() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "abe", "be", "zoo", "a"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let opts = SolveOptions {
+            sort: Sort::ByScore,
+            limit: Some(1),
+            ..SolveOptions::default()
+        };
+        let found: Vec<String> = ["FAB", "be", "fab", "", "zoo", "bef", "a"]
+            .map(String::from)
+            .to_vec();
+        let report = analyze(&found, &root, &board, &opts);
+
+        let words = |results: &[SolveResult]| -> Vec<String> {
+            results.iter().map(|result| result.word.clone()).collect()
+        };
+        assert_eq!(words(&report.found), ["fab", "be"]);
+        assert_eq!(words(&report.missed), ["abe", "ab"]);
+        assert_eq!(report.not_on_board, ["zoo"]);
+        assert_eq!(report.not_in_dictionary, ["bef"]);
+        assert_eq!(report.too_short, ["a"]);
+        // f 4 + a 1 + b 4, b 4 + e 1.
+        assert_eq!(report.found_score, 14);
+        // a 1 + b 4 + e 1, a 1 + b 4.
+        assert_eq!(report.missed_score, 11);
+
+        // Too long for max_len, rather than too short.
+        let opts = SolveOptions {
+            max_len: Some(2),
+            ..SolveOptions::default()
+        };
+        let report = analyze(&found, &root, &board, &opts);
+        assert_eq!(words(&report.found), ["be"]);
+        assert_eq!(report.too_short, ["a"]);
+        assert_eq!(report.excluded, ["fab"]);
+    }
+}
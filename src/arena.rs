@@ -0,0 +1,152 @@
+use crate::dictionary::{Cursor, Dictionary};
+use crate::trie::TrieNode;
+use std::collections::VecDeque;
+
+// A trie stored in one Vec, with the children of each node kept next to
+// each other (and sorted) so that lookups touch as little memory as possible.
+// Only the words are kept, not their payloads.
+pub struct ArenaTrie {
+    nodes: Vec<ArenaNode>, // nodes[0] is the root.
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ArenaNode {
+    ch: char, // '\0' for the root.
+    is_terminal: bool,
+    first_child: u32,
+    child_count: u32,
+}
+
+impl ArenaTrie {
+    pub fn from_words<I: IntoIterator<Item = S>, S: AsRef<str>>(words: I) -> ArenaTrie {
+        words.into_iter().collect::<TrieNode>().to_arena()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn root(&self) -> ArenaCursor<'_> {
+        ArenaCursor {
+            arena: self,
+            node: 0,
+        }
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        let mut cursor = self.root();
+        for ch in crate::trie::normalize(word).chars() {
+            match cursor.descend(ch) {
+                Some(child) => cursor = child,
+                None => return false,
+            }
+        }
+        cursor.is_terminal()
+    }
+
+    fn children_of(&self, node: u32) -> &[ArenaNode] {
+        let node = &self.nodes[node as usize];
+        let first = node.first_child as usize;
+        &self.nodes[first..first + node.child_count as usize]
+    }
+}
+
+impl TrieNode {
+    pub fn to_arena(&self) -> ArenaTrie {
+        // Breadth first, so that each node's children end up contiguous.
+        let mut nodes = vec![ArenaNode {
+            ch: '\0',
+            is_terminal: self.is_terminal,
+            first_child: 0,
+            child_count: 0,
+        }];
+        let mut queue: VecDeque<(&TrieNode, usize)> = VecDeque::from([(self, 0)]);
+        while let Some((trie_node, pos)) = queue.pop_front() {
+            nodes[pos].first_child = nodes.len() as u32;
+            nodes[pos].child_count = trie_node.children.len() as u32;
+            for child in &trie_node.children {
+                queue.push_back((child, nodes.len()));
+                nodes.push(ArenaNode {
+                    ch: child.ch.unwrap_or('\0'),
+                    is_terminal: child.is_terminal,
+                    first_child: 0,
+                    child_count: 0,
+                });
+            }
+        }
+        ArenaTrie { nodes }
+    }
+}
+
+impl Dictionary for ArenaTrie {
+    type Cursor<'a> = ArenaCursor<'a>;
+
+    fn root(&self) -> ArenaCursor<'_> {
+        ArenaTrie::root(self)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ArenaCursor<'a> {
+    arena: &'a ArenaTrie,
+    node: u32,
+}
+
+impl Cursor for ArenaCursor<'_> {
+    fn descend(self, ch: char) -> Option<Self> {
+        let first = self.arena.nodes[self.node as usize].first_child;
+        let children = self.arena.children_of(self.node);
+        let pos = children.binary_search_by_key(&ch, |c| c.ch).ok()?;
+        Some(ArenaCursor {
+            arena: self.arena,
+            node: first + pos as u32,
+        })
+    }
+
+    fn is_terminal(self) -> bool {
+        self.arena.nodes[self.node as usize].is_terminal
+    }
+
+    fn ch(self) -> Option<char> {
+        match self.node {
+            0 => None,
+            n => Some(self.arena.nodes[n as usize].ch),
+        }
+    }
+
+    fn children(self) -> impl Iterator<Item = Self> {
+        let node = self.arena.nodes[self.node as usize];
+        (node.first_child..node.first_child + node.child_count).map(move |n| ArenaCursor {
+            arena: self.arena,
+            node: n,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arena_matches_trie() {
+        let words = [
+            "hello", "he", "hell", "rust", "rusty", "trie", "tree", "åsna",
+        ];
+        let mut root = TrieNode::new_root();
+        for word in words {
+            root.add_word(word);
+        }
+        let arena = root.to_arena();
+        assert_eq!(arena.node_count(), root.node_count());
+        for word in words {
+            assert!(arena.contains_word(word));
+        }
+        for word in ["", "h", "hel", "rusts", "tri", "ås", "x"] {
+            assert!(!arena.contains_word(word));
+        }
+
+        let from_words = ArenaTrie::from_words(words);
+        assert_eq!(from_words.node_count(), arena.node_count());
+        assert!(from_words.contains_word("TREE"));
+    }
+}
@@ -1,9 +1,14 @@
+use crate::Path;
+use crate::mask::BoardMask;
+use crate::tile::{Tile, WILDCARD};
+use crate::trie::{compose, normalize};
 use std::fmt;
-use std::ops::Index;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BoardIndex<const W: usize, const H: usize> {
-    flattened: usize,
+    pub(crate) flattened: usize,
 }
 
 impl<const W: usize, const H: usize> BoardIndex<W, H> {
@@ -12,11 +17,32 @@ impl<const W: usize, const H: usize> BoardIndex<W, H> {
     }
 
     pub const fn from_xy(x: usize, y: usize) -> Self {
+        debug_assert!(x < W && y < H, "board index out of bounds");
         Self {
             flattened: x + W * y,
         }
     }
 
+    pub const fn try_from_xy(x: usize, y: usize) -> Option<Self> {
+        if x < W && y < H {
+            Some(Self::from_xy(x, y))
+        } else {
+            None
+        }
+    }
+
+    pub const fn try_from_flat(n: usize) -> Option<Self> {
+        if n < W * H {
+            Some(Self { flattened: n })
+        } else {
+            None
+        }
+    }
+
+    pub const fn flat(&self) -> usize {
+        self.flattened
+    }
+
     pub const fn to_xy(self) -> (usize, usize) {
         (self.flattened % W, self.flattened / W)
     }
@@ -43,6 +69,110 @@ impl<const W: usize, const H: usize> BoardIndex<W, H> {
     }
 }
 
+// Compass directions on the board, with north being up (decreasing y).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    // (dx, dy) of one step.
+    pub const fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::N => (0, -1),
+            Direction::NE => (1, -1),
+            Direction::E => (1, 0),
+            Direction::SE => (1, 1),
+            Direction::S => (0, 1),
+            Direction::SW => (-1, 1),
+            Direction::W => (-1, 0),
+            Direction::NW => (-1, -1),
+        }
+    }
+
+    // The direction of the step from a to b, if they are neighbours.
+    pub fn between<const W: usize, const H: usize>(
+        a: BoardIndex<W, H>,
+        b: BoardIndex<W, H>,
+    ) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|&dir| a.neighbour(dir) == Some(b))
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<const W: usize, const H: usize> BoardIndex<W, H> {
+    pub fn chebyshev_distance(&self, other: Self) -> usize {
+        let ((x, y), (ox, oy)) = (self.to_xy(), other.to_xy());
+        x.abs_diff(ox).max(y.abs_diff(oy))
+    }
+
+    pub fn manhattan_distance(&self, other: Self) -> usize {
+        let ((x, y), (ox, oy)) = (self.to_xy(), other.to_xy());
+        x.abs_diff(ox) + y.abs_diff(oy)
+    }
+
+    // Diagonals count, but a tile isn't adjacent to itself.
+    pub fn is_adjacent(&self, other: Self) -> bool {
+        self.chebyshev_distance(other) == 1
+    }
+
+    pub fn neighbour(&self, dir: Direction) -> Option<Self> {
+        let (x, y) = self.to_xy();
+        let (dx, dy) = dir.offset();
+        Self::try_from_xy(x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)
+    }
+}
+
+// A path as its first tile and the direction of every step after it, e.g.
+// for swiping. None for empty paths and paths with non-adjacent steps.
+pub fn path_to_directions<const W: usize, const H: usize>(
+    path: &[BoardIndex<W, H>],
+) -> Option<(BoardIndex<W, H>, Vec<Direction>)> {
+    let (&start, _) = path.split_first()?;
+    let dirs = path
+        .windows(2)
+        .map(|step| Direction::between(step[0], step[1]))
+        .collect::<Option<Vec<Direction>>>()?;
+    Some((start, dirs))
+}
+
+// The inverse of path_to_directions(). None if a step leaves the board.
+pub fn path_from_directions<const W: usize, const H: usize>(
+    start: BoardIndex<W, H>,
+    dirs: &[Direction],
+) -> Option<Vec<BoardIndex<W, H>>> {
+    let mut path = vec![start];
+    for &dir in dirs {
+        path.push(path.last()?.neighbour(dir)?);
+    }
+    Some(path)
+}
+
 impl<const W: usize, const H: usize> fmt::Display for BoardIndex<W, H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (x, y) = self.to_xy();
@@ -51,63 +181,234 @@ impl<const W: usize, const H: usize> fmt::Display for BoardIndex<W, H> {
     }
 }
 
+// As {"x": 0, "y": 0}, top left.
+#[cfg(feature = "serde")]
+impl<const W: usize, const H: usize> serde::Serialize for BoardIndex<W, H> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let (x, y) = self.to_xy();
+        let mut state = serializer.serialize_struct("BoardIndex", 2)?;
+        state.serialize_field("x", &x)?;
+        state.serialize_field("y", &y)?;
+        state.end()
+    }
+}
+
 pub type Index4x4 = BoardIndex<4, 4>;
 
+// A W wide, H high grid of T, stored row by row. Indexed with a BoardIndex
+// of the same size, or with its flat index.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Board4x4<T>([T; 16]);
-// If const generic expressions were supported in Rust as of
-// I would implement a generic Board class with const param-
-// eters W and H (To match the BoardIndex<W,H>), and set the
-// size of the array to W*H. Unfortunately, Rust does not
-// allow this at the time of writing (2025-12-21). It exists
-// in nightly builds, so it might be coming.
+pub struct Board<T, const W: usize = 4, const H: usize = 4>([[T; W]; H]);
+
+pub type Board4x4<T> = Board<T, 4, 4>;
+
+impl<T, const W: usize, const H: usize> Board<T, W, H> {
+    pub fn from_fn(mut f: impl FnMut(BoardIndex<W, H>) -> T) -> Self {
+        Board(std::array::from_fn(|y| {
+            std::array::from_fn(|x| f(BoardIndex::from_xy(x, y)))
+        }))
+    }
+
+    pub fn into_rows(self) -> [[T; W]; H] {
+        self.0
+    }
+
+    // Cells in reading order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.as_flattened().iter()
+    }
+
+    pub fn enumerate_cells(&self) -> impl Iterator<Item = (BoardIndex<W, H>, &T)> {
+        BoardIndex::all_indices_within_bounds().zip(self.iter())
+    }
+
+    // Top to bottom, each row left to right.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.0.iter().map(|row| &row[..])
+    }
+
+    // Left to right, each column top to bottom.
+    pub fn columns(&self) -> impl Iterator<Item = [&T; H]> {
+        (0..W).map(|x| std::array::from_fn(|y| &self.0[y][x]))
+    }
+
+    pub fn set(&mut self, idx: BoardIndex<W, H>, value: T) {
+        self[idx] = value;
+    }
+
+    // Whether path could be swiped: at least one tile, every step to an
+    // adjacent tile, and no tile used twice.
+    pub fn is_traceable(&self, path: &[BoardIndex<W, H>]) -> bool {
+        let steps_adjacent = path.windows(2).all(|step| step[0].is_adjacent(step[1]));
+        let mask = BoardMask::from_indices(path.iter().copied());
+        !path.is_empty() && steps_adjacent && mask.count_set() as usize == path.len()
+    }
+}
+
+impl<T> Board4x4<T> {
+    // The cells in reading order.
+    pub fn into_inner(self) -> [T; 16] {
+        let mut cells = self.0.into_iter().flatten();
+        std::array::from_fn(|_| cells.next().unwrap())
+    }
+}
 
-impl<T: Copy> Board4x4<T> {
-    pub fn with_at(&self, value: T, idx: Index4x4) -> Self {
-        let mut arr = self.0.clone();
-        arr[idx.flattened] = value;
-        Board4x4::<T>(arr)
+impl<T: Copy, const W: usize, const H: usize> Board<T, W, H> {
+    pub fn filled(value: T) -> Self {
+        Board([[value; W]; H])
+    }
+
+    pub fn with_at(&self, value: T, idx: BoardIndex<W, H>) -> Self {
+        let mut board = *self;
+        board[idx] = value;
+        board
+    }
+}
+
+impl<T: Default, const W: usize, const H: usize> Default for Board<T, W, H> {
+    fn default() -> Self {
+        Board::from_fn(|_| T::default())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardParseError {
+    WrongLength { expected: usize, got: usize },
+    InvalidChar { ch: char, pos: usize },
+}
+
+impl fmt::Display for BoardParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardParseError::WrongLength { expected, got } => {
+                write!(f, "expected {} tiles, got {}", expected, got)
+            }
+            BoardParseError::InvalidChar { ch, pos } => {
+                write!(f, "invalid character '{}' at position {}", ch, pos)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardParseError {}
+
+// Tiles are lowercased to match the trie. A letter which lowercases
+// to more than one char can't be represented by a single tile.
+fn parse_letter(ch: char, pos: usize) -> Result<char, BoardParseError> {
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) if l.is_alphabetic() || l == WILDCARD => Ok(l),
+        _ => Err(BoardParseError::InvalidChar { ch, pos }),
+    }
+}
+
+// There's no From<&str> alongside, not even a deprecated one: the standard
+// library gives every From<&str> a TryFrom<&str> that can't fail, which
+// would clash with this one.
+impl<const W: usize, const H: usize> TryFrom<&str> for Board<Tile, W, H> {
+    type Error = BoardParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        // Whitespace between tiles is ignored, so boards can be entered row by row.
+        // Positions in errors count tiles, not including the whitespace.
+        // A digraph tile is written with its second letter in brackets: "q(u)".
+        let s = compose(s);
+        let mut chars = s.chars().filter(|ch| !ch.is_whitespace()).peekable();
+        let mut tiles: Vec<Tile> = vec![];
+        while let Some(ch) = chars.next() {
+            let pos = tiles.len();
+            let first = parse_letter(ch, pos)?;
+            if chars.next_if_eq(&'(').is_none() {
+                tiles.push(Tile::from(first));
+                continue;
+            }
+            let second = match chars.next() {
+                Some(ch) if ch != WILDCARD => parse_letter(ch, pos)?,
+                Some(ch) => return Err(BoardParseError::InvalidChar { ch, pos }),
+                None => return Err(BoardParseError::InvalidChar { ch: '(', pos }),
+            };
+            match chars.next() {
+                Some(')') if first != WILDCARD => tiles.push(Tile::Digraph(first, second)),
+                Some(ch) => return Err(BoardParseError::InvalidChar { ch, pos }),
+                None => return Err(BoardParseError::InvalidChar { ch: '(', pos }),
+            }
+        }
+        if tiles.len() != W * H {
+            return Err(BoardParseError::WrongLength {
+                expected: W * H,
+                got: tiles.len(),
+            });
+        }
+        Ok(Board::from_fn(|idx| tiles[idx.flattened]))
+    }
+}
+
+impl<const W: usize, const H: usize> FromStr for Board<Tile, W, H> {
+    type Err = BoardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
     }
 }
 
-impl From<&str> for Board4x4<char> {
-    fn from(s: &str) -> Self {
-        let chars: Vec<char> = s.chars().collect();
-        let arr: [char; 16] = chars.try_into().unwrap();
-        Board4x4(arr)
+impl<'a, T, const W: usize, const H: usize> IntoIterator for &'a Board<T, W, H> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl From<u16> for Board4x4<bool> {
-    fn from(u: u16) -> Self {
-        // Bit order: most significant bit  => top left
-        //            least significant bit => bottom right
-        let arr: [bool; 16] = std::array::from_fn(|n| ((u >> (15 - n)) & 1) != 0);
-        Board4x4(arr)
+impl<T, const W: usize, const H: usize> From<[[T; W]; H]> for Board<T, W, H> {
+    fn from(rows: [[T; W]; H]) -> Self {
+        Board(rows)
     }
 }
 
-impl<T> Index<Index4x4> for Board4x4<T> {
+impl<T> From<[T; 16]> for Board4x4<T> {
+    fn from(arr: [T; 16]) -> Self {
+        let mut cells = arr.into_iter();
+        Board::from_fn(|_| cells.next().unwrap())
+    }
+}
+
+impl<T, const W: usize, const H: usize> Index<BoardIndex<W, H>> for Board<T, W, H> {
     // 2D ("Grid") indexing.
     type Output = T;
 
-    fn index(&self, idx: Index4x4) -> &Self::Output {
-        &self.0[idx.flattened]
+    fn index(&self, idx: BoardIndex<W, H>) -> &Self::Output {
+        let (x, y) = idx.to_xy();
+        &self.0[y][x]
+    }
+}
+
+impl<T, const W: usize, const H: usize> IndexMut<BoardIndex<W, H>> for Board<T, W, H> {
+    fn index_mut(&mut self, idx: BoardIndex<W, H>) -> &mut Self::Output {
+        let (x, y) = idx.to_xy();
+        &mut self.0[y][x]
     }
 }
 
-impl<T> Index<usize> for Board4x4<T> {
+impl<T, const W: usize, const H: usize> Index<usize> for Board<T, W, H> {
     // 1D ("Flat") indexing.
     type Output = T;
 
     fn index(&self, idx: usize) -> &Self::Output {
-        &self.0[idx]
+        &self.0.as_flattened()[idx]
+    }
+}
+
+impl<T, const W: usize, const H: usize> IndexMut<usize> for Board<T, W, H> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.0.as_flattened_mut()[idx]
     }
 }
 
-impl<T: fmt::Display> fmt::Display for Board4x4<T> {
+impl<T: fmt::Display, const W: usize, const H: usize> fmt::Display for Board<T, W, H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in self.0.chunks(4) {
+        for row in self.rows() {
             for tile in row {
                 write!(f, "{}", tile)?;
             }
@@ -117,8 +418,78 @@ impl<T: fmt::Display> fmt::Display for Board4x4<T> {
     }
 }
 
-pub type RuzzleBoard = Board4x4<char>;
-pub type BoardMask = Board4x4<bool>;
+// As an array of rows, top to bottom.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const W: usize, const H: usize> serde::Serialize for Board<T, W, H> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+pub type RuzzleBoard = Board4x4<Tile>;
+
+impl<const W: usize, const H: usize> Board<Tile, W, H> {
+    // The letters along path, with '?' for wildcards.
+    pub fn spell(&self, path: &Path<W, H>) -> String {
+        path.iter().flat_map(|&idx| self[idx].letters()).collect()
+    }
+
+    // Whether path is traceable and spells word, with wildcards standing in
+    // for any letter. For checking solutions that didn't come from the solver.
+    pub fn is_valid_path(&self, path: &Path<W, H>, word: &str) -> bool {
+        let word = normalize(word);
+        let mut chars = word.chars();
+        let spells = path.iter().all(|&idx| match self[idx] {
+            Tile::Wildcard => chars.next().is_some(),
+            tile => tile.letters().all(|ch| chars.next() == Some(ch)),
+        });
+        self.is_traceable(path) && spells && chars.next().is_none()
+    }
+
+    // Every distinct path spelling word, without needing a trie.
+    pub fn find_paths_for_word(&self, word: &str) -> Vec<Path<W, H>> {
+        let chars: Vec<char> = normalize(word).chars().collect();
+        let mut out = vec![];
+        if chars.is_empty() {
+            return out;
+        }
+        let mut path = vec![];
+        for idx in BoardIndex::all_indices_within_bounds() {
+            self.trace(&chars, BoardMask::default(), idx, &mut path, &mut out);
+        }
+        out
+    }
+
+    fn trace(
+        &self,
+        chars: &[char],
+        visited: BoardMask<W, H>,
+        idx: BoardIndex<W, H>,
+        path: &mut Path<W, H>,
+        out: &mut Vec<Path<W, H>>,
+    ) {
+        let rest = match self[idx] {
+            Tile::Wildcard => &chars[1..],
+            tile => {
+                let len = tile.letter_count();
+                if chars.len() < len || !tile.letters().eq(chars[..len].iter().copied()) {
+                    return;
+                }
+                &chars[len..]
+            }
+        };
+        path.push(idx);
+        if rest.is_empty() {
+            out.push(path.clone());
+        } else {
+            let new_visited = visited.with_at(true, idx);
+            for n_idx in idx.get_neighbouring().filter(|&n| !new_visited.get(n)) {
+                self.trace(rest, new_visited, n_idx, path, out);
+            }
+        }
+        path.pop();
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -127,8 +498,8 @@ mod tests {
     #[test]
     fn board_from_string() {
         let alphabet = "abcdefghijklmnop";
-        let board = RuzzleBoard::from(alphabet);
-        let mut idxs = (0..=15).map(|n| Index4x4 { flattened: n });
+        let board = RuzzleBoard::try_from(alphabet).unwrap();
+        let mut idxs = (0..=15).map(|n| Index4x4::try_from_flat(n).unwrap());
         assert_eq!(board[idxs.next().unwrap()], 'a');
         assert_eq!(board[idxs.next().unwrap()], 'b');
         assert_eq!(board[idxs.next().unwrap()], 'c');
@@ -201,4 +572,432 @@ mod tests {
         );
         assert_eq!(tl_neighbours.next(), None);
     }
+
+    #[test]
+    fn board_parse_errors() {
+        assert_eq!(
+            RuzzleBoard::try_from("abcdefghijklmno"),
+            Err(BoardParseError::WrongLength {
+                expected: 16,
+                got: 15
+            })
+        );
+        assert_eq!(
+            RuzzleBoard::try_from("abcdefghijklmnopq"),
+            Err(BoardParseError::WrongLength {
+                expected: 16,
+                got: 17
+            })
+        );
+        assert_eq!(
+            "abcdefg8ijklmnop".parse::<RuzzleBoard>(),
+            Err(BoardParseError::InvalidChar { ch: '8', pos: 7 })
+        );
+        assert_eq!(
+            "abcdefghijklmnop".parse::<RuzzleBoard>(),
+            RuzzleBoard::try_from("abcdefghijklmnop")
+        );
+        assert!("abcdefghijklmnop".parse::<RuzzleBoard>().is_ok());
+    }
+
+    #[test]
+    fn board_from_string_with_whitespace() {
+        let flat = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        assert_eq!(RuzzleBoard::try_from("abcd\nefgh\nijkl\nmnop\n"), Ok(flat));
+        assert_eq!(
+            RuzzleBoard::try_from("a b c d e f g h i j k l m n o p"),
+            Ok(flat)
+        );
+        assert_eq!(
+            RuzzleBoard::try_from("  ab cd\r\n\tef gh\nijkl  mn\top"),
+            Ok(flat)
+        );
+
+        assert_eq!(
+            RuzzleBoard::try_from("abcd\nefgh\nijkl\nmnopq"),
+            Err(BoardParseError::WrongLength {
+                expected: 16,
+                got: 17
+            })
+        );
+        assert_eq!(
+            RuzzleBoard::try_from("abcd efgh ijkl mn0p"),
+            Err(BoardParseError::InvalidChar { ch: '0', pos: 14 })
+        );
+    }
+
+    #[test]
+    fn board_from_string_is_lowercased() {
+        let lower = RuzzleBoard::try_from("abcdefghijklmnåp").unwrap();
+        assert_eq!(RuzzleBoard::try_from("ABCDefghIJKLmnÅp"), Ok(lower));
+        assert_eq!(
+            RuzzleBoard::try_from("abcdefghijklmnoİ"),
+            Err(BoardParseError::InvalidChar { ch: 'İ', pos: 15 })
+        );
+    }
+
+    #[test]
+    fn board_from_string_is_composed() {
+        let composed = RuzzleBoard::try_from("båtxxxxxxxxxxxxx");
+        let decomposed = RuzzleBoard::try_from("ba\u{30a}txxxxxxxxxxxxx");
+        #[cfg(feature = "unicode")]
+        assert_eq!(decomposed, composed);
+        #[cfg(not(feature = "unicode"))]
+        {
+            assert!(composed.is_ok());
+            assert_eq!(
+                decomposed,
+                Err(BoardParseError::InvalidChar {
+                    ch: '\u{30a}',
+                    pos: 2
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn find_paths_for_word() {
+        let xy = |x, y| Index4x4::from_xy(x, y);
+        // t e s t
+        // x x t x
+        // x x x x
+        // x x x x
+        let board = RuzzleBoard::try_from("testxxtxxxxxxxxx").unwrap();
+        assert_eq!(
+            board.find_paths_for_word("test"),
+            vec![
+                vec![xy(0, 0), xy(1, 0), xy(2, 0), xy(3, 0)],
+                vec![xy(0, 0), xy(1, 0), xy(2, 0), xy(2, 1)],
+                vec![xy(2, 1), xy(1, 0), xy(2, 0), xy(3, 0)],
+            ]
+        );
+        assert_eq!(board.find_paths_for_word("TEST").len(), 3);
+        // The only 's' can't be used twice.
+        assert!(board.find_paths_for_word("tests").is_empty());
+        assert!(board.find_paths_for_word("").is_empty());
+    }
+
+    #[test]
+    fn find_paths_for_word_not_adjacent() {
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        assert!(board.find_paths_for_word("ap").is_empty());
+        assert_eq!(board.find_paths_for_word("fin").len(), 1);
+    }
+
+    #[test]
+    fn wildcard_tiles() {
+        let board = RuzzleBoard::try_from("?bcdefghijklmno?").unwrap();
+        assert_eq!(board[0], Tile::Wildcard);
+        assert_eq!(board[15], Tile::Wildcard);
+        assert_eq!(board.find_paths_for_word("ab").len(), 1);
+        assert_eq!(board.find_paths_for_word("zb").len(), 1);
+        assert_eq!(board.find_paths_for_word("kz").len(), 1);
+        assert!(board.find_paths_for_word("zz").is_empty());
+    }
+
+    #[test]
+    fn digraph_tiles() {
+        let board = RuzzleBoard::try_from("q(u)een xxxx xxxx xxxx").unwrap();
+        assert_eq!(board[0], Tile::QU);
+        assert_eq!(board[1], 'e');
+        assert_eq!(board.to_string().lines().next(), Some("queen"));
+        assert_eq!(RuzzleBoard::try_from("Q(U)eenxxxxxxxxxxxx"), Ok(board));
+        assert_eq!(
+            board.spell(&vec![Index4x4::from_xy(0, 0), Index4x4::from_xy(1, 0)]),
+            "que"
+        );
+
+        assert_eq!(
+            RuzzleBoard::try_from("q(u"),
+            Err(BoardParseError::InvalidChar { ch: '(', pos: 0 })
+        );
+        assert_eq!(
+            RuzzleBoard::try_from("ab(cd)efghijklmnop"),
+            Err(BoardParseError::InvalidChar { ch: 'd', pos: 1 })
+        );
+        assert_eq!(
+            RuzzleBoard::try_from("a?(c)"),
+            Err(BoardParseError::InvalidChar { ch: ')', pos: 1 })
+        );
+    }
+
+    #[test]
+    fn find_paths_across_digraph() {
+        let board = RuzzleBoard::try_from("q(u)eenxxxxxxxxxxxx").unwrap();
+        assert_eq!(board.find_paths_for_word("queen").len(), 1);
+        assert!(board.find_paths_for_word("qeen").is_empty());
+        assert!(board.find_paths_for_word("q").is_empty());
+    }
+
+    #[test]
+    fn mutate_in_place() {
+        let mut board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        board[Index4x4::from_xy(1, 0)] = Tile::Letter('x');
+        board[4] = Tile::QU;
+        board.set(Index4x4::from_xy(3, 3), Tile::Wildcard);
+        assert_eq!(board[1], Tile::Letter('x'));
+        assert_eq!(board[Index4x4::from_xy(0, 1)], Tile::QU);
+        assert_eq!(board.to_string(), "axcd\nqufgh\nijkl\nmno?\n");
+        assert_eq!(
+            board,
+            RuzzleBoard::try_from("axcd q(u)fgh ijkl mno?").unwrap()
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(Board4x4::filled(7u32).into_inner(), [7; 16]);
+        let flat = Board4x4::from_fn(|idx| idx.flat());
+        assert_eq!(flat.into_inner(), std::array::from_fn(|n| n));
+        let xs = Board4x4::from_fn(|idx| idx.to_xy().0);
+        assert_eq!(xs[Index4x4::from_xy(2, 3)], 2);
+        assert_eq!(Board4x4::from([1u8; 16]), Board4x4::filled(1));
+
+        // How many times each tile is used by the words found on a board.
+        let mut root = crate::trie::TrieNode::new_root();
+        for word in ["ab", "fab", "abc"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let mut usage: Board4x4<u32> = Board4x4::filled(0);
+        for result in crate::solve(&root, &board, &crate::SolveOptions::default()) {
+            for idx in result.path {
+                usage[idx] += 1;
+            }
+        }
+        assert_eq!(usage[Index4x4::from_xy(0, 0)], 3);
+        assert_eq!(usage[Index4x4::from_xy(1, 0)], 3);
+        assert_eq!(usage[Index4x4::from_xy(2, 0)], 1);
+        assert_eq!(usage[Index4x4::from_xy(1, 1)], 1);
+        assert_eq!(usage.into_inner().iter().sum::<u32>(), 2 + 3 + 3);
+    }
+
+    #[test]
+    fn iterators() {
+        let board = Board4x4::from_fn(|idx| idx.to_xy());
+        assert!(
+            board
+                .iter()
+                .copied()
+                .eq(Index4x4::all_indices_within_bounds().map(|idx| idx.to_xy()))
+        );
+        assert_eq!((&board).into_iter().count(), 16);
+
+        let cells: Vec<(Index4x4, &(usize, usize))> = board.enumerate_cells().collect();
+        assert_eq!(cells.len(), 16);
+        assert_eq!(
+            cells
+                .iter()
+                .map(|&(idx, _)| idx)
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            16
+        );
+        for (idx, &xy) in cells {
+            assert_eq!(idx.to_xy(), xy);
+        }
+
+        for (y, row) in board.rows().enumerate() {
+            assert_eq!(row, [(0, y), (1, y), (2, y), (3, y)]);
+        }
+        assert_eq!(board.rows().count(), 4);
+        for (x, column) in board.columns().enumerate() {
+            assert_eq!(column, [&(x, 0), &(x, 1), &(x, 2), &(x, 3)]);
+        }
+        assert_eq!(board.columns().count(), 4);
+    }
+
+    #[test]
+    fn checked_indices() {
+        assert_eq!(Index4x4::try_from_xy(3, 3), Some(Index4x4::from_xy(3, 3)));
+        assert_eq!(Index4x4::try_from_xy(4, 0), None);
+        assert_eq!(Index4x4::try_from_xy(0, 4), None);
+        assert_eq!(Index4x4::try_from_xy(3, 4), None);
+        assert_eq!(Index4x4::try_from_xy(4, 3), None);
+        assert_eq!(Index4x4::try_from_xy(0, 3).map(|idx| idx.flat()), Some(12));
+
+        assert_eq!(Index4x4::try_from_flat(15), Some(Index4x4::from_xy(3, 3)));
+        assert_eq!(Index4x4::try_from_flat(16), None);
+        assert_eq!(
+            BoardIndex::<3, 2>::try_from_flat(5).map(|idx| idx.to_xy()),
+            Some((2, 1))
+        );
+        assert_eq!(BoardIndex::<3, 2>::try_from_flat(6), None);
+        assert_eq!(BoardIndex::<3, 2>::try_from_xy(2, 2), None);
+        for idx in Index4x4::all_indices_within_bounds() {
+            assert_eq!(Index4x4::try_from_flat(idx.flat()), Some(idx));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "board index out of bounds")]
+    #[cfg(debug_assertions)]
+    fn from_xy_out_of_bounds() {
+        Index4x4::from_xy(5, 0);
+    }
+
+    #[test]
+    fn neighbours_by_direction() {
+        let top_left = Index4x4::from_xy(0, 0);
+        for dir in [
+            Direction::N,
+            Direction::NE,
+            Direction::W,
+            Direction::NW,
+            Direction::SW,
+        ] {
+            assert_eq!(top_left.neighbour(dir), None);
+        }
+        assert_eq!(
+            top_left.neighbour(Direction::E),
+            Some(Index4x4::from_xy(1, 0))
+        );
+        assert_eq!(
+            top_left.neighbour(Direction::SE),
+            Some(Index4x4::from_xy(1, 1))
+        );
+        assert_eq!(
+            top_left.neighbour(Direction::S),
+            Some(Index4x4::from_xy(0, 1))
+        );
+
+        let bottom_right = Index4x4::from_xy(3, 3);
+        for dir in [
+            Direction::NE,
+            Direction::E,
+            Direction::SE,
+            Direction::S,
+            Direction::SW,
+        ] {
+            assert_eq!(bottom_right.neighbour(dir), None);
+        }
+        assert_eq!(
+            bottom_right.neighbour(Direction::NW),
+            Some(Index4x4::from_xy(2, 2))
+        );
+
+        // Every direction from the middle agrees with get_neighbouring().
+        let middle = Index4x4::from_xy(1, 2);
+        let mut by_dir: Vec<Index4x4> = Direction::ALL
+            .into_iter()
+            .filter_map(|dir| middle.neighbour(dir))
+            .collect();
+        by_dir.sort();
+        assert_eq!(by_dir, middle.get_neighbouring().collect::<Vec<_>>());
+        for n in middle.get_neighbouring() {
+            let dir = Direction::between(middle, n).unwrap();
+            assert_eq!(middle.neighbour(dir), Some(n));
+        }
+        assert_eq!(Direction::between(middle, middle), None);
+        assert_eq!(Direction::between(middle, Index4x4::from_xy(3, 2)), None);
+    }
+
+    #[test]
+    fn path_directions_round_trip() {
+        let path: Path = [(0, 0), (1, 0), (2, 1), (2, 2), (1, 3), (0, 2)]
+            .into_iter()
+            .map(|(x, y)| Index4x4::from_xy(x, y))
+            .collect();
+        let (start, dirs) = path_to_directions(&path).unwrap();
+        assert_eq!(start, path[0]);
+        assert_eq!(
+            dirs,
+            [
+                Direction::E,
+                Direction::SE,
+                Direction::S,
+                Direction::SW,
+                Direction::NW
+            ]
+        );
+        assert_eq!(path_from_directions(start, &dirs), Some(path));
+
+        assert_eq!(path_to_directions::<4, 4>(&[]), None);
+        let gap = [Index4x4::from_xy(0, 0), Index4x4::from_xy(2, 0)];
+        assert_eq!(path_to_directions(&gap), None);
+        assert_eq!(
+            path_from_directions(Index4x4::from_xy(3, 0), &[Direction::E]),
+            None
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let corner = Index4x4::from_xy(0, 0);
+        let edge = Index4x4::from_xy(0, 2);
+        let center = Index4x4::from_xy(1, 1);
+        let far = Index4x4::from_xy(3, 3);
+
+        assert!(corner.is_adjacent(center));
+        assert!(center.is_adjacent(corner));
+        assert!(edge.is_adjacent(center));
+        assert!(!corner.is_adjacent(edge));
+        assert!(!corner.is_adjacent(corner));
+        assert!(!center.is_adjacent(far));
+
+        assert_eq!(corner.chebyshev_distance(corner), 0);
+        assert_eq!(corner.chebyshev_distance(center), 1);
+        assert_eq!(corner.chebyshev_distance(far), 3);
+        assert_eq!(edge.chebyshev_distance(far), 3);
+        assert_eq!(corner.manhattan_distance(center), 2);
+        assert_eq!(corner.manhattan_distance(far), 6);
+        assert_eq!(edge.manhattan_distance(center), 2);
+
+        for idx in Index4x4::all_indices_within_bounds() {
+            for other in Index4x4::all_indices_within_bounds() {
+                let neighbours = idx.get_neighbouring().any(|n| n == other);
+                assert_eq!(idx.is_adjacent(other), neighbours);
+            }
+        }
+    }
+
+    #[test]
+    fn traceable_paths() {
+        let board = RuzzleBoard::try_from("testxxtxxxxxxxxx").unwrap();
+        for path in board.find_paths_for_word("test") {
+            assert!(board.is_traceable(&path));
+        }
+        let xy = |coords: &[(usize, usize)]| -> Path {
+            coords
+                .iter()
+                .map(|&(x, y)| Index4x4::from_xy(x, y))
+                .collect()
+        };
+        assert!(board.is_traceable(&xy(&[(2, 2)])));
+        assert!(board.is_traceable(&xy(&[(0, 0), (1, 1), (2, 0)])));
+        assert!(!board.is_traceable(&[]));
+        assert!(!board.is_traceable(&xy(&[(0, 0), (2, 0)])));
+        assert!(!board.is_traceable(&xy(&[(0, 0), (1, 0), (0, 0)])));
+        assert!(!board.is_traceable(&xy(&[(0, 0), (0, 0)])));
+    }
+
+    #[test]
+    fn valid_paths() {
+        let board = RuzzleBoard::try_from("testxxtxxq(u)?xxxxx").unwrap();
+        let xy = |coords: &[(usize, usize)]| -> Path {
+            coords
+                .iter()
+                .map(|&(x, y)| Index4x4::from_xy(x, y))
+                .collect()
+        };
+        assert!(board.is_valid_path(&xy(&[(0, 0), (1, 0), (2, 0), (3, 0)]), "test"));
+        assert!(board.is_valid_path(&xy(&[(0, 0), (1, 0), (2, 0), (2, 1)]), "TEST"));
+        for path in board.find_paths_for_word("test") {
+            assert!(board.is_valid_path(&path, "test"));
+        }
+        // Digraphs and wildcards.
+        assert!(board.is_valid_path(&xy(&[(1, 2), (2, 2), (2, 1)]), "quat"));
+        assert!(!board.is_valid_path(&xy(&[(1, 2), (2, 2)]), "qa"));
+
+        // Repeated tile.
+        assert!(!board.is_valid_path(&xy(&[(0, 0), (1, 0), (0, 0)]), "tet"));
+        // Non-adjacent jump.
+        assert!(!board.is_valid_path(&xy(&[(0, 0), (1, 0), (3, 0)]), "tet"));
+        // Letter mismatch, and words too long or short for the path.
+        assert!(!board.is_valid_path(&xy(&[(0, 0), (1, 0), (2, 0)]), "tex"));
+        assert!(!board.is_valid_path(&xy(&[(0, 0), (1, 0), (2, 0)]), "test"));
+        assert!(!board.is_valid_path(&xy(&[(0, 0), (1, 0), (2, 0)]), "te"));
+        // Empty path.
+        assert!(!board.is_valid_path(&vec![], ""));
+    }
 }
@@ -0,0 +1,2230 @@
+use crate::config::{self, ConfigError};
+use crate::{practice, repl};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ruzzle_solver::anagram::solve_anagram;
+use ruzzle_solver::analyze::{MissedReport, analyze};
+use ruzzle_solver::board::{Board, BoardParseError};
+use ruzzle_solver::disjoint::select_disjoint_best;
+use ruzzle_solver::heatmap::{render_heatmap, tile_heatmap, tile_score_heatmap};
+use ruzzle_solver::render::{render_masks, render_path, render_path_ansi};
+use ruzzle_solver::score::{BonusBoard, Language, LetterValues, Scoring};
+use ruzzle_solver::serialize::{MAGIC, Source};
+use ruzzle_solver::suggest::suggest_changes;
+use ruzzle_solver::tile::Tile;
+use ruzzle_solver::trie::{
+    LoadOptions, LoadProgress, MemoryReport, TrieNode, WordFilter, normalize, open_word_file,
+};
+use ruzzle_solver::{SolveOptions, SolveResult, SolveSummary, Sort, count_words, solve_best_words};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+#[cfg(feature = "rand")]
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Finds every word on a Ruzzle board",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // Only optional for the sake of the subcommands, --boards, --interactive
+    // and --anagram; clap requires a board and a dictionary to solve anything.
+    /// The letters of the board, row by row
+    #[arg(
+        required_unless_present_any = ["boards", "interactive", "anagram"],
+        conflicts_with = "boards"
+    )]
+    pub board: Option<String>,
+
+    /// Where the board's bonus tiles are, a character per tile in the same
+    /// order as its letters: '.' for none, d or t for a double or triple
+    /// letter, D or T for a double or triple word, e.g. "....d......T...."
+    #[arg(long, value_name = "BONUSES", conflicts_with_all = ["boards", "interactive", "anagram"])]
+    pub bonuses: Option<String>,
+
+    /// Solve every board in FILE, one per line, and print a summary line
+    /// (board, unique words, total score) for each, or with --format jsonl
+    /// a line of JSON
+    #[arg(long, value_name = "FILE")]
+    pub boards: Option<PathBuf>,
+
+    /// Load the dictionary, then prompt for boards to solve until :quit.
+    /// Type :help at the prompt for the other commands
+    #[arg(long, conflicts_with_all = ["board", "boards"])]
+    pub interactive: bool,
+
+    /// Show the board and score the words you find in it, then the best
+    /// ones you missed
+    #[arg(long, conflicts_with_all = ["boards", "interactive"])]
+    pub practice: bool,
+
+    /// How long --practice lasts
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 120,
+        requires = "practice"
+    )]
+    pub time: u64,
+
+    /// Newline separated word list, or one compiled with compile-dict.
+    /// Given more than once, the words of all of them are used. If not
+    /// given, those of $RUZZLE_DICT, or else the dictionary key of
+    /// ~/.config/ruzzle-solver/config.toml, or else with the embedded-dict
+    /// feature a list of common words
+    #[arg(long)]
+    pub dict: Vec<PathBuf>,
+
+    /// Words not to report, one per line, even if a --dict has them
+    #[arg(long, value_name = "FILE")]
+    pub exclude_dict: Vec<PathBuf>,
+
+    /// 4x4 for Ruzzle, 5x5 for Boggle scoring and a 3 letter minimum
+    #[arg(long, default_value_t = BoardSize::Ruzzle)]
+    pub size: BoardSize,
+
+    /// Score letters as the tiles of this language do: en, sv, it or de
+    /// [default: en]
+    #[arg(long, value_name = "CODE", value_parser = parse_language)]
+    pub language: Option<Language>,
+
+    /// Score letters as FILE says instead, in `a = 1` lines or as a JSON
+    /// object. Letters it leaves out score its "default" key, or 0
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_parser = read_letter_values,
+        conflicts_with = "language"
+    )]
+    pub letter_values: Option<LetterValues>,
+
+    /// Only report words of at least N letters [default: 2, or 3 for 5x5]
+    #[arg(long, value_name = "N")]
+    pub min_len: Option<usize>,
+
+    /// Only report the N highest scoring words
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Draw the paths of the N best words on the board
+    #[arg(long, value_name = "N")]
+    pub show_paths: Option<usize>,
+
+    /// Show how the scores of the N best words add up: letter values,
+    /// bonuses and length bonus
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["show_paths", "words_only", "group_by_length", "heatmap", "disjoint"]
+    )]
+    pub explain: Option<usize>,
+
+    /// Print just the words, one per line in alphabetical order
+    #[arg(long, conflicts_with_all = ["show_paths", "group_by_length"])]
+    pub words_only: bool,
+
+    /// Print the words under a heading for each length, longest first
+    #[arg(long, conflicts_with = "show_paths")]
+    pub group_by_length: bool,
+
+    /// Instead of the words, show how many of them use each tile, and how
+    /// many points they are worth
+    #[arg(long, conflicts_with_all = ["show_paths", "words_only", "group_by_length"])]
+    pub heatmap: bool,
+
+    /// Instead of all the words, show the set of them with the highest
+    /// combined score whose paths share no tile
+    #[arg(
+        long,
+        conflicts_with_all = ["show_paths", "words_only", "group_by_length", "heatmap"]
+    )]
+    pub disjoint: bool,
+
+    /// Instead of the words, show the N changes of one tile to another
+    /// letter which raise the board's total score most
+    #[arg(long, value_name = "N", conflicts_with_all = ["boards", "interactive", "practice"])]
+    pub suggest: Option<usize>,
+
+    /// Let --suggest swap two tiles as well
+    #[arg(long, requires = "suggest")]
+    pub swaps: bool,
+
+    /// Compare the words in FILE, one per line, with the solution: which
+    /// are wrong and why, and the best words missed (see --top)
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["boards", "interactive", "practice", "suggest"]
+    )]
+    pub found_words: Option<PathBuf>,
+
+    /// Instead of solving a board, list the words that can be made from
+    /// LETTERS in any order, each letter used at most as often as given
+    #[arg(
+        long,
+        value_name = "LETTERS",
+        conflicts_with_all = ["board", "boards", "interactive", "practice", "suggest", "found_words"]
+    )]
+    pub anagram: Option<String>,
+
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Indent the JSON output
+    #[arg(long)]
+    pub pretty: bool,
+
+    /// Print the JSON Schema of the JSON output and exit
+    #[cfg(feature = "serde")]
+    #[arg(long, exclusive = true)]
+    pub schema: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Build the trie for a word list once and save it, for faster startup
+    CompileDict(CompileArgs),
+    /// Count the words of a dictionary, by length
+    DictStats(DictStatsArgs),
+    /// List the words one dictionary adds to or removes from another
+    DictDiff(DictDiffArgs),
+    /// Print random boards with English letter frequencies
+    #[cfg(feature = "rand")]
+    Generate(GenerateArgs),
+    /// Search for the board with the highest total score
+    #[cfg(feature = "rand")]
+    Optimize(OptimizeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CompileArgs {
+    /// Newline separated word list
+    pub words: PathBuf,
+
+    /// Where to write the compiled dictionary [default: WORDS with a .rzd extension]
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct DictStatsArgs {
+    /// Word lists or compiled dictionaries, merged as for solving
+    #[arg(long, required = true)]
+    pub dict: Vec<PathBuf>,
+
+    /// Words to leave out, one per line
+    #[arg(long, value_name = "FILE")]
+    pub exclude_dict: Vec<PathBuf>,
+
+    /// Also write the trie as a Graphviz graph
+    #[arg(long, value_name = "FILE")]
+    pub export_dot: Option<PathBuf>,
+
+    /// Trie nodes to draw at most in the graph
+    #[arg(long, default_value_t = 1000, requires = "export_dot")]
+    pub dot_max_nodes: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DictDiffArgs {
+    /// Word list or compiled dictionary to compare with
+    pub old: PathBuf,
+
+    /// Word list or compiled dictionary to compare
+    pub new: PathBuf,
+
+    /// How many of the added and removed words to list
+    #[arg(long, default_value_t = 10)]
+    pub samples: usize,
+}
+
+#[cfg(feature = "rand")]
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// How many boards to print
+    #[arg(long, short = 'n', default_value_t = 1)]
+    pub count: usize,
+
+    /// Print the same boards every time for the same seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Follow each board with its unique words and total score, as --boards
+    /// would print them, using these word lists
+    #[arg(long)]
+    pub dict: Vec<PathBuf>,
+
+    /// 4x4 for Ruzzle, 5x5 for Boggle
+    #[arg(long, default_value_t = BoardSize::Ruzzle)]
+    pub size: BoardSize,
+}
+
+#[cfg(feature = "rand")]
+#[derive(Debug, Args)]
+pub struct OptimizeArgs {
+    /// Word lists to score boards with
+    #[arg(long, required = true)]
+    pub dict: Vec<PathBuf>,
+
+    /// 4x4 for Ruzzle, 5x5 for Boggle
+    #[arg(long, default_value_t = BoardSize::Ruzzle)]
+    pub size: BoardSize,
+
+    /// Stop after N changes to the board [default: 10000]
+    #[arg(long, value_name = "N", conflicts_with = "seconds")]
+    pub iterations: Option<usize>,
+
+    /// Stop after S seconds instead
+    #[arg(long, value_name = "S", value_parser = parse_seconds)]
+    pub seconds: Option<Duration>,
+
+    /// Print the best board so far to stderr every K iterations
+    #[arg(
+        long,
+        value_name = "K",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub progress: Option<usize>,
+
+    /// Start from the same board and make the same changes every time,
+    /// given the same --iterations
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    #[default]
+    Text, // One "score word" line per word.
+    Csv, // See write_csv().
+    #[cfg(feature = "serde")]
+    Json, // See JsonOutput.
+    #[cfg(feature = "serde")]
+    Jsonl, // JsonOutput on a single line, which for --boards means one line per board.
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BoardSize {
+    #[default]
+    Ruzzle, // 4x4
+    Boggle, // 5x5
+}
+
+impl fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardSize::Ruzzle => write!(f, "4x4"),
+            BoardSize::Boggle => write!(f, "5x5"),
+        }
+    }
+}
+
+impl FromStr for BoardSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "4x4" => Ok(BoardSize::Ruzzle),
+            "5x5" => Ok(BoardSize::Boggle),
+            _ => Err(format!(
+                "unsupported board size {:?}, expected 4x4 or 5x5",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Board(BoardParseError),
+    Bonuses(BoardParseError),
+    Dictionary { path: PathBuf, source: io::Error },
+    Compile { path: PathBuf, source: io::Error },
+    Boards { path: PathBuf, source: io::Error },
+    BoardsFormat(Format),
+    FoundWords { path: PathBuf, source: io::Error },
+    Config(ConfigError),
+    Input(io::Error),
+    Output(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Board(err) => write!(f, "invalid board: {}", err),
+            CliError::Bonuses(err) => write!(f, "invalid bonuses: {}", err),
+            CliError::Dictionary { path, source } => {
+                write!(
+                    f,
+                    "could not read dictionary {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            CliError::Compile { path, source } => {
+                write!(f, "could not write {}: {}", path.display(), source)
+            }
+            CliError::Boards { path, source } => {
+                write!(f, "could not read boards {}: {}", path.display(), source)
+            }
+            CliError::BoardsFormat(format) => {
+                let name = format.to_possible_value().unwrap();
+                write!(
+                    f,
+                    "--format {} can't be used with --boards",
+                    name.get_name()
+                )
+            }
+            CliError::FoundWords { path, source } => {
+                write!(
+                    f,
+                    "could not read found words {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            CliError::Config(err) => write!(f, "{}", err),
+            CliError::Input(err) => write!(f, "could not read input: {}", err),
+            CliError::Output(err) => write!(f, "could not write results: {}", err),
+        }
+    }
+}
+
+fn parse_language(code: &str) -> Result<Language, String> {
+    Language::from_code(code).ok_or_else(|| {
+        let codes: Vec<&str> = Language::ALL
+            .iter()
+            .map(|language| language.code())
+            .collect();
+        format!("unknown language {:?}, expected {}", code, codes.join(", "))
+    })
+}
+
+#[cfg(feature = "rand")]
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    let seconds: f64 = seconds
+        .parse()
+        .map_err(|_| format!("invalid number {:?}", seconds))?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("{} is not a number of seconds", seconds))
+}
+
+fn read_letter_values(path: &str) -> Result<LetterValues, String> {
+    let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    LetterValues::parse(&text).map_err(|(line, message)| format!("{}:{}: {}", path, line, message))
+}
+
+// Letters on the board without a value score the table's default, which is
+// likely not what was meant.
+fn warn_unknown_letters<const W: usize, const H: usize>(
+    opts: &SolveOptions<W, H>,
+    board: &Board<Tile, W, H>,
+) {
+    let unknown = opts.letter_values.unknown_letters(board);
+    if opts.scoring == Scoring::Ruzzle && !unknown.is_empty() {
+        let letters: Vec<String> = unknown.iter().map(char::to_string).collect();
+        eprintln!(
+            "warning: no value for {}, scoring {} instead",
+            letters.join(", "),
+            opts.letter_values.default
+        );
+    }
+}
+
+pub fn parse_board<const W: usize, const H: usize>(s: &str) -> Result<Board<Tile, W, H>, CliError> {
+    s.parse().map_err(CliError::Board)
+}
+
+// The --bonuses board, or no bonuses without it.
+pub fn parse_bonuses<const W: usize, const H: usize>(
+    cli: &Cli,
+) -> Result<BonusBoard<W, H>, CliError> {
+    match &cli.bonuses {
+        Some(s) => s.parse().map_err(CliError::Bonuses),
+        None => Ok(BonusBoard::default()),
+    }
+}
+
+// Fills in cli.dict if it wasn't given, from the environment or the config
+// file. Subcommands take their own dictionaries.
+pub fn resolve_dictionaries(cli: &mut Cli) -> Result<(), CliError> {
+    #[cfg(feature = "serde")]
+    if cli.schema {
+        return Ok(());
+    }
+    if cli.command.is_none() {
+        cli.dict =
+            config::resolve_dictionaries(&cli.dict, &|name| std::env::var_os(name), &|path| {
+                fs::read_to_string(path)
+            })
+            .map_err(CliError::Config)?;
+    }
+    Ok(())
+}
+
+// Adds the words of each of dicts, then removes the words of each of
+// excludes, as if they were all one word list.
+pub fn load_dictionaries(dicts: &[PathBuf], excludes: &[PathBuf]) -> Result<TrieNode, CliError> {
+    let mut root = merge_dictionaries(dicts, excludes)?;
+    root.shrink_to_fit();
+    Ok(root)
+}
+
+// load_dictionaries() without giving back the spare capacity at the end.
+fn merge_dictionaries(dicts: &[PathBuf], excludes: &[PathBuf]) -> Result<TrieNode, CliError> {
+    let mut root = match dicts {
+        #[cfg(feature = "embedded-dict")]
+        [] => {
+            eprintln!("note: no --dict given, using the built-in list of common words");
+            TrieNode::default_dictionary()
+        }
+        _ => TrieNode::new_root(),
+    };
+    for path in dicts {
+        add_dictionary(&mut root, path)?;
+    }
+    for path in excludes {
+        root.remove_word_file(path)
+            .map_err(|source| CliError::Dictionary {
+                path: path.clone(),
+                source,
+            })?;
+    }
+    Ok(root)
+}
+
+// Word lists for solving leave out words no board can spell.
+fn word_list_options() -> LoadOptions {
+    LoadOptions {
+        filter: WordFilter::SkipNonAlphabetic,
+        ..LoadOptions::default()
+    }
+}
+
+// With cli-progress, shows how far loading the word list at path has got,
+// if there's a terminal to show it on.
+#[cfg(feature = "cli-progress")]
+fn load_progress(path: &Path) -> impl FnMut(LoadProgress) {
+    let mut bar = crate::progress::LoadBar::new(path);
+    move |progress| {
+        if let Some(bar) = &mut bar {
+            bar.update(progress);
+        }
+    }
+}
+
+#[cfg(not(feature = "cli-progress"))]
+fn load_progress(_path: &Path) -> impl FnMut(LoadProgress) {
+    |_| {}
+}
+
+// Either a word list or a compiled dictionary, told apart by the compiled
+// format's magic bytes. A compiled dictionary older than its word list is
+// still used, with a warning.
+fn add_dictionary(root: &mut TrieNode, path: &Path) -> Result<(), CliError> {
+    let mut add = || {
+        let mut file = BufReader::new(File::open(path)?);
+        if !file.fill_buf()?.starts_with(MAGIC) {
+            let on_progress = load_progress(path);
+            root.add_reader_with_progress(
+                open_word_file(path)?,
+                &word_list_options(),
+                on_progress,
+            )?;
+            return Ok(());
+        }
+        let (compiled, source) = TrieNode::read_with_source(file)?;
+        if let Some(warning) = source.and_then(|source| stale_warning(path, &source)) {
+            eprintln!("warning: {}", warning);
+        }
+        // Only a first dictionary can be used as it is.
+        if root.node_count() == 1 {
+            *root = compiled;
+        } else {
+            for word in compiled.words() {
+                match compiled.get(&word) {
+                    Some(payload) => root.add_word_with(&word, payload.clone()),
+                    None => root.add_word(&word),
+                }
+            }
+        }
+        Ok(())
+    };
+    add().map_err(|source| CliError::Dictionary {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub fn stale_warning(path: &Path, source: &Source) -> Option<String> {
+    source.is_stale().then(|| {
+        format!(
+            "{} has changed since {} was compiled from it; run compile-dict again",
+            source.path.display(),
+            path.display()
+        )
+    })
+}
+
+pub fn compile_dict(args: &CompileArgs) -> Result<(), CliError> {
+    let read_err = |source| CliError::Dictionary {
+        path: args.words.clone(),
+        source,
+    };
+    let mut root = TrieNode::new_root();
+    open_word_file(&args.words)
+        .and_then(|reader| root.add_reader(reader, &word_list_options()))
+        .map_err(read_err)?;
+    let source = Source::of(&args.words).map_err(read_err)?;
+    let output = match &args.output {
+        Some(output) => output.clone(),
+        None => args.words.with_extension("rzd"),
+    };
+    File::create(&output)
+        .and_then(|file| root.write_with_source(BufWriter::new(file), Some(&source)))
+        .map_err(|source| CliError::Compile {
+            path: output,
+            source,
+        })
+}
+
+// The dict-stats output: the number of words and trie nodes, the longest
+// word, then how many words there are of each length that has any.
+pub fn dict_summary(root: &TrieNode) -> String {
+    let histogram = root.word_length_histogram();
+    let longest = match root.longest_word() {
+        Some(word) => format!("{} ({} letters)", word, histogram.len() - 1),
+        None => "none".to_string(),
+    };
+    let mut out = format!(
+        "Words: {}\nTrie nodes: {}\nDAWG nodes: {}\nLongest word: {}\nLetters  Words\n",
+        root.word_count(),
+        root.node_count(),
+        root.minimize().node_count(),
+        longest
+    );
+    for (len, &count) in histogram.iter().enumerate() {
+        if count > 0 {
+            out.push_str(&format!("{:>7}  {}\n", len, count));
+        }
+    }
+    out
+}
+
+// The memory dict-stats reports, as loaded and after shrink_to_fit().
+pub fn memory_summary(loaded: &MemoryReport, shrunk: &MemoryReport) -> String {
+    format!(
+        "Memory: {} bytes used, {} allocated ({} as loaded)\n",
+        shrunk.used_bytes, shrunk.allocated_bytes, loaded.allocated_bytes
+    )
+}
+
+// The dict-diff output: how many words new adds to old and how many it
+// removes, each followed by the first samples of those words.
+pub fn dict_diff(old: &TrieNode, new: &TrieNode, samples: usize) -> String {
+    let mut out = String::new();
+    for (name, from, to) in [("Added", new, old), ("Removed", old, new)] {
+        let words: Vec<String> = from.difference(to).collect();
+        out.push_str(&format!("{}: {}\n", name, words.len()));
+        for word in words.iter().take(samples) {
+            out.push_str(&format!("  {}\n", word));
+        }
+        if words.len() > samples {
+            out.push_str("  ...\n");
+        }
+    }
+    out
+}
+
+pub fn run(cli: &Cli) -> Result<(), CliError> {
+    #[cfg(feature = "serde")]
+    if cli.schema {
+        print!("{}", SCHEMA);
+        return Ok(());
+    }
+    match (&cli.command, &cli.boards, cli.size) {
+        (Some(Command::CompileDict(args)), _, _) => compile_dict(args),
+        (Some(Command::DictStats(args)), _, _) => {
+            let mut root = merge_dictionaries(&args.dict, &args.exclude_dict)?;
+            let loaded = root.memory_usage();
+            root.shrink_to_fit();
+            if let Some(path) = &args.export_dot {
+                fs::write(path, root.to_dot(args.dot_max_nodes)).map_err(|source| {
+                    CliError::Compile {
+                        path: path.clone(),
+                        source,
+                    }
+                })?;
+            }
+            print!("{}", dict_summary(&root));
+            print!("{}", memory_summary(&loaded, &root.memory_usage()));
+            Ok(())
+        }
+        (Some(Command::DictDiff(args)), _, _) => {
+            let old = load_dictionaries(std::slice::from_ref(&args.old), &[])?;
+            let new = load_dictionaries(std::slice::from_ref(&args.new), &[])?;
+            print!("{}", dict_diff(&old, &new, args.samples));
+            Ok(())
+        }
+        #[cfg(feature = "rand")]
+        (Some(Command::Generate(args)), _, _) => {
+            for line in generate(args)? {
+                println!("{}", line);
+            }
+            Ok(())
+        }
+        #[cfg(feature = "rand")]
+        (Some(Command::Optimize(args)), _, _) => {
+            println!("{}", optimize(args)?);
+            Ok(())
+        }
+        (None, None, _) if cli.anagram.is_some() => {
+            print!("{}", anagrams(cli)?);
+            Ok(())
+        }
+        (None, Some(boards), BoardSize::Ruzzle) => run_batch::<4, 4>(cli, boards),
+        (None, Some(boards), BoardSize::Boggle) => run_batch::<5, 5>(cli, boards),
+        (None, None, BoardSize::Ruzzle) if cli.practice => practice::run::<4, 4>(cli),
+        (None, None, BoardSize::Boggle) if cli.practice => practice::run::<5, 5>(cli),
+        (None, None, BoardSize::Ruzzle) if cli.interactive => repl::run::<4, 4>(cli),
+        (None, None, BoardSize::Boggle) if cli.interactive => repl::run::<5, 5>(cli),
+        (None, None, BoardSize::Ruzzle) => run_sized::<4, 4>(cli),
+        (None, None, BoardSize::Boggle) => run_sized::<5, 5>(cli),
+    }
+}
+
+// Loads the dictionary once for all the boards. A line which isn't a board
+// is reported on stderr and skipped. Only text and JSON lines output can be
+// given a line per board.
+fn run_batch<const W: usize, const H: usize>(cli: &Cli, path: &Path) -> Result<(), CliError> {
+    match cli.format {
+        Format::Text => {}
+        #[cfg(feature = "serde")]
+        Format::Jsonl => {}
+        format => return Err(CliError::BoardsFormat(format)),
+    }
+    let read_err = |source| CliError::Boards {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = File::open(path).map_err(read_err)?;
+    let mut lines = vec![];
+    for (n, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(read_err)?;
+        if !line.trim().is_empty() {
+            lines.push((n + 1, line));
+        }
+    }
+    let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+    let solve = |(n, line): &(usize, String)| {
+        batch_line::<W, H>(cli, &root, line)
+            .map_err(|err| format!("line {}: invalid board: {}", n, err))
+    };
+
+    #[cfg(feature = "parallel")]
+    let outputs: Vec<_> = {
+        use rayon::prelude::*;
+        lines.par_iter().map(solve).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let outputs: Vec<_> = lines.iter().map(solve).collect();
+
+    for output in outputs {
+        match output {
+            Ok(output) => println!("{}", output),
+            Err(warning) => eprintln!("warning: {}", warning),
+        }
+    }
+    Ok(())
+}
+
+// One board's line of --boards output: its results in JSON with --format
+// jsonl, or else "board unique_words total_score".
+pub fn batch_line<const W: usize, const H: usize>(
+    cli: &Cli,
+    root: &TrieNode,
+    line: &str,
+) -> Result<String, BoardParseError> {
+    let board: Board<Tile, W, H> = line.parse()?;
+    let opts = solve_options(cli);
+    #[cfg(feature = "serde")]
+    if cli.format == Format::Jsonl {
+        let results = solve_best_words(root, &board, &opts);
+        return Ok(to_json(cli, &board, &results, false));
+    }
+    let summary = count_words(root, &board, &opts);
+    Ok(format!(
+        "{} {} {}",
+        line.trim(),
+        summary.unique_words,
+        summary.total_score
+    ))
+}
+
+// One line per board, its letters run together, so that the output can be
+// given to --boards.
+#[cfg(feature = "rand")]
+pub fn generate(args: &GenerateArgs) -> Result<Vec<String>, CliError> {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    match args.size {
+        BoardSize::Ruzzle => generate_sized::<4, 4>(args, &mut rng),
+        BoardSize::Boggle => generate_sized::<5, 5>(args, &mut rng),
+    }
+}
+
+#[cfg(feature = "rand")]
+fn generate_sized<const W: usize, const H: usize>(
+    args: &GenerateArgs,
+    rng: &mut impl rand::Rng,
+) -> Result<Vec<String>, CliError> {
+    let root = match args.dict.is_empty() {
+        true => None,
+        false => Some(load_dictionaries(&args.dict, &[])?),
+    };
+    let opts = size_options::<W, H>(args.size);
+    let lines = (0..args.count).map(|_| {
+        let board = Board::<Tile, W, H>::random(rng);
+        let letters: String = board.iter().map(ToString::to_string).collect();
+        match &root {
+            Some(root) => {
+                let summary = count_words(root, &board, &opts);
+                format!(
+                    "{} {} {}",
+                    letters, summary.unique_words, summary.total_score
+                )
+            }
+            None => letters,
+        }
+    });
+    Ok(lines.collect())
+}
+
+// The best board found, as "board total_score". Starts from a random board.
+#[cfg(feature = "rand")]
+pub fn optimize(args: &OptimizeArgs) -> Result<String, CliError> {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    match args.size {
+        BoardSize::Ruzzle => optimize_sized::<4, 4>(args, &mut rng),
+        BoardSize::Boggle => optimize_sized::<5, 5>(args, &mut rng),
+    }
+}
+
+#[cfg(feature = "rand")]
+fn optimize_sized<const W: usize, const H: usize>(
+    args: &OptimizeArgs,
+    rng: &mut impl rand::Rng,
+) -> Result<String, CliError> {
+    use ruzzle_solver::optimize::{AnnealOptions, Budget, Optimized, optimize};
+
+    let root = load_dictionaries(&args.dict, &[])?;
+    let budget = match (args.seconds, args.iterations) {
+        (Some(seconds), _) => Budget::Time(seconds),
+        (None, iterations) => Budget::Iterations(iterations.unwrap_or(10_000)),
+    };
+    let opts = AnnealOptions {
+        budget,
+        progress_every: args.progress,
+        ..AnnealOptions::default()
+    };
+    let letters =
+        |board: &Board<Tile, W, H>| -> String { board.iter().map(ToString::to_string).collect() };
+    let start = Board::random(rng);
+    let best = optimize(
+        &root,
+        start,
+        &size_options(args.size),
+        &opts,
+        rng,
+        |best: &Optimized<W, H>| {
+            eprintln!(
+                "iteration {}: {} {}",
+                best.iterations,
+                letters(&best.board),
+                best.score
+            )
+        },
+    );
+    Ok(format!("{} {}", letters(&best.board), best.score))
+}
+
+fn run_sized<const W: usize, const H: usize>(cli: &Cli) -> Result<(), CliError> {
+    let board = parse_board::<W, H>(cli.board.as_deref().unwrap_or_default())?;
+    parse_bonuses::<W, H>(cli)?;
+    warn_unknown_letters(&solve_options(cli), &board);
+    if let Some(n) = cli.suggest {
+        let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+        let suggestions = suggest_changes(&root, &board, &solve_options(cli), cli.swaps);
+        for suggestion in suggestions.iter().take(n) {
+            println!("{}", suggestion);
+        }
+        return Ok(());
+    }
+    if let Some(path) = &cli.found_words {
+        let found = std::fs::read_to_string(path).map_err(|source| CliError::FoundWords {
+            path: path.clone(),
+            source,
+        })?;
+        let found: Vec<String> = found.lines().map(str::to_string).collect();
+        let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+        let report = analyze(&found, &root, &board, &solve_options(cli));
+        print!("{}", missed_report(&report, cli.top));
+        return Ok(());
+    }
+    let all = solve_board(cli, &board)?;
+    let results = top_results(cli, &all);
+    match (cli.format, cli.show_paths) {
+        #[cfg(feature = "serde")]
+        (Format::Json, _) => println!("{}", to_json(cli, &board, results, cli.pretty)),
+        #[cfg(feature = "serde")]
+        (Format::Jsonl, _) => println!("{}", to_json(cli, &board, results, false)),
+        (Format::Csv, _) => {
+            write_csv(&mut io::stdout().lock(), results).map_err(CliError::Output)?
+        }
+        (Format::Text, _) if cli.heatmap => {
+            println!("Words per tile:");
+            print!("{}", render_heatmap(&board, &tile_heatmap(results)));
+            println!("Points per tile:");
+            print!("{}", render_heatmap(&board, &tile_score_heatmap(results)));
+        }
+        (Format::Text, _) if cli.disjoint => print!("{}", disjoint_words(&board, results)),
+        (Format::Text, _) if cli.words_only => print!("{}", words_only(results)),
+        (Format::Text, _) if cli.group_by_length => print!("{}", group_by_length(results)),
+        (Format::Text, None) if cli.explain.is_some() => {
+            let n = cli.explain.unwrap_or_default().min(results.len());
+            print!("{}", explained(&board, &solve_options(cli), &results[..n]));
+        }
+        (Format::Text, Some(n)) => {
+            let colour = colour_enabled(std::env::var_os("NO_COLOR"), io::stdout().is_terminal());
+            print_paths(&board, &results[..n.min(results.len())], colour);
+        }
+        (Format::Text, None) => {
+            write_results(&mut io::stdout().lock(), results).map_err(CliError::Output)?;
+            print!("\n{}", SolveSummary::from_results(&all));
+        }
+    }
+    Ok(())
+}
+
+// The --anagram words, one "score word" line each as for a board. --size
+// only decides the scoring and minimum length, since there's no board.
+pub fn anagrams(cli: &Cli) -> Result<String, CliError> {
+    let letters: Vec<char> = normalize(cli.anagram.as_deref().unwrap_or_default())
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect();
+    let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+    let results = solve_anagram(&root, &letters, &solve_options::<4, 4>(cli));
+    Ok(results
+        .iter()
+        .map(|result| format!("{:>4} {}\n", result.score, result.word))
+        .collect())
+}
+
+// What every solve of a board of this size starts from, before the options
+// given on the command line.
+pub fn size_options<const W: usize, const H: usize>(size: BoardSize) -> SolveOptions<W, H> {
+    let mut opts = SolveOptions {
+        sort: Sort::ByScore,
+        ..SolveOptions::default()
+    };
+    if size == BoardSize::Boggle {
+        opts.min_len = 3;
+        opts.scoring = Scoring::Boggle;
+    }
+    opts
+}
+
+pub fn solve_options<const W: usize, const H: usize>(cli: &Cli) -> SolveOptions<W, H> {
+    let mut opts = size_options(cli.size);
+    if let Some(min_len) = cli.min_len {
+        opts.min_len = min_len;
+    }
+    opts.limit = cli.top;
+    // Checked along with the board, before solving.
+    opts.bonuses = parse_bonuses(cli).unwrap_or_default();
+    opts.letter_values = match (&cli.letter_values, cli.language) {
+        (Some(values), _) => values.clone(),
+        (None, language) => LetterValues::for_language(language.unwrap_or_default()),
+    };
+    opts
+}
+
+// Every word on the board, best first. --top only decides how many of them
+// are shown, see top_results(), since the summary is of all of them.
+pub fn solve_board<const W: usize, const H: usize>(
+    cli: &Cli,
+    board: &Board<Tile, W, H>,
+) -> Result<Vec<SolveResult<W, H>>, CliError> {
+    let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+    let opts = SolveOptions {
+        limit: None,
+        ..solve_options(cli)
+    };
+    Ok(solve_best_words(&root, board, &opts))
+}
+
+pub fn top_results<'a, const W: usize, const H: usize>(
+    cli: &Cli,
+    results: &'a [SolveResult<W, H>],
+) -> &'a [SolveResult<W, H>] {
+    &results[..cli.top.map_or(results.len(), |n| n.min(results.len()))]
+}
+
+// What --schema prints, for the JsonOutput of SCHEMA_VERSION.
+#[cfg(feature = "serde")]
+pub const SCHEMA: &str = include_str!("../data/schema.json");
+
+// The --format json output. Tools read this, so the field names and their
+// meaning must stay as they are; only new fields may be added. Anything
+// else raises SCHEMA_VERSION. testdata/results_v1.json is output of the
+// first version, which the output must stay compatible with.
+//
+// schema_version: SCHEMA_VERSION.
+// board: the tiles as an array of rows, top to bottom, each tile a string
+//        ("a", "qu", or "?" for a wildcard).
+// options: size ("4x4" or "5x5"), min_len (letters), scoring ("ruzzle" or
+//          "boggle") and sort ("by_score"), as used for this solve.
+// results: the best path of each word, highest score first, as
+//          {"word", "path", "score"}, path being an array of {"x", "y"} from
+//          the top left tile. Words spelled with a wildcard also have
+//          "wildcard_bindings", an array of [{"x", "y"}, letter] pairs, and
+//          words the dictionary has a frequency or definition for have
+//          "payload", as {"frequency", "definition"} with either left out.
+#[cfg(feature = "serde")]
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+pub struct JsonOutput<'a, const W: usize, const H: usize> {
+    pub schema_version: u32,
+    pub board: &'a Board<Tile, W, H>,
+    pub options: JsonOptions,
+    pub results: &'a [SolveResult<W, H>],
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+pub struct JsonOptions {
+    pub size: String,
+    pub min_len: usize,
+    pub scoring: Scoring,
+    pub sort: Sort,
+}
+
+#[cfg(feature = "serde")]
+pub fn to_json<const W: usize, const H: usize>(
+    cli: &Cli,
+    board: &Board<Tile, W, H>,
+    results: &[SolveResult<W, H>],
+    pretty: bool,
+) -> String {
+    let opts = solve_options::<W, H>(cli);
+    let output = JsonOutput {
+        schema_version: SCHEMA_VERSION,
+        board,
+        options: JsonOptions {
+            size: cli.size.to_string(),
+            min_len: opts.min_len,
+            scoring: opts.scoring,
+            sort: opts.sort,
+        },
+        results,
+    };
+    let json = if pretty {
+        serde_json::to_string_pretty(&output)
+    } else {
+        serde_json::to_string(&output)
+    };
+    json.expect("results always serialize")
+}
+
+// Colour only goes to a terminal, and never when NO_COLOR is set to
+// anything but the empty string (https://no-color.org).
+pub fn colour_enabled(no_color: Option<OsString>, is_tty: bool) -> bool {
+    is_tty && no_color.is_none_or(|value| value.is_empty())
+}
+
+pub fn print_paths<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    results: &[SolveResult<W, H>],
+    colour: bool,
+) {
+    for result in results {
+        if colour {
+            print!("{}", render_path_ansi(board, &result.path));
+        } else {
+            print!("{}", render_path(board, &result.path));
+        }
+        println!("{} {}", result.word, result.score);
+        println!();
+    }
+}
+
+// One row per result, after a header: word,length,score,start_x,start_y,path
+// with length in letters and path as "(x,y)>(x,y)>...". Rows are written as
+// they go, so wrap out in a BufWriter if it isn't buffered already.
+pub fn write_csv<const W: usize, const H: usize>(
+    out: &mut impl io::Write,
+    results: &[SolveResult<W, H>],
+) -> io::Result<()> {
+    writeln!(out, "word,length,score,start_x,start_y,path")?;
+    for result in results {
+        let (start_x, start_y) = result.path[0].to_xy();
+        let path: Vec<String> = result
+            .path
+            .iter()
+            .map(|idx| format!("({},{})", idx.to_xy().0, idx.to_xy().1))
+            .collect();
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            csv_field(&result.word),
+            result.word.chars().count(),
+            result.score,
+            start_x,
+            start_y,
+            csv_field(&path.join(">"))
+        )?;
+    }
+    out.flush()
+}
+
+// Quoted, with quotes doubled, if it holds anything CSV treats specially.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Each word once, in alphabetical order, for comparing with the game's list.
+pub fn words_only<const W: usize, const H: usize>(results: &[SolveResult<W, H>]) -> String {
+    let words: BTreeSet<&str> = results.iter().map(|r| r.word.as_str()).collect();
+    words
+        .into_iter()
+        .map(|word| format!("{}\n", word))
+        .collect()
+}
+
+// Like the game's results screen: a "N letters:" heading for each word
+// length, longest first, with that length's words beneath in alphabetical
+// order and a blank line between groups.
+pub fn group_by_length<const W: usize, const H: usize>(results: &[SolveResult<W, H>]) -> String {
+    let mut groups: BTreeMap<Reverse<usize>, BTreeSet<&str>> = BTreeMap::new();
+    for result in results {
+        let len = result.word.chars().count();
+        groups.entry(Reverse(len)).or_default().insert(&result.word);
+    }
+    let groups: Vec<String> = groups
+        .into_iter()
+        .map(|(Reverse(len), words)| {
+            let mut group = format!("{} letters:\n", len);
+            for word in words {
+                group.push_str(word);
+                group.push('\n');
+            }
+            group
+        })
+        .collect();
+    groups.join("\n")
+}
+
+// The found words with their scores, then the words that aren't, grouped by
+// why, then the top best missed words (or all of them), like:
+//
+// Found 2 words for 14 points:
+//    9 fab
+//    5 be
+// Not on the board: zoo
+// Not in the dictionary: bef
+// Missed 2 words for 11 points:
+//    6 abe
+//    5 ab
+pub fn missed_report<const W: usize, const H: usize>(
+    report: &MissedReport<W, H>,
+    top: Option<usize>,
+) -> String {
+    let lines = |results: &[SolveResult<W, H>]| {
+        let mut out = vec![];
+        write_results(&mut out, results).expect("writing to a Vec never fails");
+        String::from_utf8(out).expect("words are UTF-8")
+    };
+    let words = |n: usize| {
+        if n == 1 {
+            "1 word".to_string()
+        } else {
+            format!("{} words", n)
+        }
+    };
+    let mut out = format!(
+        "Found {} for {} points:\n{}",
+        words(report.found.len()),
+        report.found_score,
+        lines(&report.found)
+    );
+    for (heading, wrong) in [
+        ("Not on the board", &report.not_on_board),
+        ("Not in the dictionary", &report.not_in_dictionary),
+        ("Too short", &report.too_short),
+        ("Left out by the options", &report.excluded),
+    ] {
+        if !wrong.is_empty() {
+            out.push_str(&format!("{}: {}\n", heading, wrong.join(", ")));
+        }
+    }
+    let shown = top.unwrap_or(usize::MAX).min(report.missed.len());
+    out.push_str(&format!(
+        "Missed {} for {} points:\n{}",
+        words(report.missed.len()),
+        report.missed_score,
+        lines(&report.missed[..shown])
+    ));
+    out
+}
+
+// A "score word" line for each result, with how the score adds up under it.
+pub fn explained<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    results: &[SolveResult<W, H>],
+) -> String {
+    results
+        .iter()
+        .map(|result| {
+            let breakdown = opts.explain(board, &result.path, &result.word);
+            format!("{:>4} {}\n     {}\n", result.score, result.word, breakdown)
+        })
+        .collect()
+}
+
+// The words select_disjoint_best() picks, numbered on the board, then
+// listed with their scores and the total.
+pub fn disjoint_words<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    results: &[SolveResult<W, H>],
+) -> String {
+    let chosen: Vec<SolveResult<W, H>> =
+        select_disjoint_best(results).into_iter().cloned().collect();
+    let masks: Vec<_> = chosen.iter().map(SolveResult::mask).collect();
+    let mut out = render_masks(board, &masks);
+    out.push('\n');
+    for (n, result) in chosen.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>2}. {:>4} {}\n",
+            n + 1,
+            result.score,
+            result.word
+        ));
+    }
+    let total: u32 = chosen.iter().map(|result| result.score).sum();
+    out.push_str(&format!("Combined score: {}\n", total));
+    out
+}
+
+// One "score word" line per result, the score right aligned.
+pub fn write_results<const W: usize, const H: usize>(
+    out: &mut impl io::Write,
+    results: &[SolveResult<W, H>],
+) -> io::Result<()> {
+    for result in results {
+        writeln!(out, "{:>4} {}", result.score, result.word)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruzzle_solver::board::Index4x4;
+
+    fn temp_dict(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("ruzzle-cli-{}-{}.txt", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_arguments() {
+        let cli = Cli::try_parse_from(["ruzzle-solver", "abcdefghijklmnop", "--dict", "words.txt"])
+            .unwrap();
+        assert_eq!(cli.board.as_deref(), Some("abcdefghijklmnop"));
+        assert_eq!(cli.dict, [PathBuf::from("words.txt")]);
+        assert!(cli.command.is_none());
+        assert_eq!(cli.size, BoardSize::Ruzzle);
+        assert_eq!(cli.show_paths, None);
+
+        let cli =
+            Cli::try_parse_from(["ruzzle-solver", "x", "--dict", "w", "--size", "5x5"]).unwrap();
+        assert_eq!(cli.size, BoardSize::Boggle);
+        assert!(
+            Cli::try_parse_from(["ruzzle-solver", "x", "--dict", "w", "--size", "6x6"]).is_err()
+        );
+
+        // The dictionary can come from elsewhere, see resolve_dictionaries().
+        let cli = Cli::try_parse_from(["ruzzle-solver", "abcdefghijklmnop"]).unwrap();
+        assert!(cli.dict.is_empty());
+        assert!(Cli::try_parse_from(["ruzzle-solver", "--dict", "words.txt"]).is_err());
+
+        let args = ["ruzzle-solver", "x", "--dict", "w"];
+        let cli = Cli::try_parse_from(args.iter().chain(&["--practice"])).unwrap();
+        assert!(cli.practice);
+        assert_eq!(cli.time, 120);
+        let cli = Cli::try_parse_from(args.iter().chain(&["--practice", "--time", "60"])).unwrap();
+        assert_eq!(cli.time, 60);
+        assert!(Cli::try_parse_from(args.iter().chain(&["--time", "60"])).is_err());
+        let cli = Cli::try_parse_from(args.iter().chain(&["--suggest", "5", "--swaps"])).unwrap();
+        assert_eq!((cli.suggest, cli.swaps), (Some(5), true));
+        assert!(Cli::try_parse_from(args.iter().chain(&["--swaps"])).is_err());
+        assert!(
+            Cli::try_parse_from(args.iter().chain(&["--heatmap"]))
+                .unwrap()
+                .heatmap
+        );
+        assert!(Cli::try_parse_from(args.iter().chain(&["--heatmap", "--words-only"])).is_err());
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn solves_with_embedded_dictionary() {
+        let cli = Cli::try_parse_from(["ruzzle-solver", "catsxxxxdogsxxxx"]).unwrap();
+        assert!(cli.dict.is_empty());
+        let results = solve_board(&cli, &parse_board::<4, 4>("catsxxxxdogsxxxx").unwrap());
+        let words: Vec<String> = results.unwrap().into_iter().map(|r| r.word).collect();
+        assert!(words.iter().any(|word| word == "cats"));
+        assert!(words.iter().any(|word| word == "dogs"));
+    }
+
+    #[test]
+    fn rejects_bad_boards() {
+        let err = parse_board::<4, 4>("abcdefghijklmno").unwrap_err();
+        assert_eq!(err.to_string(), "invalid board: expected 16 tiles, got 15");
+        let err = parse_board::<4, 4>("abcdefghijklmnopq").unwrap_err();
+        assert_eq!(err.to_string(), "invalid board: expected 16 tiles, got 17");
+        let err = parse_board::<4, 4>("abcdefgh1jklmnop").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid board: invalid character '1' at position 8"
+        );
+        assert!(parse_board::<4, 4>("abcdefghijklmnop").is_ok());
+    }
+
+    #[test]
+    fn letter_values() {
+        let parse = |extra: &[&str]| {
+            let args = ["ruzzle-solver", "catsxxxxxxxxxxxx", "--dict", "w"];
+            Cli::try_parse_from(args.iter().chain(extra))
+        };
+        let score = |cli: &Cli| {
+            let board = parse_board::<4, 4>("catsxxxxxxxxxxxx").unwrap();
+            let path: Vec<_> = (0..4).map(|x| Index4x4::from_xy(x, 0)).collect();
+            let opts = solve_options::<4, 4>(cli);
+            opts.scoring
+                .score_path(&opts.letter_values, &board, &opts.bonuses, &path)
+        };
+        assert_eq!(score(&parse(&[]).unwrap()), 7);
+        assert_eq!(score(&parse(&["--language", "sv"]).unwrap()), 11);
+        let err = parse(&["--language", "xx"]).unwrap_err().to_string();
+        assert!(err.contains("unknown language \"xx\", expected en, sv, it, de"));
+
+        let table = temp_dict("letter-values", "default = 2\nc = 5\n");
+        let cli = parse(&["--letter-values", table.to_str().unwrap()]);
+        let both = parse(&[
+            "--letter-values",
+            table.to_str().unwrap(),
+            "--language",
+            "sv",
+        ]);
+        let bad = temp_dict("bad-letter-values", "c = five\n");
+        let err = parse(&["--letter-values", bad.to_str().unwrap()]).unwrap_err();
+        std::fs::remove_file(&table).unwrap();
+        std::fs::remove_file(&bad).unwrap();
+        assert_eq!(score(&cli.unwrap()), 5 + 2 + 2 + 2);
+        assert!(both.is_err());
+        assert!(err.to_string().contains(":1: expected points, got five"));
+    }
+
+    #[test]
+    fn solves_from_arguments() {
+        let dict = temp_dict("solve", "fab\nab\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+        ])
+        .unwrap();
+        let results = solve_board(
+            &cli,
+            &parse_board::<4, 4>(cli.board.as_deref().unwrap()).unwrap(),
+        );
+        std::fs::remove_file(&dict).unwrap();
+
+        let words: Vec<String> = results.unwrap().into_iter().map(|r| r.word).collect();
+        assert_eq!(words, ["fab", "ab"]);
+    }
+
+    #[test]
+    fn missing_dictionary() {
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            "/nonexistent/words.txt",
+        ])
+        .unwrap();
+        let err = run(&cli).unwrap_err();
+        assert!(matches!(err, CliError::Dictionary { .. }));
+        assert!(
+            err.to_string()
+                .starts_with("could not read dictionary /nonexistent/words.txt")
+        );
+    }
+
+    #[test]
+    fn boggle_board() {
+        let err = parse_board::<5, 5>("abcdefghijklmnop").unwrap_err();
+        assert_eq!(err.to_string(), "invalid board: expected 25 tiles, got 16");
+
+        // s t a r e
+        // l i n e d
+        // qu o r s x
+        // t e a m s
+        // x x x x x
+        let dict = temp_dict(
+            "boggle",
+            "at\nstar\nstare\nstared\nlined\nquote\nteams\nrat\nzzz\n",
+        );
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "stare lined q(u)orsx teams xxxxx",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--size",
+            "5x5",
+        ])
+        .unwrap();
+        let results = solve_board(
+            &cli,
+            &parse_board::<5, 5>(cli.board.as_deref().unwrap()).unwrap(),
+        );
+        std::fs::remove_file(&dict).unwrap();
+
+        let scored: Vec<(u32, String)> = results
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.score, r.word))
+            .collect();
+        let expected = [
+            (3, "stared"),
+            (2, "lined"),
+            (2, "quote"),
+            (2, "stare"),
+            (2, "teams"),
+            (1, "rat"),
+            (1, "star"),
+        ];
+        assert_eq!(
+            scored,
+            expected.map(|(score, word)| (score, word.to_string()))
+        );
+    }
+
+    #[test]
+    fn colour_detection() {
+        assert!(colour_enabled(None, true));
+        assert!(colour_enabled(Some(OsString::new()), true));
+        assert!(!colour_enabled(Some(OsString::from("1")), true));
+        assert!(!colour_enabled(None, false));
+
+        let cli = Cli::try_parse_from(["ruzzle-solver", "x", "--dict", "w", "--show-paths", "3"])
+            .unwrap();
+        assert_eq!(cli.show_paths, Some(3));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_output() {
+        let dict = temp_dict("json", "fab\nab\nqua\n");
+        let args = [
+            "ruzzle-solver",
+            "abcdefghq(u)?klmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+        ];
+        let cli =
+            Cli::try_parse_from(args.iter().chain(&["--format", "json", "--pretty"])).unwrap();
+        let compact = Cli::try_parse_from(args.iter().chain(&["--format", "json"])).unwrap();
+        let board = parse_board::<4, 4>(cli.board.as_deref().unwrap()).unwrap();
+        let results = solve_board(&cli, &board);
+        std::fs::remove_file(&dict).unwrap();
+        let results = results.unwrap();
+
+        let pretty = to_json(&cli, &board, &results, cli.pretty);
+        assert_eq!(pretty, include_str!("../testdata/results.json").trim_end());
+
+        let compact = to_json(&compact, &board, &results, compact.pretty);
+        assert!(!compact.contains('\n'));
+        let parse = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+        assert_eq!(parse(&compact), parse(&pretty));
+    }
+
+    // Whether new has everything old has, with the same JSON types: the
+    // same keys in objects, possibly more, and as many array elements.
+    #[cfg(feature = "serde")]
+    fn assert_compatible(old: &serde_json::Value, new: &serde_json::Value, at: &str) {
+        use serde_json::Value;
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                for (key, value) in old {
+                    let field = format!("{}.{}", at, key);
+                    let new = new
+                        .get(key)
+                        .unwrap_or_else(|| panic!("{} is missing", field));
+                    assert_compatible(value, new, &field);
+                }
+            }
+            (Value::Array(old), Value::Array(new)) => {
+                assert_eq!(old.len(), new.len(), "{} changed length", at);
+                for (n, (old, new)) in old.iter().zip(new).enumerate() {
+                    assert_compatible(old, new, &format!("{}[{}]", at, n));
+                }
+            }
+            (Value::Number(old), Value::Number(new)) => {
+                assert_eq!(old.is_u64(), new.is_u64(), "{} changed type", at);
+            }
+            (old, new) => assert_eq!(
+                std::mem::discriminant(old),
+                std::mem::discriminant(new),
+                "{} changed type",
+                at
+            ),
+        }
+    }
+
+    // Just enough JSON Schema to check the output against SCHEMA: type,
+    // const, enum, required, properties, items, prefixItems and $ref.
+    #[cfg(feature = "serde")]
+    fn conforms(
+        value: &serde_json::Value,
+        schema: &serde_json::Value,
+        root: &serde_json::Value,
+    ) -> bool {
+        use serde_json::Value;
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            let name = reference.trim_start_matches("#/$defs/");
+            return conforms(value, &root["$defs"][name], root);
+        }
+        let type_ok = match schema.get("type").and_then(Value::as_str) {
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_u64() || value.is_i64(),
+            _ => true,
+        };
+        let const_ok = schema.get("const").is_none_or(|expected| value == expected);
+        let enum_ok = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .is_none_or(|allowed| allowed.contains(value));
+        let required_ok = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .is_none_or(|keys| {
+                keys.iter()
+                    .all(|key| value.get(key.as_str().unwrap()).is_some())
+            });
+        let properties_ok = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_none_or(|properties| {
+                properties.iter().all(|(key, property)| {
+                    value
+                        .get(key)
+                        .is_none_or(|field| conforms(field, property, root))
+                })
+            });
+        let items = value.as_array().map(Vec::as_slice).unwrap_or_default();
+        let items_ok = schema
+            .get("items")
+            .is_none_or(|item| items.iter().all(|value| conforms(value, item, root)));
+        let prefix_ok = schema
+            .get("prefixItems")
+            .and_then(Value::as_array)
+            .is_none_or(|prefix| {
+                prefix
+                    .iter()
+                    .zip(items)
+                    .all(|(item, value)| conforms(value, item, root))
+            });
+        type_ok && const_ok && enum_ok && required_ok && properties_ok && items_ok && prefix_ok
+    }
+
+    // The same solve as json_output(), as the first version of the schema
+    // had it. Fields may be added, but none of these removed or changed.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_schema_compatibility() {
+        let dict = temp_dict("json-v1", "fab\nab\nqua\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghq(u)?klmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        let board = parse_board::<4, 4>(cli.board.as_deref().unwrap()).unwrap();
+        let results = solve_board(&cli, &board);
+        std::fs::remove_file(&dict).unwrap();
+
+        let parse = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+        let v1 = parse(include_str!("../testdata/results_v1.json"));
+        let current = parse(&to_json(&cli, &board, &results.unwrap(), false));
+        assert_eq!(v1["schema_version"], 1);
+        assert_compatible(&v1, &current, "");
+
+        let schema = parse(SCHEMA);
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        assert!(conforms(&current, &schema, &schema));
+        assert!(conforms(&v1, &schema, &schema));
+        let mut broken = current.clone();
+        broken["results"][0]["path"][0]["x"] = "0".into();
+        assert!(!conforms(&broken, &schema, &schema));
+        broken = current.clone();
+        broken["options"].as_object_mut().unwrap().remove("sort");
+        assert!(!conforms(&broken, &schema, &schema));
+
+        let cli = Cli::try_parse_from(["ruzzle-solver", "--schema"]).unwrap();
+        assert!(cli.schema);
+        assert!(Cli::try_parse_from(["ruzzle-solver", "--schema", "abcdefghijklmnop"]).is_err());
+    }
+
+    // Just enough of CSV to read write_csv() back.
+    fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+        let mut rows = vec![];
+        for line in csv.lines() {
+            let (mut row, mut field, mut quoted) = (vec![], String::new(), false);
+            let mut chars = line.chars().peekable();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '"' if quoted && chars.peek() == Some(&'"') => {
+                        field.push(chars.next().unwrap());
+                    }
+                    '"' => quoted = !quoted,
+                    ',' if !quoted => row.push(std::mem::take(&mut field)),
+                    ch => field.push(ch),
+                }
+            }
+            row.push(field);
+            rows.push(row);
+        }
+        rows
+    }
+
+    #[test]
+    fn csv_output() {
+        let dict = temp_dict("csv", "fab\nab\nqua\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghq(u)?klmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--format",
+            "csv",
+        ])
+        .unwrap();
+        assert_eq!(cli.format, Format::Csv);
+        let results = solve_board(
+            &cli,
+            &parse_board::<4, 4>(cli.board.as_deref().unwrap()).unwrap(),
+        );
+        std::fs::remove_file(&dict).unwrap();
+        let results = results.unwrap();
+
+        let mut out = vec![];
+        write_csv(&mut out, &results).unwrap();
+        let rows = parse_csv(&String::from_utf8(out).unwrap());
+        assert_eq!(rows.len(), results.len() + 1);
+        assert_eq!(
+            rows[0],
+            ["word", "length", "score", "start_x", "start_y", "path"]
+        );
+        assert_eq!(rows[1], ["qua", "3", "12", "0", "2", "(0,2)>(1,2)"]);
+        assert_eq!(rows[2], ["fab", "3", "9", "1", "1", "(1,1)>(0,0)>(1,0)"]);
+
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(parse_csv(&csv_field("a,\"b\"")), [["a,\"b\""]]);
+    }
+
+    #[test]
+    fn word_list_outputs() {
+        let dict = temp_dict(
+            "lists",
+            "ab\nba\nfab\nabe\nbe\njin\nkop\nplonk\nglop\nponk\n",
+        );
+        let board = parse_board::<4, 4>("abcdefghijklmnop").unwrap();
+        let solve = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                [
+                    "ruzzle-solver",
+                    "abcdefghijklmnop",
+                    "--dict",
+                    dict.to_str().unwrap(),
+                ]
+                .iter()
+                .chain(args),
+            )
+            .unwrap();
+            top_results(&cli, &solve_board(&cli, &board).unwrap()).to_vec()
+        };
+        let all = solve(&["--words-only"]);
+        let top = solve(&["--group-by-length", "--top", "4"]);
+        let long = solve(&["--group-by-length", "--min-len", "4"]);
+        std::fs::remove_file(&dict).unwrap();
+
+        assert_eq!(words_only(&all), include_str!("../testdata/words_only.txt"));
+        assert_eq!(
+            group_by_length(&all),
+            include_str!("../testdata/by_length.txt")
+        );
+        assert_eq!(
+            group_by_length(&top),
+            "5 letters:\nplonk\n\n4 letters:\nponk\n\n3 letters:\njin\nkop\n"
+        );
+        assert_eq!(words_only(&long), "glop\nplonk\nponk\n");
+
+        let args = ["ruzzle-solver", "x", "--dict", "w", "--words-only"];
+        assert!(Cli::try_parse_from(args.iter().chain(&["--group-by-length"])).is_err());
+        assert!(Cli::try_parse_from(args.iter().chain(&["--show-paths", "1"])).is_err());
+    }
+
+    #[test]
+    fn compiled_dictionary() {
+        let words = temp_dict(
+            "compile",
+            "ab\nba\nfab\nabe\nbe\njin\nkop\nplonk\nglop\nponk\n",
+        );
+        let compiled = words.with_extension("rzd");
+        let cli = Cli::try_parse_from(["ruzzle-solver", "compile-dict", words.to_str().unwrap()])
+            .unwrap();
+        assert!(cli.board.is_none());
+        run(&cli).unwrap();
+        assert!(std::fs::read(&compiled).unwrap().starts_with(MAGIC));
+
+        let solve = |dict: &Path| {
+            let cli = Cli::try_parse_from([
+                "ruzzle-solver",
+                "abcdefghijklmnop",
+                "--dict",
+                dict.to_str().unwrap(),
+            ])
+            .unwrap();
+            solve_board(
+                &cli,
+                &parse_board::<4, 4>(cli.board.as_deref().unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+        let from_words = solve(&words);
+        assert_eq!(from_words.len(), 10);
+        assert_eq!(solve(&compiled), from_words);
+
+        let (_, source) = TrieNode::read_with_source(File::open(&compiled).unwrap()).unwrap();
+        let source = source.unwrap();
+        assert_eq!(stale_warning(&compiled, &source), None);
+        std::fs::write(&words, "ab\n").unwrap();
+        let warning = stale_warning(&compiled, &source).unwrap();
+        assert!(warning.ends_with("was compiled from it; run compile-dict again"));
+        // Still usable when stale.
+        assert_eq!(solve(&compiled), from_words);
+
+        std::fs::remove_file(&words).unwrap();
+        std::fs::remove_file(&compiled).unwrap();
+
+        assert!(Cli::try_parse_from(["ruzzle-solver", "compile-dict"]).is_err());
+    }
+
+    #[test]
+    fn dict_stats() {
+        let words = temp_dict("stats", "a\nat\nate\ntea\ntee\nteapot\n");
+        let excluded = temp_dict("stats-excluded", "tee\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "dict-stats",
+            "--dict",
+            words.to_str().unwrap(),
+            "--exclude-dict",
+            excluded.to_str().unwrap(),
+        ])
+        .unwrap();
+        let Some(Command::DictStats(args)) = &cli.command else {
+            panic!("expected dict-stats, got {:?}", cli.command);
+        };
+        let root = load_dictionaries(&args.dict, &args.exclude_dict);
+        std::fs::remove_file(&words).unwrap();
+        std::fs::remove_file(&excluded).unwrap();
+
+        // The root, a-t-e, t-e-a-p-o-t.
+        assert_eq!(
+            dict_summary(&root.unwrap()),
+            "Words: 5\n\
+             Trie nodes: 10\n\
+             DAWG nodes: 9\n\
+             Longest word: teapot (6 letters)\n\
+             Letters  Words\n      \
+             1  1\n      \
+             2  1\n      \
+             3  2\n      \
+             6  1\n"
+        );
+        assert_eq!(
+            dict_summary(&TrieNode::new_root()),
+            "Words: 0\nTrie nodes: 1\nDAWG nodes: 1\nLongest word: none\nLetters  Words\n"
+        );
+    }
+
+    #[test]
+    fn memory() {
+        let report = |used_bytes, allocated_bytes| MemoryReport {
+            nodes: 10,
+            used_bytes,
+            allocated_bytes,
+        };
+        assert_eq!(
+            memory_summary(&report(20_000, 30_000), &report(20_000, 20_500)),
+            "Memory: 20000 bytes used, 20500 allocated (30000 as loaded)\n"
+        );
+    }
+
+    #[test]
+    fn dict_diffs() {
+        let twl = temp_dict("twl", "tea\nteam\nzealot\nqi\n");
+        let sowpods = temp_dict("sowpods", "tea\nte\nzealots\nqi\nza\nzo\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "dict-diff",
+            twl.to_str().unwrap(),
+            sowpods.to_str().unwrap(),
+            "--samples",
+            "3",
+        ])
+        .unwrap();
+        let Some(Command::DictDiff(args)) = &cli.command else {
+            panic!("expected dict-diff, got {:?}", cli.command);
+        };
+        let old = load_dictionaries(std::slice::from_ref(&args.old), &[]).unwrap();
+        let new = load_dictionaries(std::slice::from_ref(&args.new), &[]).unwrap();
+        std::fs::remove_file(&twl).unwrap();
+        std::fs::remove_file(&sowpods).unwrap();
+
+        assert_eq!(
+            dict_diff(&old, &new, args.samples),
+            "Added: 4\n  te\n  za\n  zealots\n  ...\n\
+             Removed: 2\n  team\n  zealot\n"
+        );
+        assert_eq!(dict_diff(&old, &old, 3), "Added: 0\nRemoved: 0\n");
+    }
+
+    #[test]
+    fn skips_non_alphabetic_words() {
+        let words = temp_dict("punctuated", include_str!("../testdata/punctuated.txt"));
+        let root = load_dictionaries(std::slice::from_ref(&words), &[]).unwrap();
+        let compiled = words.with_extension("rzd");
+        compile_dict(&CompileArgs {
+            words: words.clone(),
+            output: Some(compiled.clone()),
+        })
+        .unwrap();
+        let from_compiled = load_dictionaries(std::slice::from_ref(&compiled), &[]).unwrap();
+        std::fs::remove_file(&words).unwrap();
+        std::fs::remove_file(&compiled).unwrap();
+
+        assert_eq!(root.words().collect::<Vec<_>>(), ["café", "mail", "tea"]);
+        assert_eq!(from_compiled, root);
+    }
+
+    #[test]
+    fn merged_dictionaries() {
+        let base = temp_dict("base", "ab\nba\nfab\nabe\n");
+        let additions = temp_dict("additions", "fab\nplonk\nkop\n");
+        let removals = temp_dict("removals", "ba\nkop\nzzz\n");
+        let compiled = additions.with_extension("rzd");
+        compile_dict(&CompileArgs {
+            words: additions.clone(),
+            output: Some(compiled.clone()),
+        })
+        .unwrap();
+
+        let dict = |path: &PathBuf| path.to_str().unwrap().to_string();
+        let mut cli = Cli::try_parse_from([
+            "ruzzle-solver".to_string(),
+            "abcdefghijklmnop".to_string(),
+            "--dict".to_string(),
+            dict(&base),
+            "--dict".to_string(),
+            dict(&additions),
+            "--exclude-dict".to_string(),
+            dict(&removals),
+        ])
+        .unwrap();
+        assert_eq!(cli.dict, [base.clone(), additions.clone()]);
+        let root = load_dictionaries(&cli.dict, &cli.exclude_dict).unwrap();
+        let results = solve_board(&cli, &parse_board::<4, 4>("abcdefghijklmnop").unwrap());
+        // A compiled dictionary merges in the same way, first or not.
+        let with_compiled =
+            load_dictionaries(&[base.clone(), compiled.clone()], &cli.exclude_dict).unwrap();
+        let compiled_first =
+            load_dictionaries(&[compiled.clone(), base.clone()], &cli.exclude_dict).unwrap();
+        cli.exclude_dict
+            .push(PathBuf::from("/nonexistent/words.txt"));
+        let missing = solve_board(&cli, &parse_board::<4, 4>("abcdefghijklmnop").unwrap());
+        for path in [base, additions, removals, compiled] {
+            std::fs::remove_file(path).unwrap();
+        }
+
+        for root in [&root, &with_compiled, &compiled_first] {
+            assert!(root.contains_word("ab") && root.contains_word("abe"));
+            assert!(root.contains_word("fab") && root.contains_word("plonk"));
+            assert!(!root.contains_word("ba") && !root.contains_word("kop"));
+            assert!(!root.contains_word("zzz"));
+            assert_eq!(root.words().count(), 4);
+        }
+        let words: Vec<String> = results.unwrap().into_iter().map(|r| r.word).collect();
+        assert_eq!(words, ["plonk", "fab", "abe", "ab"]);
+        assert!(matches!(missing, Err(CliError::Dictionary { .. })));
+    }
+
+    #[test]
+    fn batch_of_boards() {
+        let dict = temp_dict("batch", "ab\nba\nfab\nabe\nbe\njin\nkop\nplonk\n");
+        let boards = temp_dict(
+            "boards",
+            "abcdefghijklmnop\n\nnot a board\nplonkabfeatajinz\n",
+        );
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "--boards",
+            boards.to_str().unwrap(),
+            "--dict",
+            dict.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(cli.board.is_none());
+        run(&cli).unwrap();
+        let root = load_dictionaries(&cli.dict, &[]).unwrap();
+        std::fs::remove_file(&dict).unwrap();
+        std::fs::remove_file(&boards).unwrap();
+
+        assert_eq!(
+            batch_line::<4, 4>(&cli, &root, "abcdefghijklmnop").unwrap(),
+            "abcdefghijklmnop 8 69"
+        );
+        let err = batch_line::<4, 4>(&cli, &root, "not a board").unwrap_err();
+        assert_eq!(err.to_string(), "expected 16 tiles, got 9");
+        let line = batch_line::<4, 4>(&cli, &root, "plonkabfeatajinz").unwrap();
+        let solved = solve_best_words(
+            &root,
+            &parse_board::<4, 4>("plonkabfeatajinz").unwrap(),
+            &solve_options(&cli),
+        );
+        let total: u32 = solved.iter().map(|r| r.score).sum();
+        assert_eq!(line, format!("plonkabfeatajinz {} {}", solved.len(), total));
+
+        let args = ["ruzzle-solver", "--boards", "b.txt", "--dict", "w"];
+        assert!(Cli::try_parse_from(args.iter().chain(&["abcdefghijklmnop"])).is_err());
+        let cli = Cli::try_parse_from(args.iter().chain(&["--format", "csv"])).unwrap();
+        let err = run(&cli).unwrap_err();
+        assert_eq!(err.to_string(), "--format csv can't be used with --boards");
+        #[cfg(feature = "serde")]
+        {
+            let cli = Cli::try_parse_from(args.iter().chain(&["--format", "json"])).unwrap();
+            assert!(matches!(
+                run(&cli),
+                Err(CliError::BoardsFormat(Format::Json))
+            ));
+        }
+        assert!(Cli::try_parse_from(["ruzzle-solver", "--dict", "w"]).is_err());
+
+        let cli = Cli::try_parse_from(["ruzzle-solver", "--interactive", "--dict", "w"]).unwrap();
+        assert!(cli.interactive && cli.board.is_none());
+        assert!(
+            Cli::try_parse_from(["ruzzle-solver", "--interactive", "--dict", "w", "x"]).is_err()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn batch_json_lines() {
+        let dict = temp_dict("batch-json", "fab\nab\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "--boards",
+            "b.txt",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--format",
+            "jsonl",
+        ])
+        .unwrap();
+        let root = load_dictionaries(&cli.dict, &[]).unwrap();
+        std::fs::remove_file(&dict).unwrap();
+
+        let line = batch_line::<4, 4>(&cli, &root, "abcdefghijklmnop").unwrap();
+        assert!(!line.contains('\n'));
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["results"][0]["word"], "fab");
+        assert_eq!(json["results"].as_array().unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn generated_boards() {
+        let generate_with = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["ruzzle-solver", "generate"].iter().chain(args));
+            match cli.unwrap().command {
+                Some(Command::Generate(args)) => generate(&args).unwrap(),
+                command => panic!("parsed as {:?}", command),
+            }
+        };
+        let boards = generate_with(&["-n", "3", "--seed", "42"]);
+        assert_eq!(boards.len(), 3);
+        assert_eq!(boards, generate_with(&["-n", "3", "--seed", "42"]));
+        assert_ne!(boards, generate_with(&["-n", "3", "--seed", "43"]));
+        for board in &boards {
+            assert!(parse_board::<4, 4>(board).is_ok());
+        }
+        let boggle = generate_with(&["--size", "5x5", "--seed", "42"]);
+        assert!(parse_board::<5, 5>(&boggle[0]).is_ok());
+
+        let dict = temp_dict("generate", "ab\nba\nfab\nabe\nbe\n");
+        let summaries =
+            generate_with(&["-n", "3", "--seed", "42", "--dict", dict.to_str().unwrap()]);
+        std::fs::remove_file(&dict).unwrap();
+        for (summary, board) in summaries.iter().zip(&boards) {
+            let fields: Vec<&str> = summary.split(' ').collect();
+            assert_eq!(fields.len(), 3);
+            assert_eq!(fields[0], board);
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn optimized_board() {
+        let dict = temp_dict("optimize", "at\ntea\neat\nate\nseat\neast\nrates\n");
+        let optimize_with = |args: &[&str]| {
+            let dict = ["--dict", dict.to_str().unwrap()];
+            let args = ["ruzzle-solver", "optimize"]
+                .iter()
+                .chain(&dict)
+                .chain(args);
+            match Cli::try_parse_from(args).unwrap().command {
+                Some(Command::Optimize(args)) => optimize(&args).unwrap(),
+                command => panic!("parsed as {:?}", command),
+            }
+        };
+        let line = optimize_with(&["--iterations", "500", "--seed", "3"]);
+        assert_eq!(line, optimize_with(&["--iterations", "500", "--seed", "3"]));
+        let boggle = optimize_with(&["--iterations", "5", "--size", "5x5"]);
+        std::fs::remove_file(&dict).unwrap();
+
+        let (board, score) = line.split_once(' ').unwrap();
+        assert!(parse_board::<4, 4>(board).is_ok());
+        assert!(score.parse::<u32>().unwrap() > 0);
+        assert!(parse_board::<5, 5>(boggle.split(' ').next().unwrap()).is_ok());
+
+        let args = [
+            "ruzzle-solver",
+            "optimize",
+            "--dict",
+            "w",
+            "--iterations",
+            "5",
+        ];
+        assert!(Cli::try_parse_from(args.iter().chain(&["--seconds", "1"])).is_err());
+        let args = ["ruzzle-solver", "optimize", "--dict", "w"];
+        for bad in [
+            ["--seconds=-1"],
+            ["--seconds=1e30"],
+            ["--seconds=NaN"],
+            ["--progress=0"],
+        ] {
+            assert!(Cli::try_parse_from(args.iter().chain(&bad)).is_err());
+        }
+        let cli = Cli::try_parse_from(args.iter().chain(&["--seconds", "0.5"])).unwrap();
+        match cli.command {
+            Some(Command::Optimize(args)) => {
+                assert_eq!(args.seconds, Some(Duration::from_millis(500)))
+            }
+            command => panic!("parsed as {:?}", command),
+        }
+    }
+
+    #[test]
+    fn found_words_report() {
+        let dict = temp_dict("found-dict", "ab\nfab\nabe\nbe\nzoo\n");
+        let found = temp_dict("found", "FAB\nbe\n\nzoo\nbef\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--found-words",
+            found.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(&cli).unwrap();
+        let root = load_dictionaries(&cli.dict, &[]).unwrap();
+        std::fs::remove_file(&dict).unwrap();
+        std::fs::remove_file(&found).unwrap();
+
+        let board = parse_board::<4, 4>("abcdefghijklmnop").unwrap();
+        let found = ["FAB", "be", "", "zoo", "bef"].map(String::from);
+        let report = analyze(&found, &root, &board, &solve_options(&cli));
+        assert_eq!(
+            missed_report(&report, None),
+            "Found 2 words for 14 points:\n   9 fab\n   5 be\n\
+             Not on the board: zoo\n\
+             Not in the dictionary: bef\n\
+             Missed 2 words for 11 points:\n   6 abe\n   5 ab\n"
+        );
+        assert!(missed_report(&report, Some(1)).ends_with("points:\n   6 abe\n"));
+
+        let missing = Cli {
+            found_words: Some(PathBuf::from("/nonexistent/found.txt")),
+            ..cli
+        };
+        assert!(matches!(run(&missing), Err(CliError::FoundWords { .. })));
+    }
+
+    #[test]
+    fn disjoint_output() {
+        let dict = temp_dict("disjoint", "ab\nfab\nabe\nop\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+            "--disjoint",
+        ])
+        .unwrap();
+        let board = parse_board::<4, 4>("abcdefghijklmnop").unwrap();
+        let results = solve_board(&cli, &board);
+        std::fs::remove_file(&dict).unwrap();
+
+        assert_eq!(
+            disjoint_words(&board, &results.unwrap()),
+            concat!(
+                "A1 B1  c  d\n",
+                " e F1  g  h\n",
+                " i  j  k  l\n",
+                " m  n O2 P2\n",
+                "\n",
+                " 1.    9 fab\n",
+                " 2.    5 op\n",
+                "Combined score: 14\n",
+            )
+        );
+    }
+
+    #[test]
+    fn explains_best_words() {
+        let dict = temp_dict("explain", "fab\nab\nfabe\n");
+        let args = [
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+        ];
+        let cli = Cli::try_parse_from(args.iter().chain(&["--explain", "2"])).unwrap();
+        assert_eq!(cli.explain, Some(2));
+        let board = parse_board::<4, 4>("abcdefghijklmnop").unwrap();
+        let results = solve_board(&cli, &board);
+        std::fs::remove_file(&dict).unwrap();
+
+        let results = results.unwrap();
+        assert_eq!(
+            explained(&board, &solve_options(&cli), &results[..2]),
+            "  10 fabe\n     f(4) + a(1) + b(4) + e(1) = 10\n   \
+             9 fab\n     f(4) + a(1) + b(4) = 9\n"
+        );
+        let both = args.iter().chain(&["--explain", "2", "--words-only"]);
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+
+    #[test]
+    fn bonus_tiles() {
+        let dict = temp_dict("bonuses", "fab\n");
+        let args = [
+            "ruzzle-solver",
+            "abcdefghijklmnop",
+            "--dict",
+            dict.to_str().unwrap(),
+        ];
+        let cli = Cli::try_parse_from(args.iter().chain(&["--bonuses", "t... .D.. .... ...."]));
+        let bad = Cli::try_parse_from(args.iter().chain(&["--bonuses", "t..."])).unwrap();
+        let board = parse_board::<4, 4>("abcdefghijklmnop").unwrap();
+        let results = solve_board(&cli.unwrap(), &board);
+        let err = run(&bad).unwrap_err();
+        std::fs::remove_file(&dict).unwrap();
+
+        // (f 4 + a 1 × 3 + b 4) × 2.
+        assert_eq!(results.unwrap()[0].score, 22);
+        assert!(matches!(err, CliError::Bonuses(_)));
+        assert_eq!(err.to_string(), "invalid bonuses: expected 16 tiles, got 4");
+        let both = args.iter().chain(&["--bonuses", "....", "--interactive"]);
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+
+    #[test]
+    fn anagram_output() {
+        let dict = temp_dict("anagram", "setter\nstreet\nteeter\ntree\nsee\n");
+        let cli = Cli::try_parse_from([
+            "ruzzle-solver",
+            "--anagram",
+            "SET TER",
+            "--dict",
+            dict.to_str().unwrap(),
+        ])
+        .unwrap();
+        let output = anagrams(&cli);
+        std::fs::remove_file(&dict).unwrap();
+        assert_eq!(
+            output.unwrap(),
+            "  16 setter\n  16 street\n   4 tree\n   3 see\n"
+        );
+
+        let both = ["ruzzle-solver", "abcd", "--anagram", "abcd", "--dict", "d"];
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+}
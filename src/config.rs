@@ -0,0 +1,312 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Where to find the dictionary when --dict isn't given: paths separated as
+// in PATH, e.g. RUZZLE_DICT=~/words/sowpods.txt:~/words/extra.txt.
+pub const DICT_VAR: &str = "RUZZLE_DICT";
+
+// The settings file, under $XDG_CONFIG_HOME or ~/.config.
+const CONFIG_FILE: &str = "ruzzle-solver/config.toml";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    // None of --dict, DICT_VAR or the config file named a dictionary.
+    NoDictionary {
+        config: Option<PathBuf>, // None without a home directory to look in.
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Read { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            ConfigError::Invalid {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ConfigError::NoDictionary { config } => {
+                write!(
+                    f,
+                    "no dictionary: looked for --dict, then {}, then ",
+                    DICT_VAR
+                )?;
+                match config {
+                    Some(path) => write!(f, "a dictionary key in {}", path.display()),
+                    None => write!(
+                        f,
+                        "a config file, but neither XDG_CONFIG_HOME nor HOME is set"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+// The settings of the config file. Keys it doesn't know are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    pub dictionary: Option<String>,
+}
+
+// The dictionaries to use: those given with --dict, or else those of
+// DICT_VAR, or else the config file's. env and read_file stand in for
+// std::env::var_os() and std::fs::read_to_string(). With the embedded-dict
+// feature, finding none is fine and gives none; otherwise it's an error.
+pub fn resolve_dictionaries(
+    flag: &[PathBuf],
+    env: &dyn Fn(&str) -> Option<OsString>,
+    read_file: &dyn Fn(&Path) -> io::Result<String>,
+) -> Result<Vec<PathBuf>, ConfigError> {
+    if !flag.is_empty() {
+        return Ok(flag.to_vec());
+    }
+    if let Some(paths) = env(DICT_VAR).filter(|paths| !paths.is_empty()) {
+        return Ok(std::env::split_paths(&paths)
+            .map(|path| expand_home(&path.to_string_lossy(), env))
+            .collect());
+    }
+    let path = config_path(env);
+    if let Some(path) = &path {
+        let text = match read_file(path) {
+            Ok(text) => Some(text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.clone(),
+                    source,
+                });
+            }
+        };
+        let config = text.map(|text| parse_config(&text)).transpose();
+        let config = config.map_err(|(line, message)| ConfigError::Invalid {
+            path: path.clone(),
+            line,
+            message,
+        })?;
+        if let Some(dictionary) = config.and_then(|config| config.dictionary) {
+            return Ok(vec![expand_home(&dictionary, env)]);
+        }
+    }
+    if cfg!(feature = "embedded-dict") {
+        Ok(vec![])
+    } else {
+        Err(ConfigError::NoDictionary { config: path })
+    }
+}
+
+pub fn config_path(env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let dir = env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join(CONFIG_FILE))
+}
+
+// "~/words.txt" as a path under HOME, if it's set.
+fn expand_home(path: &str, env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    match (path.strip_prefix("~/"), env("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+// Just enough TOML for the config file: top level `key = "string"` lines,
+// with '#' comments and blank lines. Strings are basic ("..." with \\, \"
+// \n and \t escapes) or literal ('...'). Errors come with their line number.
+pub fn parse_config(text: &str) -> Result<Config, (usize, String)> {
+    let mut config = Config::default();
+    let mut in_table = false;
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |message: String| (n + 1, message);
+        if line.starts_with('[') {
+            // Keys of tables aren't ours.
+            in_table = true;
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err(format!("expected key = value, got {:?}", line)))?;
+        if in_table || key.trim() != "dictionary" {
+            continue;
+        }
+        config.dictionary = Some(parse_string(value.trim()).map_err(err)?);
+    }
+    Ok(config)
+}
+
+// A string value, with nothing after it but maybe a comment.
+fn parse_string(value: &str) -> Result<String, String> {
+    let mut chars = value.chars();
+    let quote = chars.next().filter(|&ch| ch == '"' || ch == '\'');
+    let Some(quote) = quote else {
+        return Err(format!("expected a quoted string, got {}", value));
+    };
+    let mut out = String::new();
+    loop {
+        match chars.next() {
+            None => return Err(format!("unterminated string {}", value)),
+            Some(ch) if ch == quote => break,
+            Some('\\') if quote == '"' => match chars.next() {
+                Some('\\') => out.push('\\'),
+                Some('"') => out.push('"'),
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                other => {
+                    let escape: String = other.into_iter().collect();
+                    return Err(format!("unsupported escape \\{}", escape));
+                }
+            },
+            Some(ch) => out.push(ch),
+        }
+    }
+    let rest = chars.as_str().trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected {} after the string", rest));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(
+        flag: &[&str],
+        vars: &[(&str, &str)],
+        files: &[(&str, &str)],
+    ) -> Result<Vec<PathBuf>, ConfigError> {
+        let vars: HashMap<&str, OsString> = vars
+            .iter()
+            .map(|&(name, value)| (name, OsString::from(value)))
+            .collect();
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|&(path, text)| (PathBuf::from(path), text.to_string()))
+            .collect();
+        let flag: Vec<PathBuf> = flag.iter().map(PathBuf::from).collect();
+        resolve_dictionaries(&flag, &|name| vars.get(name).cloned(), &|path| {
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        })
+    }
+
+    const CONFIG: &str = "/home/me/.config/ruzzle-solver/config.toml";
+
+    #[test]
+    fn resolves_in_order() {
+        let home = ("HOME", "/home/me");
+        let config = (CONFIG, "dictionary = \"~/words/sowpods.txt\"\n");
+        let var = (DICT_VAR, "/a.txt:~/b.txt");
+
+        let dicts = resolve(&["flag.txt"], &[home, var], &[config]).unwrap();
+        assert_eq!(dicts, [PathBuf::from("flag.txt")]);
+        let dicts = resolve(&[], &[home, var], &[config]).unwrap();
+        assert_eq!(
+            dicts,
+            [PathBuf::from("/a.txt"), PathBuf::from("/home/me/b.txt")]
+        );
+        let dicts = resolve(&[], &[home, (DICT_VAR, "")], &[config]).unwrap();
+        assert_eq!(dicts, [PathBuf::from("/home/me/words/sowpods.txt")]);
+
+        let xdg = ("XDG_CONFIG_HOME", "/xdg");
+        let xdg_config = ("/xdg/ruzzle-solver/config.toml", "dictionary = 'x.txt'");
+        let dicts = resolve(&[], &[home, xdg], &[config, xdg_config]).unwrap();
+        assert_eq!(dicts, [PathBuf::from("x.txt")]);
+    }
+
+    #[cfg(not(feature = "embedded-dict"))]
+    #[test]
+    fn names_where_it_looked() {
+        let home = ("HOME", "/home/me");
+        let err = resolve(&[], &[home], &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "no dictionary: looked for --dict, then RUZZLE_DICT, then a dictionary key in {}",
+                CONFIG
+            )
+        );
+        let err = resolve(&[], &[home], &[(CONFIG, "# nothing yet\n")]).unwrap_err();
+        assert!(matches!(err, ConfigError::NoDictionary { config: Some(_) }));
+        let err = resolve(&[], &[], &[]).unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with("neither XDG_CONFIG_HOME nor HOME is set")
+        );
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn falls_back_to_embedded() {
+        assert!(
+            resolve(&[], &[("HOME", "/home/me")], &[])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn reports_bad_config() {
+        let err = resolve(
+            &[],
+            &[("HOME", "/home/me")],
+            &[(CONFIG, "\ndictionary = words\n")],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("{}:2: expected a quoted string, got words", CONFIG)
+        );
+    }
+
+    #[test]
+    fn parses_config() {
+        let text = "# Defaults\n\
+                    \n\
+                    theme = \"dark\"\n\
+                    dictionary = \"C:\\\\words\\\\\\\"tw\\\"l.txt\" # comment\n\
+                    [other]\n\
+                    dictionary = \"not this one\"\n";
+        assert_eq!(
+            parse_config(text).unwrap().dictionary.as_deref(),
+            Some("C:\\words\\\"tw\"l.txt")
+        );
+        assert_eq!(parse_config("").unwrap(), Config::default());
+        assert_eq!(
+            parse_config("dictionary = 'a\\b.txt'")
+                .unwrap()
+                .dictionary
+                .as_deref(),
+            Some("a\\b.txt")
+        );
+        for (text, message) in [
+            ("dictionary", "expected key = value, got \"dictionary\""),
+            ("dictionary = \"a", "unterminated string \"a"),
+            ("dictionary = \"a\" b", "unexpected b after the string"),
+            ("dictionary = \"\\x\"", "unsupported escape \\x"),
+        ] {
+            assert_eq!(parse_config(text), Err((1, message.to_string())));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::dictionary::{Cursor, Dictionary};
+use crate::trie::TrieNode;
+use std::collections::HashMap;
+
+// A trie with every set of equal subtrees stored once, so that words
+// sharing an ending share its nodes too ("eat", "heat" and "seat" all end
+// in the same "at"). The letters are on the edges, and like ArenaTrie only
+// the words are kept, not their payloads.
+pub struct Dawg {
+    nodes: Vec<DawgNode>,
+    edges: Vec<(char, u32)>, // Each node's edges are contiguous and sorted.
+    root: u32,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct DawgNode {
+    is_terminal: bool,
+    first_edge: u32,
+    edge_count: u32,
+}
+
+impl Dawg {
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn root(&self) -> DawgCursor<'_> {
+        DawgCursor {
+            dawg: self,
+            node: self.root,
+            ch: None,
+        }
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        let mut cursor = self.root();
+        for ch in crate::trie::normalize(word).chars() {
+            match cursor.descend(ch) {
+                Some(child) => cursor = child,
+                None => return false,
+            }
+        }
+        cursor.is_terminal()
+    }
+
+    fn edges_of(&self, node: u32) -> &[(char, u32)] {
+        let node = &self.nodes[node as usize];
+        let first = node.first_edge as usize;
+        &self.edges[first..first + node.edge_count as usize]
+    }
+}
+
+impl TrieNode {
+    // The smallest Dawg with the same words. Subtrees are numbered bottom
+    // up, and one that's equal to one seen before, i.e. as terminal and with
+    // the same letters to the same numbered subtrees, reuses its number.
+    pub fn minimize(&self) -> Dawg {
+        let mut builder = Builder {
+            dawg: Dawg {
+                nodes: vec![],
+                edges: vec![],
+                root: 0,
+            },
+            seen: HashMap::new(),
+        };
+        builder.dawg.root = builder.add(self);
+        builder.dawg
+    }
+}
+
+struct Builder {
+    dawg: Dawg,
+    seen: HashMap<(bool, Vec<(char, u32)>), u32>,
+}
+
+impl Builder {
+    fn add(&mut self, trie_node: &TrieNode) -> u32 {
+        let edges: Vec<(char, u32)> = trie_node
+            .children
+            .iter()
+            .filter_map(|child| Some((child.ch?, self.add(child))))
+            .collect();
+        let key = (trie_node.is_terminal, edges);
+        if let Some(&id) = self.seen.get(&key) {
+            return id;
+        }
+        let id = self.dawg.nodes.len() as u32;
+        self.dawg.nodes.push(DawgNode {
+            is_terminal: key.0,
+            first_edge: self.dawg.edges.len() as u32,
+            edge_count: key.1.len() as u32,
+        });
+        self.dawg.edges.extend_from_slice(&key.1);
+        self.seen.insert(key, id);
+        id
+    }
+}
+
+impl Dictionary for Dawg {
+    type Cursor<'a> = DawgCursor<'a>;
+
+    fn root(&self) -> DawgCursor<'_> {
+        Dawg::root(self)
+    }
+}
+
+// A node can be reached by different letters, so the cursor remembers the
+// one it came by.
+#[derive(Copy, Clone)]
+pub struct DawgCursor<'a> {
+    dawg: &'a Dawg,
+    node: u32,
+    ch: Option<char>,
+}
+
+impl Cursor for DawgCursor<'_> {
+    fn descend(self, ch: char) -> Option<Self> {
+        let edges = self.dawg.edges_of(self.node);
+        let pos = edges.binary_search_by_key(&ch, |&(c, _)| c).ok()?;
+        Some(DawgCursor {
+            dawg: self.dawg,
+            node: edges[pos].1,
+            ch: Some(ch),
+        })
+    }
+
+    fn is_terminal(self) -> bool {
+        self.dawg.nodes[self.node as usize].is_terminal
+    }
+
+    fn ch(self) -> Option<char> {
+        self.ch
+    }
+
+    fn children(self) -> impl Iterator<Item = Self> {
+        self.dawg
+            .edges_of(self.node)
+            .iter()
+            .map(move |&(ch, node)| DawgCursor {
+                dawg: self.dawg,
+                node,
+                ch: Some(ch),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::RuzzleBoard;
+    use crate::solver::{SolveOptions, solve};
+
+    #[test]
+    fn dawg_matches_trie() {
+        let words = [
+            "eat", "heat", "seat", "seats", "heats", "at", "hat", "rust", "trust", "åsna",
+        ];
+        let mut root = TrieNode::new_root();
+        for word in words {
+            root.add_word(word);
+        }
+        let dawg = root.minimize();
+        assert!(dawg.node_count() < root.node_count());
+        for word in words {
+            assert!(dawg.contains_word(word));
+        }
+        for word in [
+            "", "e", "ea", "eats", "hea", "ats", "rusts", "tru", "ås", "x",
+        ] {
+            assert!(!dawg.contains_word(word));
+        }
+        assert!(dawg.contains_word("HEAT"));
+
+        let board = RuzzleBoard::try_from("heatsxxxrustxxxx").unwrap();
+        let opts = SolveOptions::default();
+        assert_eq!(solve(&dawg, &board, &opts), solve(&root, &board, &opts));
+    }
+
+    #[test]
+    fn shares_endings() {
+        let mut root = TrieNode::new_root();
+        root.add_word("cat");
+        root.add_word("bat");
+        assert_eq!(root.node_count(), 7);
+        // The root, then one node each for after "c" or "b", "ca" or "ba"
+        // and "cat" or "bat".
+        assert_eq!(root.minimize().node_count(), 4);
+        assert_eq!(TrieNode::new_root().minimize().node_count(), 1);
+    }
+}
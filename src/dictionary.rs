@@ -0,0 +1,55 @@
+use crate::trie::{Payload, TrieNode};
+
+// Anything the solver can search words in. A dictionary hands out a cursor
+// at its root, and the DFS walks down from there one letter at a time.
+pub trait Dictionary {
+    type Cursor<'a>: Cursor
+    where
+        Self: 'a;
+
+    fn root(&self) -> Self::Cursor<'_>;
+}
+
+// A position in a dictionary, i.e. the prefix spelled so far.
+pub trait Cursor: Copy {
+    fn descend(self, ch: char) -> Option<Self>;
+    fn is_terminal(self) -> bool; // Whether the prefix is a word of its own.
+    fn ch(self) -> Option<char>; // The char leading here. None for the root.
+    fn children(self) -> impl Iterator<Item = Self>; // Every continuation, for wildcards.
+
+    // What's stored with the word ending here, for the solver's results.
+    // Dictionaries without payloads have none.
+    fn payload(self) -> Option<Payload> {
+        None
+    }
+}
+
+impl Dictionary for TrieNode {
+    type Cursor<'a> = &'a TrieNode;
+
+    fn root(&self) -> &TrieNode {
+        self
+    }
+}
+
+impl Cursor for &TrieNode {
+    fn descend(self, ch: char) -> Option<Self> {
+        self.find_in_children(ch)
+    }
+
+    fn is_terminal(self) -> bool {
+        self.is_terminal
+    }
+
+    fn ch(self) -> Option<char> {
+        TrieNode::ch(self)
+    }
+
+    fn children(self) -> impl Iterator<Item = Self> {
+        TrieNode::children(self)
+    }
+
+    fn payload(self) -> Option<Payload> {
+        self.payload.as_deref().cloned()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::mask::BoardMask;
+use crate::solver::SolveResult;
+
+// Up to how many results select_disjoint_best() searches exhaustively.
+// Past this select_disjoint_exact() can take far too long.
+pub const EXACT_MAX_RESULTS: usize = 40;
+
+// Words whose paths share no tile, each word at most once, picked greedily:
+// the highest scoring result that fits with those picked so far, until none
+// does. Highest score first, ties in the order of results.
+pub fn select_disjoint<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Vec<&SolveResult<W, H>> {
+    let mut used = BoardMask::default();
+    let mut chosen: Vec<&SolveResult<W, H>> = vec![];
+    for result in by_score(results) {
+        if fits(&chosen, used, result) {
+            used = used | result.mask();
+            chosen.push(result);
+        }
+    }
+    chosen
+}
+
+// Like select_disjoint(), but the set with the highest combined score, by a
+// branch and bound search. Exponential in the number of results at worst.
+pub fn select_disjoint_exact<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Vec<&SolveResult<W, H>> {
+    let candidates = by_score(results);
+    // The most the candidates from each position on could add.
+    let mut remaining = vec![0; candidates.len() + 1];
+    for i in (0..candidates.len()).rev() {
+        remaining[i] = remaining[i + 1] + candidates[i].score;
+    }
+    let greedy = select_disjoint(results);
+    let mut search = Search {
+        candidates: &candidates,
+        remaining: &remaining,
+        chosen: vec![],
+        best_score: score(&greedy),
+        best: greedy,
+    };
+    search.branch(0, BoardMask::default(), 0);
+    search.best
+}
+
+// select_disjoint_exact() if there are few enough results for it, otherwise
+// select_disjoint().
+pub fn select_disjoint_best<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Vec<&SolveResult<W, H>> {
+    if results.len() <= EXACT_MAX_RESULTS {
+        select_disjoint_exact(results)
+    } else {
+        select_disjoint(results)
+    }
+}
+
+fn by_score<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Vec<&SolveResult<W, H>> {
+    let mut sorted: Vec<&SolveResult<W, H>> = results.iter().collect();
+    sorted.sort_by_key(|result| std::cmp::Reverse(result.score));
+    sorted
+}
+
+fn fits<const W: usize, const H: usize>(
+    chosen: &[&SolveResult<W, H>],
+    used: BoardMask<W, H>,
+    result: &SolveResult<W, H>,
+) -> bool {
+    (used & result.mask()).count_set() == 0 && chosen.iter().all(|c| c.word != result.word)
+}
+
+fn score<const W: usize, const H: usize>(chosen: &[&SolveResult<W, H>]) -> u32 {
+    chosen.iter().map(|result| result.score).sum()
+}
+
+struct Search<'a, 'r, const W: usize, const H: usize> {
+    candidates: &'a [&'r SolveResult<W, H>], // Highest score first.
+    remaining: &'a [u32],
+    chosen: Vec<&'r SolveResult<W, H>>,
+    best: Vec<&'r SolveResult<W, H>>,
+    best_score: u32,
+}
+
+impl<'r, const W: usize, const H: usize> Search<'_, 'r, W, H> {
+    fn branch(&mut self, next: usize, used: BoardMask<W, H>, chosen_score: u32) {
+        if chosen_score > self.best_score {
+            self.best = self.chosen.clone();
+            self.best_score = chosen_score;
+        }
+        for i in next..self.candidates.len() {
+            // Nothing from here on can beat the best.
+            if chosen_score + self.remaining[i] <= self.best_score {
+                return;
+            }
+            let result = self.candidates[i];
+            if fits(&self.chosen, used, result) {
+                self.chosen.push(result);
+                self.branch(i + 1, used | result.mask(), chosen_score + result.score);
+                self.chosen.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Index4x4;
+
+    fn result(word: &str, path: &[(usize, usize)], score: u32) -> SolveResult {
+        SolveResult {
+            word: word.to_string(),
+            path: path.iter().map(|&(x, y)| Index4x4::from_xy(x, y)).collect(),
+            score,
+            wildcard_bindings: vec![],
+            payload: None,
+        }
+    }
+
+    fn words<'a>(chosen: &[&'a SolveResult]) -> Vec<&'a str> {
+        chosen.iter().map(|result| result.word.as_str()).collect()
+    }
+
+    #[test]
+    fn greedy_and_exact_differ() {
+        // "big" blocks both "left" and "right", which together score more.
+        let results = [
+            result("left", &[(0, 0), (0, 1)], 6),
+            result("big", &[(0, 0), (1, 0), (2, 0)], 10),
+            result("right", &[(2, 0), (3, 0)], 6),
+            result("far", &[(3, 3), (2, 3)], 1),
+        ];
+        let greedy = select_disjoint(&results);
+        assert_eq!(words(&greedy), ["big", "far"]);
+        assert_eq!(score(&greedy), 11);
+
+        let exact = select_disjoint_exact(&results);
+        assert_eq!(words(&exact), ["left", "right", "far"]);
+        assert_eq!(score(&exact), 13);
+        assert_eq!(select_disjoint_best(&results), exact);
+    }
+
+    #[test]
+    fn each_word_once() {
+        let results = [
+            result("ab", &[(0, 0), (1, 0)], 5),
+            result("ab", &[(3, 3), (2, 3)], 5),
+            result("ba", &[(1, 1), (0, 1)], 4),
+        ];
+        assert_eq!(words(&select_disjoint(&results)), ["ab", "ba"]);
+        assert_eq!(words(&select_disjoint_exact(&results)), ["ab", "ba"]);
+        assert!(select_disjoint_exact::<4, 4>(&[]).is_empty());
+    }
+}
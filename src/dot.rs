@@ -0,0 +1,103 @@
+use crate::trie::TrieNode;
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+impl TrieNode {
+    // The trie as a Graphviz digraph, for `dot -Tsvg`: a node per trie node,
+    // numbered breadth first from n0 for the root, each edge labelled with
+    // its letter and the ends of words double circled. Only the first
+    // max_nodes nodes are drawn; a parent with children past those gets an
+    // edge to a "…" node instead.
+    pub fn to_dot(&self, max_nodes: usize) -> String {
+        let mut out = String::from("digraph trie {\n    node [shape=circle, label=\"\"];\n");
+        let mut queue: VecDeque<(&TrieNode, usize)> = VecDeque::new();
+        let mut count = 0;
+        let mut truncated = max_nodes == 0;
+        if max_nodes > 0 {
+            queue.push_back((self, 0));
+            count = 1;
+            write_node(&mut out, self, 0);
+        }
+        while let Some((node, id)) = queue.pop_front() {
+            for child in node.children() {
+                if count == max_nodes {
+                    writeln!(out, "    n{} -> more;", id).unwrap();
+                    truncated = true;
+                    break;
+                }
+                write_node(&mut out, child, count);
+                let label = child.ch().map(escape).unwrap_or_default();
+                writeln!(out, "    n{} -> n{} [label=\"{}\"];", id, count, label).unwrap();
+                queue.push_back((child, count));
+                count += 1;
+            }
+        }
+        if truncated {
+            out.push_str("    more [shape=none, label=\"…\"];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn write_node(out: &mut String, node: &TrieNode, id: usize) {
+    if node.is_terminal {
+        writeln!(out, "    n{} [shape=doublecircle];", id).unwrap();
+    } else {
+        writeln!(out, "    n{};", id).unwrap();
+    }
+}
+
+fn escape(ch: char) -> String {
+    match ch {
+        '"' | '\\' => format!("\\{}", ch),
+        ch => ch.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict() -> TrieNode {
+        let mut root = TrieNode::new_root();
+        for word in ["at", "ate", "an", "be"] {
+            root.add_word(word);
+        }
+        root
+    }
+
+    #[test]
+    fn whole_trie() {
+        assert_eq!(
+            dict().to_dot(100),
+            "digraph trie {\n    \
+             node [shape=circle, label=\"\"];\n    \
+             n0;\n    \
+             n1;\n    \
+             n0 -> n1 [label=\"a\"];\n    \
+             n2;\n    \
+             n0 -> n2 [label=\"b\"];\n    \
+             n3 [shape=doublecircle];\n    \
+             n1 -> n3 [label=\"n\"];\n    \
+             n4 [shape=doublecircle];\n    \
+             n1 -> n4 [label=\"t\"];\n    \
+             n5 [shape=doublecircle];\n    \
+             n2 -> n5 [label=\"e\"];\n    \
+             n6 [shape=doublecircle];\n    \
+             n4 -> n6 [label=\"e\"];\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        // n0 to n3 as above, then "at", "be" and "ate" left out.
+        let dot = dict().to_dot(4);
+        assert!(dot.contains("n3 [shape=doublecircle];"));
+        assert!(!dot.contains("n4"));
+        assert!(dot.contains("    n1 -> more;\n    n2 -> more;\n"));
+        assert!(dot.ends_with("    more [shape=none, label=\"…\"];\n}\n"));
+        assert!(!dict().to_dot(7).contains("more"));
+    }
+}
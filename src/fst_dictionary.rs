@@ -0,0 +1,188 @@
+use crate::dictionary::{Cursor, Dictionary};
+use crate::trie::{normalize, open_word_file};
+use fst::Set;
+use fst::raw::{Fst, Node};
+use std::io::{self, BufRead};
+use std::path::Path;
+
+// A dictionary stored as an fst::Set, which takes far less memory than a
+// TrieNode for large word lists. The fst works on UTF-8 bytes, so a cursor
+// steps over every byte of a char at once.
+pub struct FstDictionary {
+    set: Set<Vec<u8>>,
+}
+
+impl FstDictionary {
+    pub fn from_words<I: IntoIterator<Item = S>, S: AsRef<str>>(words: I) -> FstDictionary {
+        // fst wants its keys sorted and unique.
+        let mut words: Vec<String> = words
+            .into_iter()
+            .map(|word| normalize(word.as_ref()).into_owned())
+            .filter(|word| !word.is_empty())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+        let set = Set::from_iter(words).expect("words are sorted and unique");
+        FstDictionary { set }
+    }
+
+    pub fn from_word_file(path: &Path) -> io::Result<FstDictionary> {
+        let mut words = vec![];
+        for line in open_word_file(path)?.lines() {
+            let line = line?;
+            let word = line.trim();
+            if !word.is_empty() {
+                words.push(word.to_string());
+            }
+        }
+        Ok(FstDictionary::from_words(words))
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        self.set.contains(normalize(word).as_bytes())
+    }
+}
+
+impl Dictionary for FstDictionary {
+    type Cursor<'a> = FstCursor<'a>;
+
+    fn root(&self) -> FstCursor<'_> {
+        let fst = self.set.as_fst();
+        FstCursor {
+            fst,
+            node: fst.root(),
+            ch: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct FstCursor<'a> {
+    fst: &'a Fst<Vec<u8>>,
+    node: Node<'a>,
+    ch: Option<char>,
+}
+
+impl<'a> FstCursor<'a> {
+    // Pushes a cursor for every complete char reachable from node, having
+    // already read buf[..len] of its encoding.
+    fn collect_chars(self, node: Node<'a>, buf: &mut [u8; 4], len: usize, out: &mut Vec<Self>) {
+        for transition in node.transitions() {
+            buf[len] = transition.inp;
+            let next = self.fst.node(transition.addr);
+            match std::str::from_utf8(&buf[..=len]) {
+                Ok(s) => out.push(FstCursor {
+                    fst: self.fst,
+                    node: next,
+                    ch: s.chars().next(),
+                }),
+                // An incomplete char, so keep reading.
+                Err(err) if err.error_len().is_none() && len < 3 => {
+                    self.collect_chars(next, buf, len + 1, out)
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+impl Cursor for FstCursor<'_> {
+    fn descend(self, ch: char) -> Option<Self> {
+        let mut node = self.node;
+        for &byte in ch.encode_utf8(&mut [0; 4]).as_bytes() {
+            let i = node.find_input(byte)?;
+            node = self.fst.node(node.transition_addr(i));
+        }
+        Some(FstCursor {
+            fst: self.fst,
+            node,
+            ch: Some(ch),
+        })
+    }
+
+    fn is_terminal(self) -> bool {
+        self.node.is_final()
+    }
+
+    fn ch(self) -> Option<char> {
+        self.ch
+    }
+
+    fn children(self) -> impl Iterator<Item = Self> {
+        // Transitions are sorted by byte, which keeps chars in order too.
+        let mut out = vec![];
+        self.collect_chars(self.node, &mut [0; 4], 0, &mut out);
+        out.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::RuzzleBoard;
+    use crate::solver::{SolveOptions, SolveResult, solve};
+    use crate::trie::TrieNode;
+    use std::collections::BTreeSet;
+
+    const WORDS: [&str; 12] = [
+        "ab", "ba", "fab", "jin", "kop", "abfe", "ata", "plonk", "quiz", "zat", "Åsa", "såg",
+    ];
+
+    fn words(results: Vec<SolveResult>) -> BTreeSet<String> {
+        results.into_iter().map(|result| result.word).collect()
+    }
+
+    #[test]
+    fn contains_words() {
+        let dict = FstDictionary::from_words(WORDS);
+        assert_eq!(dict.len(), WORDS.len());
+        for word in WORDS {
+            assert!(dict.contains_word(word));
+        }
+        assert!(!dict.contains_word("a"));
+        assert!(!dict.contains_word("ås"));
+        assert!(dict.root().descend('å').is_some());
+        assert!(!dict.root().descend('å').unwrap().is_terminal());
+    }
+
+    #[test]
+    fn same_words_as_trie() {
+        let path =
+            std::env::temp_dir().join(format!("ruzzle-fst-words-{}.txt", std::process::id()));
+        // Unsorted, with duplicates and mixed case.
+        std::fs::write(
+            &path,
+            WORDS
+                .iter()
+                .rev()
+                .chain(&["AB", "ab"])
+                .map(|w| format!("{w}\n"))
+                .collect::<String>(),
+        )
+        .unwrap();
+        let dict = FstDictionary::from_word_file(&path).unwrap();
+        let root = TrieNode::from_word_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let opts = SolveOptions::default();
+        for board in [
+            "abcdefghijklmnop",
+            "plonkabfeatajinz",
+            "?ataq(u)izxxxxxxxxx",
+            "åsa?gxxxxxxxxxxx",
+        ] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            let expected = words(solve(&root, &board, &opts));
+            assert!(!expected.is_empty());
+            assert_eq!(words(solve(&dict, &board, &opts)), expected);
+        }
+    }
+}
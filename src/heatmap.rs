@@ -0,0 +1,127 @@
+use crate::board::Board;
+use crate::mask::BoardMask;
+use crate::solver::SolveResult;
+use crate::tile::Tile;
+use std::collections::HashMap;
+
+// For each tile, how many different words have a path through it. A word
+// found by several paths counts once on every tile any of them uses.
+pub fn tile_heatmap<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Board<u32, W, H> {
+    heatmap(results, |_| 1)
+}
+
+// Like tile_heatmap(), but each word adds its best score instead of 1.
+pub fn tile_score_heatmap<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> Board<u32, W, H> {
+    heatmap(results, |score| score)
+}
+
+fn heatmap<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+    weight: impl Fn(u32) -> u32,
+) -> Board<u32, W, H> {
+    let mut words: HashMap<&str, (BoardMask<W, H>, u32)> = HashMap::new();
+    for result in results {
+        let (mask, score) = words.entry(&result.word).or_default();
+        *mask = *mask | result.mask();
+        *score = (*score).max(result.score);
+    }
+    let mut heat = Board::filled(0);
+    for (mask, score) in words.values() {
+        for idx in mask.indices() {
+            heat[idx] += weight(*score);
+        }
+    }
+    heat
+}
+
+// The board and the heat of each tile side by side, row by row:
+//
+//  a  b  c  d | 3 4 1 0
+//  e  f  g  h | 2 1 0 0
+//  i  j  k  l | 0 0 0 0
+//  m  n  o qu | 0 0 0 1
+pub fn render_heatmap<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    heat: &Board<u32, W, H>,
+) -> String {
+    let width = |cells: Vec<String>| {
+        cells
+            .iter()
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let tile_width = width(board.iter().map(ToString::to_string).collect());
+    let heat_width = width(heat.iter().map(ToString::to_string).collect());
+    let mut out = String::new();
+    for (tiles, heats) in board.rows().zip(heat.rows()) {
+        let tiles: Vec<String> = tiles
+            .iter()
+            .map(|tile| format!("{:>tile_width$}", tile.to_string()))
+            .collect();
+        let heats: Vec<String> = heats
+            .iter()
+            .map(|heat| format!("{:>heat_width$}", heat))
+            .collect();
+        out.push_str(&format!("{} | {}\n", tiles.join(" "), heats.join(" ")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::RuzzleBoard;
+    use crate::solver::{SolveOptions, solve};
+    use crate::trie::TrieNode;
+
+    #[test]
+    fn counts_words_per_tile() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "abe", "op"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let results = solve(&root, &board, &SolveOptions::default());
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, ["ab", "abe", "ba", "fab", "op"]);
+
+        // a: ab, abe, ba, fab; b: the same; e: abe; f: fab; o, p: op.
+        let heat = tile_heatmap(&results);
+        assert_eq!(
+            heat.into_rows(),
+            [[4, 4, 0, 0], [1, 1, 0, 0], [0, 0, 0, 0], [0, 0, 1, 1]]
+        );
+        // ab 5, abe 6, ba 5, fab 9, op 5.
+        let heat = tile_score_heatmap(&results);
+        assert_eq!(
+            heat.into_rows(),
+            [[25, 25, 0, 0], [6, 9, 0, 0], [0, 0, 0, 0], [0, 0, 5, 5]]
+        );
+        assert_eq!(
+            render_heatmap(&board, &heat),
+            "a b c d | 25 25  0  0\n\
+             e f g h |  6  9  0  0\n\
+             i j k l |  0  0  0  0\n\
+             m n o p |  0  0  5  5\n"
+        );
+    }
+
+    #[test]
+    fn counts_each_word_once() {
+        let mut root = TrieNode::new_root();
+        root.add_word("aa");
+        let board = RuzzleBoard::try_from("aaxxxxxxxxxxxxxx").unwrap();
+        let results = solve(&root, &board, &SolveOptions::default());
+        // (0,0) then (1,0), and back.
+        assert_eq!(results.len(), 2);
+        let heat = tile_heatmap(&results);
+        assert_eq!(heat[0], 1);
+        assert_eq!(heat[1], 1);
+        assert_eq!(heat.iter().sum::<u32>(), 2);
+    }
+}
@@ -0,0 +1,34 @@
+pub mod anagram;
+pub mod analyze;
+pub mod arena;
+pub mod board;
+pub mod dawg;
+pub mod dictionary;
+pub mod disjoint;
+pub mod dot;
+#[cfg(feature = "fst")]
+pub mod fst_dictionary;
+pub mod heatmap;
+pub mod mask;
+#[cfg(feature = "rand")]
+pub mod optimize;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod render;
+pub mod score;
+pub mod serialize;
+pub mod solver;
+pub mod suggest;
+pub mod tile;
+pub mod trie;
+pub mod validate;
+pub mod word_search;
+
+pub use crate::dictionary::{Cursor, Dictionary};
+#[cfg(feature = "parallel")]
+pub use crate::solver::solve_parallel;
+pub use crate::solver::{
+    CancelFlag, Path, PathPolicy, RATINGS, SolveIter, SolveOptions, SolveReport, SolveResult,
+    SolveSummary, Solver, Sort, count_words, group_by_start, group_paths, solve, solve_best_words,
+    solve_grouped, solve_iter, solve_report, solve_unique_words, solve_with,
+};
@@ -0,0 +1,300 @@
+use crate::board::BoardIndex;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not};
+
+// One bit per tile of a WxH board, for boards of up to 64 tiles.
+// Bit order: bit W*H-1 => top left
+//            bit 0     => bottom right
+// so for a 4x4 board the most significant bit of the u16 is the top left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct BoardMask<const W: usize = 4, const H: usize = 4>(u64);
+
+impl<const W: usize, const H: usize> BoardMask<W, H> {
+    const TILES: usize = {
+        assert!(W * H <= 64, "BoardMask holds at most 64 tiles");
+        W * H
+    };
+    const ALL: u64 = u64::MAX >> (64 - Self::TILES);
+
+    const fn bit(idx: BoardIndex<W, H>) -> u64 {
+        1 << (Self::TILES - 1 - idx.flattened)
+    }
+
+    // Every tile set.
+    pub const fn full() -> Self {
+        BoardMask(Self::ALL)
+    }
+
+    // Bits beyond the W*H tiles are dropped.
+    pub const fn from_bits(bits: u64) -> Self {
+        BoardMask(bits & Self::ALL)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn get(&self, idx: BoardIndex<W, H>) -> bool {
+        self.0 & Self::bit(idx) != 0
+    }
+
+    pub fn set(&mut self, idx: BoardIndex<W, H>, value: bool) {
+        if value {
+            self.0 |= Self::bit(idx);
+        } else {
+            self.0 &= !Self::bit(idx);
+        }
+    }
+
+    pub fn with_at(&self, value: bool, idx: BoardIndex<W, H>) -> Self {
+        let mut mask = *self;
+        mask.set(idx, value);
+        mask
+    }
+
+    pub fn count_set(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // The set tile with the lowest flat index, i.e. the first in reading order.
+    pub fn first_set(&self) -> Option<BoardIndex<W, H>> {
+        match self.0 {
+            0 => None,
+            bits => Some(BoardIndex {
+                flattened: bits.leading_zeros() as usize - (64 - Self::TILES),
+            }),
+        }
+    }
+
+    // The set tiles in reading order.
+    pub fn indices(&self) -> impl Iterator<Item = BoardIndex<W, H>> {
+        let mut rest = *self;
+        std::iter::from_fn(move || {
+            let idx = rest.first_set()?;
+            rest.set(idx, false);
+            Some(idx)
+        })
+    }
+
+    pub fn union(&self, other: Self) -> Self {
+        *self | other
+    }
+
+    pub fn intersection(&self, other: Self) -> Self {
+        *self & other
+    }
+
+    pub fn complement(&self) -> Self {
+        !*self
+    }
+
+    pub fn is_subset_of(&self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn from_indices<I: IntoIterator<Item = BoardIndex<W, H>>>(indices: I) -> Self {
+        indices
+            .into_iter()
+            .fold(Self::default(), |mask, idx| mask.with_at(true, idx))
+    }
+}
+
+impl BoardMask {
+    pub fn to_u16(self) -> u16 {
+        self.into()
+    }
+}
+
+// A grid with a '#' for every set tile and a '.' for the rest, laid out
+// like the board.
+impl<const W: usize, const H: usize> fmt::Display for BoardMask<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..H {
+            for x in 0..W {
+                let set = self.get(BoardIndex::from_xy(x, y));
+                write!(f, "{}", if set { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<u16> for BoardMask {
+    fn from(u: u16) -> Self {
+        BoardMask(u64::from(u))
+    }
+}
+
+impl From<BoardMask> for u16 {
+    fn from(mask: BoardMask) -> Self {
+        mask.0 as u16
+    }
+}
+
+impl<const W: usize, const H: usize> BitAnd for BoardMask<W, H> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BoardMask(self.0 & rhs.0)
+    }
+}
+
+impl<const W: usize, const H: usize> BitOr for BoardMask<W, H> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BoardMask(self.0 | rhs.0)
+    }
+}
+
+impl<const W: usize, const H: usize> Not for BoardMask<W, H> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        BoardMask(!self.0 & Self::ALL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Index4x4;
+
+    #[test]
+    fn bit_order() {
+        let mask = BoardMask::from(0b1000_0000_0000_0001);
+        for idx in Index4x4::all_indices_within_bounds() {
+            let corner = idx == Index4x4::from_xy(0, 0) || idx == Index4x4::from_xy(3, 3);
+            assert_eq!(mask.get(idx), corner);
+        }
+
+        let mask = BoardMask::default().with_at(true, Index4x4::from_xy(1, 0));
+        assert_eq!(u16::from(mask), 0b0100_0000_0000_0000);
+        let mask = mask.with_at(true, Index4x4::from_xy(0, 1));
+        assert_eq!(u16::from(mask), 0b0100_1000_0000_0000);
+    }
+
+    #[test]
+    fn set_and_count() {
+        let mut mask = BoardMask::default();
+        assert_eq!(mask.count_set(), 0);
+        mask.set(Index4x4::from_xy(2, 2), true);
+        mask.set(Index4x4::from_xy(3, 1), true);
+        assert_eq!(mask.count_set(), 2);
+        mask.set(Index4x4::from_xy(2, 2), false);
+        assert_eq!(mask.count_set(), 1);
+        assert!(mask.get(Index4x4::from_xy(3, 1)));
+        assert!(!mask.get(Index4x4::from_xy(2, 2)));
+
+        let round_trip = u16::from(BoardMask::from(0xbeef));
+        assert_eq!(round_trip, 0xbeef);
+    }
+
+    #[test]
+    fn first_set() {
+        assert_eq!(BoardMask::<4, 4>::default().first_set(), None);
+        assert_eq!(
+            BoardMask::from(0b0000_0010_0100_0001).first_set(),
+            Some(Index4x4::from_xy(2, 1))
+        );
+        assert_eq!(
+            BoardMask::from(0b1000_0000_0000_0000).first_set(),
+            Some(Index4x4::from_xy(0, 0))
+        );
+        assert_eq!(
+            BoardMask::from(0b0000_0000_0000_0001).first_set(),
+            Some(Index4x4::from_xy(3, 3))
+        );
+    }
+
+    #[test]
+    fn bitwise_operations() {
+        let a = BoardMask::from(0b1100_1100_0000_0000);
+        let b = BoardMask::from(0b1010_1010_0000_0000);
+        assert_eq!(a & b, BoardMask::from(0b1000_1000_0000_0000));
+        assert_eq!(a | b, BoardMask::from(0b1110_1110_0000_0000));
+        assert_eq!(!a, BoardMask::from(0b0011_0011_1111_1111));
+        assert_eq!((!a).count_set(), 16 - a.count_set());
+    }
+
+    #[test]
+    fn indices_in_reading_order() {
+        assert_eq!(BoardMask::<4, 4>::default().indices().count(), 0);
+        let mask = BoardMask::from(0b1000_0000_0010_0001);
+        let indices: Vec<Index4x4> = mask.indices().collect();
+        assert_eq!(
+            indices,
+            [
+                Index4x4::from_xy(0, 0),
+                Index4x4::from_xy(2, 2),
+                Index4x4::from_xy(3, 3)
+            ]
+        );
+        assert_eq!(mask.indices().count() as u32, mask.count_set());
+        assert_eq!(BoardMask::<4, 4>::full().indices().count(), 16);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = BoardMask::from(0b1100_1100_0000_0000);
+        let b = BoardMask::from(0b1010_1010_0000_0000);
+        assert_eq!(a.union(b), a | b);
+        assert_eq!(a.intersection(b), a & b);
+        assert_eq!(a.complement(), !a);
+        assert_eq!(a.intersection(a.complement()), BoardMask::default());
+
+        assert!(a.intersection(b).is_subset_of(a));
+        assert!(a.is_subset_of(a.union(b)));
+        assert!(!a.is_subset_of(b));
+        assert!(BoardMask::default().is_subset_of(a));
+        assert!(a.is_subset_of(!BoardMask::default()));
+    }
+
+    #[test]
+    fn from_indices() {
+        assert_eq!(BoardMask::<4, 4>::from_indices([]), BoardMask::default());
+        let indices = [
+            Index4x4::from_xy(1, 0),
+            Index4x4::from_xy(0, 1),
+            Index4x4::from_xy(1, 0),
+        ];
+        let mask = BoardMask::from_indices(indices);
+        assert_eq!(u16::from(mask), 0b0100_1000_0000_0000);
+        assert_eq!(BoardMask::from_indices(mask.indices()), mask);
+    }
+
+    #[test]
+    fn other_board_sizes() {
+        type Index5x5 = crate::board::BoardIndex<5, 5>;
+        let full = BoardMask::<5, 5>::full();
+        assert_eq!(full.bits(), (1 << 25) - 1);
+        assert_eq!(full.count_set(), 25);
+        assert_eq!((!full).count_set(), 0);
+        assert_eq!(BoardMask::<5, 5>::from_bits(u64::MAX), full);
+
+        let corners = BoardMask::from_indices([Index5x5::from_xy(0, 0), Index5x5::from_xy(4, 4)]);
+        assert_eq!(corners.bits(), 1 << 24 | 1);
+        assert_eq!(corners.first_set(), Some(Index5x5::from_xy(0, 0)));
+        assert_eq!((!corners).first_set(), Some(Index5x5::from_xy(1, 0)));
+        assert_eq!(
+            corners.indices().collect::<Vec<_>>(),
+            [Index5x5::from_xy(0, 0), Index5x5::from_xy(4, 4)]
+        );
+
+        let full = BoardMask::<8, 8>::full();
+        assert_eq!(full.count_set(), 64);
+        assert_eq!(full.indices().last(), BoardIndex::try_from_xy(7, 7));
+    }
+
+    #[test]
+    fn display_grid() {
+        let mask = BoardMask::from(0b1000_0000_0000_0001);
+        assert_eq!(mask.to_string(), "#...\n....\n....\n...#\n");
+        assert_eq!(mask.to_u16(), 0b1000_0000_0000_0001);
+
+        let mask =
+            BoardMask::<5, 2>::from_indices([BoardIndex::from_xy(1, 0), BoardIndex::from_xy(4, 1)]);
+        assert_eq!(mask.to_string(), ".#...\n....#\n");
+    }
+}
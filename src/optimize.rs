@@ -0,0 +1,180 @@
+use crate::board::Board;
+use crate::random::ENGLISH_FREQUENCIES;
+use crate::solver::{SolveOptions, count_words};
+use crate::tile::Tile;
+use crate::trie::TrieNode;
+use rand::Rng;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnnealOptions {
+    pub budget: Budget,
+    // The temperature falls geometrically from start to end over the
+    // budget. At temperature t a change losing d points is kept with
+    // probability e^(-d/t), so these are on the scale of board scores.
+    pub start_temperature: f64,
+    pub end_temperature: f64,
+    pub progress_every: Option<usize>, // Iterations between calls to progress. Some(0) for none.
+}
+
+impl Default for AnnealOptions {
+    fn default() -> Self {
+        AnnealOptions {
+            budget: Budget::Iterations(10_000),
+            start_temperature: 50.0,
+            end_temperature: 0.5,
+            progress_every: None,
+        }
+    }
+}
+
+// The best board seen in the first iterations iterations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimized<const W: usize, const H: usize> {
+    pub board: Board<Tile, W, H>,
+    pub score: u32, // count_words()' total_score.
+    pub iterations: usize,
+}
+
+// Simulated annealing from start, towards boards with a higher total score
+// of every word on them. Each iteration either changes one tile to a letter
+// drawn from ENGLISH_FREQUENCIES or swaps two tiles, and keeps the change if
+// it scores better or, at random, per the temperature. progress is called
+// with the best board so far every opts.progress_every iterations.
+pub fn optimize<const W: usize, const H: usize>(
+    root: &TrieNode,
+    start: Board<Tile, W, H>,
+    solve_opts: &SolveOptions<W, H>,
+    opts: &AnnealOptions,
+    rng: &mut impl Rng,
+    mut progress: impl FnMut(&Optimized<W, H>),
+) -> Optimized<W, H> {
+    let score = |board: &Board<Tile, W, H>| count_words(root, board, solve_opts).total_score;
+    let letters = WeightedIndex::new(ENGLISH_FREQUENCIES.iter().map(|&(_, weight)| weight))
+        .expect("ENGLISH_FREQUENCIES are valid weights");
+    let started = Instant::now();
+    let mut current = (start, score(&start));
+    let mut best = Optimized {
+        board: start,
+        score: current.1,
+        iterations: 0,
+    };
+    for iteration in 1.. {
+        // How much of the budget is used, from 0 to 1.
+        let done = match opts.budget {
+            Budget::Iterations(n) => iteration as f64 / n.max(1) as f64,
+            Budget::Time(limit) => started.elapsed().as_secs_f64() / limit.as_secs_f64(),
+        };
+        if done > 1.0 {
+            break;
+        }
+        let temperature =
+            opts.start_temperature * (opts.end_temperature / opts.start_temperature).powf(done);
+
+        let mut board = current.0;
+        let a = rng.random_range(0..W * H);
+        if rng.random_bool(0.5) {
+            board[a] = Tile::Letter(ENGLISH_FREQUENCIES[letters.sample(rng)].0);
+        } else {
+            let b = (a + rng.random_range(1..W * H)) % (W * H);
+            let tile = board[a];
+            board[a] = board[b];
+            board[b] = tile;
+        }
+        let new_score = score(&board);
+        let loss = current.1 as f64 - new_score as f64;
+        if loss <= 0.0 || rng.random::<f64>() < (-loss / temperature).exp() {
+            current = (board, new_score);
+        }
+        if current.1 > best.score {
+            best.board = current.0;
+            best.score = current.1;
+        }
+        best.iterations = iteration;
+        if opts
+            .progress_every
+            .is_some_and(|every| iteration.is_multiple_of(every))
+        {
+            progress(&best);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::RuzzleBoard;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn tiny_dict() -> TrieNode {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "at", "tea", "eat", "ate", "seat", "east", "rates", "stare", "tears",
+        ] {
+            root.add_word(word);
+        }
+        root
+    }
+
+    #[test]
+    fn never_ends_worse() {
+        let root = tiny_dict();
+        let solve_opts = SolveOptions::default();
+        let opts = AnnealOptions {
+            budget: Budget::Iterations(300),
+            progress_every: Some(100),
+            ..AnnealOptions::default()
+        };
+        let start: RuzzleBoard = "xxxxxxxxxxxxxxat".parse().unwrap();
+        let start_score = count_words(&root, &start, &solve_opts).total_score;
+        let mut reports = vec![];
+        let run = |seed, reports: &mut Vec<usize>| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            optimize(&root, start, &solve_opts, &opts, &mut rng, |best| {
+                reports.push(best.iterations)
+            })
+        };
+        let best = run(9, &mut reports);
+
+        assert!(best.score > start_score);
+        assert_eq!(
+            count_words(&root, &best.board, &solve_opts).total_score,
+            best.score
+        );
+        assert_eq!(best.iterations, 300);
+        assert_eq!(reports, [100, 200, 300]);
+        assert_eq!(run(9, &mut vec![]), best);
+    }
+
+    #[test]
+    fn stops_in_time() {
+        let root = tiny_dict();
+        let opts = AnnealOptions {
+            budget: Budget::Time(Duration::from_millis(50)),
+            ..AnnealOptions::default()
+        };
+        let started = Instant::now();
+        let start: RuzzleBoard = "abcdefghijklmnop".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let best = optimize(
+            &root,
+            start,
+            &SolveOptions::default(),
+            &opts,
+            &mut rng,
+            |_| {},
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(best.iterations > 0);
+    }
+}
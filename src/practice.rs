@@ -0,0 +1,284 @@
+use crate::cli::{
+    Cli, CliError, load_dictionaries, parse_board, parse_bonuses, solve_options, write_results,
+};
+use ruzzle_solver::board::Board;
+use ruzzle_solver::render::render_path;
+use ruzzle_solver::tile::Tile;
+use ruzzle_solver::trie::{TrieNode, normalize};
+use ruzzle_solver::{SolveOptions, SolveResult, SolveSummary, solve_best_words};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How many of the missed words the report lists, best first.
+const MISSED_SHOWN: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    TooShort(usize), // The minimum length.
+    NotOnBoard,
+    NotInDictionary,
+    AlreadyGuessed,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::TooShort(min_len) => write!(f, "shorter than {} letters", min_len),
+            GuessError::NotOnBoard => write!(f, "not on the board"),
+            GuessError::NotInDictionary => write!(f, "not in the dictionary"),
+            GuessError::AlreadyGuessed => write!(f, "already guessed"),
+        }
+    }
+}
+
+// One round: the board being played and the words guessed right so far,
+// each with the score of its best path.
+pub struct Practice<const W: usize, const H: usize> {
+    root: TrieNode,
+    board: Board<Tile, W, H>,
+    opts: SolveOptions<W, H>,
+    found: Vec<(String, u32)>,
+}
+
+impl<const W: usize, const H: usize> Practice<W, H> {
+    pub fn new(root: TrieNode, board: Board<Tile, W, H>, opts: SolveOptions<W, H>) -> Self {
+        Practice {
+            root,
+            board,
+            opts,
+            found: vec![],
+        }
+    }
+
+    // Scores word as the solver would, with its best path on the board.
+    // Wrong guesses cost nothing and aren't remembered.
+    pub fn guess(&mut self, word: &str) -> Result<u32, GuessError> {
+        let word = normalize(word.trim()).into_owned();
+        if self.found.iter().any(|(found, _)| *found == word) {
+            return Err(GuessError::AlreadyGuessed);
+        }
+        if word.chars().count() < self.opts.min_len {
+            return Err(GuessError::TooShort(self.opts.min_len));
+        }
+        let score = self
+            .board
+            .find_paths_for_word(&word)
+            .iter()
+            .map(|path| self.opts.score(&self.board, path, &word))
+            .max()
+            .ok_or(GuessError::NotOnBoard)?;
+        if !self.root.contains_word(&word) {
+            return Err(GuessError::NotInDictionary);
+        }
+        self.found.push((word, score));
+        Ok(score)
+    }
+
+    // For a wrong guess, the words one letter off it which would have
+    // scored: on the board, long enough and not yet found.
+    pub fn did_you_mean(&self, word: &str) -> Vec<String> {
+        self.root
+            .near_matches(word.trim(), 1)
+            .into_iter()
+            .filter(|near| {
+                near.chars().count() >= self.opts.min_len
+                    && !self.found.iter().any(|(found, _)| found == near)
+                    && !self.board.find_paths_for_word(near).is_empty()
+            })
+            .collect()
+    }
+
+    // How the round went, against everything the solver finds.
+    pub fn report(&self) -> Report<W, H> {
+        let opts = SolveOptions {
+            limit: None,
+            ..self.opts.clone()
+        };
+        let all = solve_best_words(&self.root, &self.board, &opts);
+        let missed = all
+            .iter()
+            .filter(|result| !self.found.iter().any(|(word, _)| *word == result.word))
+            .take(MISSED_SHOWN)
+            .cloned()
+            .collect();
+        Report {
+            score: self.found.iter().map(|(_, score)| score).sum(),
+            found: self.found.len(),
+            summary: SolveSummary::from_results(&all),
+            missed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<const W: usize, const H: usize> {
+    pub score: u32,
+    pub found: usize,
+    pub summary: SolveSummary,          // Of every word on the board.
+    pub missed: Vec<SolveResult<W, H>>, // The best MISSED_SHOWN, in the solver's order.
+}
+
+impl<const W: usize, const H: usize> fmt::Display for Report<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "You scored {} of a possible {}, with {} of {} words",
+            self.score, self.summary.total_score, self.found, self.summary.unique_words
+        )?;
+        match self.summary.rating(self.score) {
+            Some(rating) => writeln!(f, ": {}!", rating)?,
+            None => writeln!(f, ".")?,
+        }
+        write!(f, "{}", self.summary)?;
+        if !self.missed.is_empty() {
+            writeln!(f, "Best words you missed:")?;
+            let mut results = vec![];
+            write_results(&mut results, &self.missed).map_err(|_| fmt::Error)?;
+            f.write_str(&String::from_utf8_lossy(&results))?;
+        }
+        Ok(())
+    }
+}
+
+// Shows the board and takes guesses from stdin, one per line, until the time
+// is up or stdin ends, then prints the report. Stdin is read on its own
+// thread so that the time can run out while waiting for a guess.
+pub fn run<const W: usize, const H: usize>(cli: &Cli) -> Result<(), CliError> {
+    let board = parse_board::<W, H>(cli.board.as_deref().unwrap_or_default())?;
+    parse_bonuses::<W, H>(cli)?;
+    let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+    print!("{}", render_path(&board, &vec![]));
+    println!("You have {} seconds. Type a word per line.", cli.time);
+    let mut practice = Practice::new(root, board, solve_options(cli));
+
+    let (lines, guesses) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            if lines.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let deadline = deadline(cli.time);
+    loop {
+        let guess = match deadline {
+            Some(deadline) => {
+                guesses.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => guesses.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let line = match guess {
+            Ok(line) => line.map_err(CliError::Input)?,
+            Err(RecvTimeoutError::Timeout) => {
+                println!("Time's up!");
+                break;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let word = line.trim();
+        if word.is_empty() {
+            continue;
+        }
+        match practice.guess(word) {
+            Ok(score) => println!("+{} {}", score, word),
+            Err(err @ (GuessError::NotOnBoard | GuessError::NotInDictionary)) => {
+                match practice.did_you_mean(word).as_slice() {
+                    [] => println!("{}: {}", word, err),
+                    near => println!("{}: {} (did you mean {}?)", word, err, near.join(", ")),
+                }
+            }
+            Err(err) => println!("{}: {}", word, err),
+        }
+    }
+    print!("{}", practice.report());
+    Ok(())
+}
+
+// When --time seconds from now is up, or None if that's too far off to
+// tell, which is as good as no time limit.
+fn deadline(seconds: u64) -> Option<Instant> {
+    Instant::now().checked_add(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruzzle_solver::Sort;
+
+    fn practice() -> Practice<4, 4> {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "abe", "be"] {
+            root.add_word(word);
+        }
+        let opts = SolveOptions {
+            sort: Sort::ByScore,
+            ..SolveOptions::default()
+        };
+        Practice::new(root, "abcdefghijklmnop".parse().unwrap(), opts)
+    }
+
+    #[test]
+    fn scores_guesses() {
+        let mut practice = practice();
+        assert_eq!(practice.guess("fab"), Ok(9));
+        assert_eq!(practice.guess("FAB"), Err(GuessError::AlreadyGuessed));
+        assert_eq!(practice.guess("bef"), Err(GuessError::NotInDictionary));
+        assert_eq!(practice.guess("zoo"), Err(GuessError::NotOnBoard));
+        assert_eq!(practice.guess("a"), Err(GuessError::TooShort(2)));
+        assert_eq!(practice.guess(" ab\n"), Ok(5));
+        assert_eq!(practice.found.len(), 2);
+    }
+
+    #[test]
+    fn suggests_near_words() {
+        let mut practice = practice();
+        assert_eq!(practice.did_you_mean("bef"), ["be"]);
+        assert_eq!(practice.did_you_mean("abef"), ["abe"]);
+        assert_eq!(practice.did_you_mean("fob"), ["fab"]);
+        practice.guess("fab").unwrap();
+        assert!(practice.did_you_mean("fob").is_empty());
+        assert!(practice.did_you_mean("zzz").is_empty());
+    }
+
+    #[test]
+    fn reports_missed_words() {
+        let mut practice = practice();
+        practice.guess("fab").unwrap();
+        let report = practice.report();
+        let missed: Vec<&str> = report.missed.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(missed, ["abe", "ab", "be"]);
+        assert_eq!((report.found, report.summary.unique_words), (1, 4));
+        assert_eq!(report.score, 9);
+        assert_eq!(
+            report.summary.total_score,
+            9 + report.missed.iter().map(|r| r.score).sum::<u32>()
+        );
+        assert_eq!(
+            report.to_string(),
+            "You scored 9 of a possible 25, with 1 of 4 words: good!\n\
+             4 words for 25 points\n\
+             Longest word: fab (3 letters)\n\
+             Best word: fab (9 points)\n\
+             Ratings: good 7, great 13, excellent 19\n\
+             Best words you missed:\n   \
+             6 abe\n   \
+             5 ab\n   \
+             5 be\n"
+        );
+        let nothing = Report { score: 6, ..report };
+        assert!(
+            nothing
+                .to_string()
+                .starts_with("You scored 6 of a possible 25, with 1 of 4 words.\n")
+        );
+    }
+
+    #[test]
+    fn deadline_too_far_off() {
+        assert!(deadline(120).is_some_and(|deadline| deadline > Instant::now()));
+        assert_eq!(deadline(u64::MAX), None);
+    }
+}
@@ -0,0 +1,72 @@
+use ruzzle_solver::trie::LoadProgress;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// A line on stderr counting what's been read of a word list, redrawn in
+// place as it loads and cleared when dropped.
+pub struct LoadBar {
+    name: String,
+    frame: usize,
+}
+
+impl LoadBar {
+    // None unless stdout and stderr are both terminals, so that output
+    // piped elsewhere or logged doesn't get it.
+    pub fn new(path: &Path) -> Option<LoadBar> {
+        if !io::stdout().is_terminal() || !io::stderr().is_terminal() {
+            return None;
+        }
+        let name = path.file_name().unwrap_or(path.as_os_str());
+        Some(LoadBar {
+            name: name.to_string_lossy().into_owned(),
+            frame: 0,
+        })
+    }
+
+    pub fn update(&mut self, progress: LoadProgress) {
+        let mut stderr = io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r\x1b[2K{}",
+            render(&self.name, self.frame, progress)
+        );
+        let _ = stderr.flush();
+        self.frame += 1;
+    }
+}
+
+impl Drop for LoadBar {
+    fn drop(&mut self) {
+        eprint!("\r\x1b[2K");
+    }
+}
+
+fn render(name: &str, frame: usize, progress: LoadProgress) -> String {
+    format!(
+        "{} loading {}: {} lines, {} words",
+        SPINNER[frame % SPINNER.len()],
+        name,
+        progress.lines,
+        progress.words
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counts() {
+        let progress = LoadProgress {
+            lines: 20_000,
+            words: 19_650,
+        };
+        assert_eq!(
+            render("sowpods.txt", 0, progress),
+            "⠋ loading sowpods.txt: 20000 lines, 19650 words"
+        );
+        assert!(render("sowpods.txt", 11, progress).starts_with('⠙'));
+    }
+}
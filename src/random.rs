@@ -0,0 +1,157 @@
+use crate::board::{Board, RuzzleBoard};
+use crate::tile::Tile;
+use rand::Rng;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::seq::{IteratorRandom, SliceRandom};
+
+// How often each letter turns up in English text, in percent. Boards drawn
+// from it have about as many vowels, and as few 'q's, as real ones.
+pub const ENGLISH_FREQUENCIES: [(char, f64); 26] = [
+    ('a', 8.17),
+    ('b', 1.49),
+    ('c', 2.78),
+    ('d', 4.25),
+    ('e', 12.70),
+    ('f', 2.23),
+    ('g', 2.02),
+    ('h', 6.09),
+    ('i', 6.97),
+    ('j', 0.15),
+    ('k', 0.77),
+    ('l', 4.03),
+    ('m', 2.41),
+    ('n', 6.75),
+    ('o', 7.51),
+    ('p', 1.93),
+    ('q', 0.10),
+    ('r', 5.99),
+    ('s', 6.33),
+    ('t', 9.06),
+    ('u', 2.76),
+    ('v', 0.98),
+    ('w', 2.36),
+    ('x', 0.15),
+    ('y', 1.97),
+    ('z', 0.07),
+];
+
+impl<const W: usize, const H: usize> Board<Tile, W, H> {
+    // A board of letters drawn independently from ENGLISH_FREQUENCIES.
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self::random_with_distribution(rng, &ENGLISH_FREQUENCIES)
+    }
+
+    // A board of letters drawn independently, each letter as likely as its
+    // weight (which need not add up to anything in particular). Panics if
+    // there are no weights, any is negative or they are all zero.
+    pub fn random_with_distribution(rng: &mut impl Rng, weights: &[(char, f64)]) -> Self {
+        let index = WeightedIndex::new(weights.iter().map(|&(_, weight)| weight))
+            .expect("letter weights must be non-negative and not all zero");
+        Board::from_fn(|_| Tile::Letter(weights[index.sample(rng)].0))
+    }
+}
+
+// The faces of the 16 dice of modern Boggle, 'q' being the "Qu" face.
+pub const BOGGLE_DICE: [&str; 16] = [
+    "aaeegn", "abbjoo", "achops", "affkps", "aoottw", "cimotu", "deilrx", "delrvy", "distty",
+    "eeghnw", "eeinsu", "ehrtvw", "eiosst", "elrtty", "himnuq", "hlnnrz",
+];
+
+// A set of dice, one per tile, rolled into a box like Boggle's: each die
+// lands on a random tile, showing a random face. Letters come out far less
+// independently than from a frequency table, e.g. never more than one 'q'.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Dice(pub [&'static str; 16]);
+
+impl Dice {
+    pub const BOGGLE: Dice = Dice(BOGGLE_DICE);
+
+    pub fn roll(&self, rng: &mut impl Rng) -> RuzzleBoard {
+        let mut dice = self.0;
+        dice.shuffle(rng);
+        RuzzleBoard::from(
+            dice.map(|die| face_tile(die.chars().choose(rng).expect("dice have faces"))),
+        )
+    }
+}
+
+fn face_tile(face: char) -> Tile {
+    match face {
+        'q' => Tile::QU,
+        ch => Tile::Letter(ch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn same_seed_same_boards() {
+        let boards = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            [(); 3].map(|_| RuzzleBoard::random(&mut rng))
+        };
+        assert_eq!(boards(7), boards(7));
+        assert_ne!(boards(7), boards(8));
+        let [first, second, _] = boards(7);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn follows_the_distribution() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let board: Board<Tile, 5, 5> =
+            Board::random_with_distribution(&mut rng, &[('x', 1.0), ('y', 0.0)]);
+        assert!(board.iter().all(|&tile| tile == 'x'));
+
+        let mut counts = [0; 26];
+        for _ in 0..200 {
+            for tile in &RuzzleBoard::random(&mut rng) {
+                let Tile::Letter(ch) = *tile else {
+                    panic!("{:?} is not a letter", tile);
+                };
+                counts[(ch as u8 - b'a') as usize] += 1;
+            }
+        }
+        let count = |ch: char| counts[(ch as u8 - b'a') as usize];
+        assert!(count('e') > count('t') / 2 && count('t') > count('k'));
+        assert!(count('e') > 10 * count('z'));
+    }
+
+    #[test]
+    fn rolls_dice() {
+        let roll = |seed| Dice::BOGGLE.roll(&mut StdRng::seed_from_u64(seed));
+        assert_eq!(roll(3), roll(3));
+        assert_ne!(roll(3), roll(4));
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut qu = 0;
+        for _ in 0..100 {
+            let board = Dice::BOGGLE.roll(&mut rng);
+            for tile in &board {
+                let face = match *tile {
+                    Tile::QU => 'q',
+                    Tile::Letter(ch) => ch,
+                    tile => panic!("{:?} is on no die", tile),
+                };
+                assert!(BOGGLE_DICE.iter().any(|die| die.contains(face)));
+            }
+            // Only one die has a Qu.
+            let count = board.iter().filter(|&&tile| tile == Tile::QU).count();
+            assert!(count <= 1);
+            qu += count;
+        }
+        assert!(qu > 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "letter weights")]
+    fn rejects_empty_distribution() {
+        let mut rng = StdRng::seed_from_u64(1);
+        RuzzleBoard::random_with_distribution(&mut rng, &[]);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::board::Board;
+use crate::mask::BoardMask;
+use crate::solver::Path;
+use crate::tile::Tile;
+
+// Steps after the first cycle through these (yellow, cyan, magenta, blue).
+const STEP_COLOURS: [&str; 4] = ["\x1b[33m", "\x1b[36m", "\x1b[35m", "\x1b[34m"];
+const START_COLOUR: &str = "\x1b[1;32m"; // Bold green.
+const UNUSED_COLOUR: &str = "\x1b[90m"; // Grey.
+const RESET: &str = "\x1b[0m";
+
+// Draws the board with a path traced on it: the start tile bracketed, the
+// following tiles numbered in path order, both uppercase, and every other
+// tile lowercase. Plain text, so it can go to logs.
+pub fn render_path<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    path: &Path<W, H>,
+) -> String {
+    render(board, path, |_, cell| cell)
+}
+
+// The same as render_path(), coloured with ANSI escape codes for terminals:
+// the start tile green, each later step in one of STEP_COLOURS and unused
+// tiles grey.
+pub fn render_path_ansi<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    path: &Path<W, H>,
+) -> String {
+    render(board, path, |step, cell| {
+        let colour = match step {
+            None => UNUSED_COLOUR,
+            Some(0) => START_COLOUR,
+            Some(step) => STEP_COLOURS[(step - 1) % STEP_COLOURS.len()],
+        };
+        format!("{}{}{}", colour, cell, RESET)
+    })
+}
+
+// Draws the board with several masks laid over it: a tile in the nth mask,
+// counting from 1, uppercase and followed by n, and every other tile
+// lowercase. A tile in more than one mask is numbered for the first.
+pub fn render_masks<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    masks: &[BoardMask<W, H>],
+) -> String {
+    let cells: Board<String, W, H> =
+        Board::from_fn(|idx| match masks.iter().position(|mask| mask.get(idx)) {
+            Some(n) => format!("{}{}", board[idx].to_string().to_uppercase(), n + 1),
+            None => board[idx].to_string(),
+        });
+    let width = cells
+        .iter()
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for row in cells.rows() {
+        let row: Vec<String> = row.iter().map(|cell| format!("{:>width$}", cell)).collect();
+        out.push_str(&row.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+// Lays out the cells, padded to a common width before style() wraps each
+// in whatever it likes, so that escape codes don't throw off the alignment.
+fn render<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    path: &Path<W, H>,
+    style: impl Fn(Option<usize>, String) -> String,
+) -> String {
+    let on_path = BoardMask::from_indices(path.iter().copied());
+    let cells: Board<(Option<usize>, String), W, H> = Board::from_fn(|idx| {
+        if !on_path.get(idx) {
+            return (None, board[idx].to_string());
+        }
+        let step = path.iter().position(|&p| p == idx).unwrap();
+        let letters = board[idx].to_string().to_uppercase();
+        match step {
+            0 => (Some(step), format!("[{}]", letters)),
+            _ => (Some(step), format!("{}:{}", step + 1, letters)),
+        }
+    });
+
+    let width = cells
+        .iter()
+        .map(|(_, cell)| cell.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for row in cells.rows() {
+        let row: Vec<String> = row
+            .iter()
+            .map(|(step, cell)| style(*step, format!("{:>width$}", cell)))
+            .collect();
+        out.push_str(&row.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Index4x4, RuzzleBoard};
+
+    #[test]
+    fn renders_path() {
+        let board = RuzzleBoard::try_from("catsq(u)izxeonexxxx").unwrap();
+        let path: Path = [(1, 0), (0, 0), (0, 1), (1, 1), (2, 1)]
+            .map(|(x, y)| Index4x4::from_xy(x, y))
+            .to_vec();
+        assert_eq!(
+            render_path(&board, &path),
+            concat!(
+                " 2:C  [A]    t    s\n",
+                "3:QU  4:I  5:Z    x\n",
+                "   e    o    n    e\n",
+                "   x    x    x    x\n",
+            )
+        );
+
+        // Nothing traced.
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        assert_eq!(
+            render_path(&board, &vec![]),
+            "a b c d\ne f g h\ni j k l\nm n o p\n"
+        );
+    }
+
+    #[test]
+    fn renders_masks() {
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let masks = [
+            BoardMask::from_indices([(0, 0), (1, 0)].map(|(x, y)| Index4x4::from_xy(x, y))),
+            BoardMask::from_indices([(3, 3), (2, 3)].map(|(x, y)| Index4x4::from_xy(x, y))),
+        ];
+        assert_eq!(
+            render_masks(&board, &masks),
+            concat!(
+                "A1 B1  c  d\n",
+                " e  f  g  h\n",
+                " i  j  k  l\n",
+                " m  n O2 P2\n",
+            )
+        );
+    }
+
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                chars.by_ref().find(|&ch| ch == 'm');
+            } else {
+                out.push(ch);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn ansi_matches_plain() {
+        let board = RuzzleBoard::try_from("catsq(u)izxeonexxxx").unwrap();
+        let path: Path = [(1, 0), (0, 0), (0, 1), (1, 1), (2, 1), (2, 2)]
+            .map(|(x, y)| Index4x4::from_xy(x, y))
+            .to_vec();
+        let ansi = render_path_ansi(&board, &path);
+        assert_eq!(strip_ansi(&ansi), render_path(&board, &path));
+        assert!(ansi.starts_with("\x1b[33m 2:C\x1b[0m \x1b[1;32m [A]\x1b[0m \x1b[90m   t\x1b[0m"));
+        // The sixth tile wraps around to the first step colour.
+        assert!(ansi.contains("\x1b[33m 6:N\x1b[0m"));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::cli::{Cli, CliError, load_dictionaries, solve_options, write_results};
+use ruzzle_solver::board::Board;
+use ruzzle_solver::render::render_path;
+use ruzzle_solver::tile::Tile;
+use ruzzle_solver::trie::TrieNode;
+use ruzzle_solver::{SolveOptions, solve_best_words};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Enter the letters of a board to solve it, or one of:
+  :top N       only show the N highest scoring words
+  :top all     show every word again
+  :min N       only show words of at least N letters
+  :word WORD   show where WORD is on the current board and its score
+  :help        show this list
+  :quit        leave
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Board(String),
+    Top(Option<usize>), // None for every word.
+    Min(usize),
+    Word(String),
+    Help,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    Unknown(String),
+    MissingArgument(&'static str),
+    BadNumber(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Unknown(name) => {
+                write!(f, "unknown command :{}, try :help", name)
+            }
+            CommandError::MissingArgument(command) => {
+                write!(f, ":{} needs an argument, try :help", command)
+            }
+            CommandError::BadNumber(arg) => write!(f, "expected a number, got {:?}", arg),
+        }
+    }
+}
+
+// A line typed at the prompt. Anything not starting with ':' is taken to be
+// a board, and left for the board parser to complain about. Blank lines are
+// None.
+pub fn parse_command(line: &str) -> Result<Option<Command>, CommandError> {
+    let line = line.trim();
+    let Some(rest) = line.strip_prefix(':') else {
+        return Ok((!line.is_empty()).then(|| Command::Board(line.to_string())));
+    };
+    let (name, arg) = match rest.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (rest, ""),
+    };
+    let number = |command| match arg {
+        "" => Err(CommandError::MissingArgument(command)),
+        _ => arg
+            .parse()
+            .map_err(|_| CommandError::BadNumber(arg.to_string())),
+    };
+    let command = match name {
+        "top" if arg == "all" => Command::Top(None),
+        "top" => Command::Top(Some(number("top")?)),
+        "min" => Command::Min(number("min")?),
+        "word" if arg.is_empty() => return Err(CommandError::MissingArgument("word")),
+        "word" => Command::Word(arg.to_string()),
+        "help" => Command::Help,
+        "quit" => Command::Quit,
+        _ => return Err(CommandError::Unknown(name.to_string())),
+    };
+    Ok(Some(command))
+}
+
+// What carries over from one prompt to the next: the dictionary, loaded
+// once, the options as changed by :top and :min, and the last board solved.
+pub struct Session<const W: usize, const H: usize> {
+    root: TrieNode,
+    opts: SolveOptions<W, H>,
+    board: Option<Board<Tile, W, H>>,
+}
+
+impl<const W: usize, const H: usize> Session<W, H> {
+    pub fn new(root: TrieNode, opts: SolveOptions<W, H>) -> Self {
+        Session {
+            root,
+            opts,
+            board: None,
+        }
+    }
+
+    // Carries out command, writing anything it has to say to out. A bad
+    // board is reported there too, leaving the current board as it was.
+    // Returns false once the session should end.
+    pub fn execute(&mut self, command: Command, out: &mut impl Write) -> io::Result<bool> {
+        match command {
+            Command::Board(letters) => match letters.parse::<Board<Tile, W, H>>() {
+                Ok(board) => {
+                    let results = solve_best_words(&self.root, &board, &self.opts);
+                    write_results(out, &results)?;
+                    self.board = Some(board);
+                }
+                Err(err) => writeln!(out, "invalid board: {}", err)?,
+            },
+            Command::Top(limit) => self.opts.limit = limit,
+            Command::Min(min_len) => self.opts.min_len = min_len,
+            Command::Word(word) => self.show_word(&word, out)?,
+            Command::Help => write!(out, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    // The highest scoring path of word on the current board, whether or not
+    // the dictionary has it.
+    fn show_word(&self, word: &str, out: &mut impl Write) -> io::Result<()> {
+        let Some(board) = &self.board else {
+            return writeln!(out, "no board yet, enter one first");
+        };
+        let score = |path: &_| self.opts.score(board, path, word);
+        let best = board
+            .find_paths_for_word(word)
+            .into_iter()
+            .max_by_key(|path| score(path));
+        let Some(path) = best else {
+            return writeln!(out, "{} is not on the board", word);
+        };
+        write!(out, "{}", render_path(board, &path))?;
+        write!(out, "{} {}", word, score(&path))?;
+        if !self.root.contains_word(word) {
+            write!(out, " (not in the dictionary)")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", self.opts.explain(board, &path, word))
+    }
+}
+
+// Prompts on stdout until :quit or the end of stdin.
+pub fn run<const W: usize, const H: usize>(cli: &Cli) -> Result<(), CliError> {
+    let root = load_dictionaries(&cli.dict, &cli.exclude_dict)?;
+    let mut session = Session::new(root, solve_options::<W, H>(cli));
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut line = String::new();
+    loop {
+        write!(stdout, "> ")
+            .and_then(|()| stdout.flush())
+            .map_err(CliError::Output)?;
+        line.clear();
+        if stdin.read_line(&mut line).map_err(CliError::Input)? == 0 {
+            return Ok(());
+        }
+        let keep_going = match parse_command(&line) {
+            Ok(Some(command)) => session.execute(command, &mut stdout),
+            Ok(None) => Ok(true),
+            Err(err) => writeln!(stdout, "error: {}", err).map(|()| true),
+        };
+        if !keep_going.map_err(CliError::Output)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruzzle_solver::Sort;
+
+    #[test]
+    fn parses_commands() {
+        let parse = |line| parse_command(line).unwrap();
+        assert_eq!(
+            parse("abcdefghijklmnop\n"),
+            Some(Command::Board("abcdefghijklmnop".into()))
+        );
+        assert_eq!(parse("  \n"), None);
+        assert_eq!(parse(":top 20"), Some(Command::Top(Some(20))));
+        assert_eq!(parse(":top all"), Some(Command::Top(None)));
+        assert_eq!(parse(":min   3 "), Some(Command::Min(3)));
+        assert_eq!(parse(":word hello"), Some(Command::Word("hello".into())));
+        assert_eq!(parse(":help"), Some(Command::Help));
+        assert_eq!(parse(":quit\n"), Some(Command::Quit));
+    }
+
+    #[test]
+    fn rejects_bad_commands() {
+        let err = |line| parse_command(line).unwrap_err();
+        assert_eq!(
+            err(":frobnicate"),
+            CommandError::Unknown("frobnicate".into())
+        );
+        assert_eq!(err(":top"), CommandError::MissingArgument("top"));
+        assert_eq!(err(":word  "), CommandError::MissingArgument("word"));
+        assert_eq!(err(":min three"), CommandError::BadNumber("three".into()));
+        assert_eq!(err(":top -1"), CommandError::BadNumber("-1".into()));
+        assert_eq!(err(":min x").to_string(), "expected a number, got \"x\"");
+    }
+
+    #[test]
+    fn session_keeps_state() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "abe"] {
+            root.add_word(word);
+        }
+        let mut session = Session::<4, 4>::new(
+            root,
+            SolveOptions {
+                sort: Sort::ByScore,
+                ..SolveOptions::default()
+            },
+        );
+        let mut run = |line: &str| {
+            let mut out = vec![];
+            let command = parse_command(line).unwrap().unwrap();
+            let keep_going = session.execute(command, &mut out).unwrap();
+            (String::from_utf8(out).unwrap(), keep_going)
+        };
+
+        assert_eq!(run(":word ab").0, "no board yet, enter one first\n");
+        assert_eq!(run("abcdefghijklmnop").0.lines().count(), 3);
+        assert_eq!(
+            run("not a board").0,
+            "invalid board: expected 16 tiles, got 9\n"
+        );
+        run(":min 3");
+        run(":top 1");
+        assert_eq!(run("abcdefghijklmnop").0, "   9 fab\n");
+        assert!(
+            run(":word fab")
+                .0
+                .ends_with("fab 9\nf(4) + a(1) + b(4) = 9\n")
+        );
+        assert!(run(":word bef").0.contains(" (not in the dictionary)\n"));
+        assert_eq!(run(":word zoo").0, "zoo is not on the board\n");
+        assert_eq!(run(":quit"), (String::new(), false));
+    }
+}
@@ -0,0 +1,911 @@
+use crate::Path;
+use crate::board::{Board, BoardParseError};
+use crate::tile::Tile;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Bonus {
+    #[default]
+    None,
+    DoubleLetter,
+    TripleLetter,
+    DoubleWord,
+    TripleWord,
+}
+
+impl Bonus {
+    pub fn letter_multiplier(self) -> u32 {
+        match self {
+            Bonus::DoubleLetter => 2,
+            Bonus::TripleLetter => 3,
+            _ => 1,
+        }
+    }
+
+    pub fn word_multiplier(self) -> u32 {
+        match self {
+            Bonus::DoubleWord => 2,
+            Bonus::TripleWord => 3,
+            _ => 1,
+        }
+    }
+
+    // As on the board: DL, TL, DW or TW, and nothing for no bonus.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Bonus::None => "",
+            Bonus::DoubleLetter => "DL",
+            Bonus::TripleLetter => "TL",
+            Bonus::DoubleWord => "DW",
+            Bonus::TripleWord => "TW",
+        }
+    }
+
+    // The character for the bonus in a bonus board string: '.' for none,
+    // 'd' and 't' for double and triple letter, 'D' and 'T' for double and
+    // triple word.
+    pub fn notation(self) -> char {
+        match self {
+            Bonus::None => '.',
+            Bonus::DoubleLetter => 'd',
+            Bonus::TripleLetter => 't',
+            Bonus::DoubleWord => 'D',
+            Bonus::TripleWord => 'T',
+        }
+    }
+
+    pub fn from_notation(ch: char) -> Option<Bonus> {
+        match ch {
+            '.' => Some(Bonus::None),
+            'd' => Some(Bonus::DoubleLetter),
+            't' => Some(Bonus::TripleLetter),
+            'D' => Some(Bonus::DoubleWord),
+            'T' => Some(Bonus::TripleWord),
+            _ => None,
+        }
+    }
+}
+
+pub type BonusBoard<const W: usize = 4, const H: usize = 4> = Board<Bonus, W, H>;
+
+impl<const W: usize, const H: usize> TryFrom<&str> for BonusBoard<W, H> {
+    type Error = BoardParseError;
+
+    // A Bonus::notation() character per tile, row by row, as in
+    // "....d......T....". Whitespace is ignored as for letter boards.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut bonuses = vec![];
+        for ch in s.chars().filter(|ch| !ch.is_whitespace()) {
+            let pos = bonuses.len();
+            bonuses.push(Bonus::from_notation(ch).ok_or(BoardParseError::InvalidChar { ch, pos })?);
+        }
+        if bonuses.len() != W * H {
+            return Err(BoardParseError::WrongLength {
+                expected: W * H,
+                got: bonuses.len(),
+            });
+        }
+        Ok(Board::from_fn(|idx| bonuses[idx.flattened]))
+    }
+}
+
+impl<const W: usize, const H: usize> FromStr for BonusBoard<W, H> {
+    type Err = BoardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+// Built-in letter values, chosen with --language.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Swedish,
+    Italian,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Swedish,
+        Language::Italian,
+        Language::German,
+    ];
+
+    // The ISO 639-1 code, as given to --language.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Swedish => "sv",
+            Language::Italian => "it",
+            Language::German => "de",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    fn table(self) -> &'static [(u32, &'static str)] {
+        match self {
+            // The English Ruzzle language pack, plus the Swedish letters.
+            Language::English => &[
+                (1, "aeilnorst"),
+                (2, "du"),
+                (3, "gmä"),
+                (4, "bcfhpvwyåö"),
+                (5, "k"),
+                (8, "x"),
+                (10, "jqz"),
+            ],
+            // Scrabble values from here on.
+            Language::Swedish => &[
+                (1, "adeilnrst"),
+                (2, "ghkmo"),
+                (3, "fvä"),
+                (4, "bpuåö"),
+                (7, "jy"),
+                (8, "cx"),
+                (10, "z"),
+            ],
+            Language::Italian => &[
+                (1, "aeio"),
+                (2, "crst"),
+                (3, "lmnu"),
+                (5, "bdfpv"),
+                (8, "ghz"),
+                (10, "q"),
+            ],
+            Language::German => &[
+                (1, "adeinrstu"),
+                (2, "ghlo"),
+                (3, "bmwz"),
+                (4, "cfkp"),
+                (6, "jväü"),
+                (8, "xö"),
+                (10, "qy"),
+            ],
+        }
+    }
+}
+
+// Points per letter. Letters the table leaves out score `default`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterValues {
+    ascii: [Option<u32>; 26], // a to z
+    other: BTreeMap<char, u32>,
+    pub default: u32,
+}
+
+impl Default for LetterValues {
+    fn default() -> Self {
+        LetterValues::for_language(Language::English)
+    }
+}
+
+impl LetterValues {
+    // No letters, each scoring `default`.
+    pub fn empty(default: u32) -> Self {
+        LetterValues {
+            ascii: [None; 26],
+            other: BTreeMap::new(),
+            default,
+        }
+    }
+
+    pub fn for_language(language: Language) -> Self {
+        let mut values = LetterValues::empty(0);
+        for &(value, letters) in language.table() {
+            for ch in letters.chars() {
+                values.set(ch, value);
+            }
+        }
+        values
+    }
+
+    pub fn set(&mut self, ch: char, value: u32) {
+        match ascii_slot(ch) {
+            Some(slot) => self.ascii[slot] = Some(value),
+            None => {
+                self.other.insert(ch, value);
+            }
+        }
+    }
+
+    // The value of ch, if the table has it.
+    pub fn get(&self, ch: char) -> Option<u32> {
+        match ascii_slot(ch) {
+            Some(slot) => self.ascii[slot],
+            None => self.other.get(&ch).copied(),
+        }
+    }
+
+    pub fn letter_value(&self, ch: char) -> u32 {
+        self.get(ch).unwrap_or(self.default)
+    }
+
+    // Wildcards are worth nothing, digraphs the sum of their letters.
+    pub fn tile_value(&self, tile: Tile) -> u32 {
+        match tile {
+            Tile::Wildcard => 0,
+            tile => tile.letters().map(|ch| self.letter_value(ch)).sum(),
+        }
+    }
+
+    // The letters on the board the table has no value for, in order and
+    // each once, so they can be warned about.
+    pub fn unknown_letters<const W: usize, const H: usize>(
+        &self,
+        board: &Board<Tile, W, H>,
+    ) -> Vec<char> {
+        let mut unknown: Vec<char> = board
+            .iter()
+            .flat_map(|tile| tile.letters())
+            .filter(|&ch| self.get(ch).is_none())
+            .collect();
+        unknown.sort_unstable();
+        unknown.dedup();
+        unknown
+    }
+
+    // A table as `letter = points` lines, with '#' comments, or as a JSON
+    // object of the same; either may set the value of other letters with
+    // a "default" key. Letters may be quoted, and are lowercased. Errors
+    // come with their line number.
+    pub fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let json = text.trim_start().starts_with('{');
+        let mut values = LetterValues::empty(0);
+        let mut entries: Vec<(usize, &str)> = vec![];
+        if json {
+            let body = text.trim();
+            let body = body
+                .strip_prefix('{')
+                .and_then(|body| body.strip_suffix('}'))
+                .ok_or_else(|| (1, "expected a JSON object".to_string()))?;
+            let mut offset = text.find('{').unwrap_or(0) + 1;
+            for entry in body.split(',') {
+                let start = offset + entry.len() - entry.trim_start().len();
+                offset += entry.len() + 1;
+                if !entry.trim().is_empty() {
+                    entries.push((text[..start].matches('\n').count() + 1, entry.trim()));
+                }
+            }
+        } else {
+            for (n, line) in text.lines().enumerate() {
+                let line = line.split('#').next().unwrap_or_default().trim();
+                if !line.is_empty() {
+                    entries.push((n + 1, line));
+                }
+            }
+        }
+        let separator = if json { ':' } else { '=' };
+        for (line, entry) in entries {
+            let err = |message: String| (line, message);
+            let (key, value) = entry.split_once(separator).ok_or_else(|| {
+                err(format!(
+                    "expected letter {} points, got {}",
+                    separator, entry
+                ))
+            })?;
+            let key = key.trim();
+            let key = key
+                .strip_prefix('"')
+                .and_then(|key| key.strip_suffix('"'))
+                .unwrap_or(key);
+            let value: u32 = value
+                .trim()
+                .parse()
+                .map_err(|_| err(format!("expected points, got {}", value.trim())))?;
+            let mut chars = key.chars().flat_map(char::to_lowercase);
+            match (key, chars.next(), chars.next()) {
+                ("default", _, _) => values.default = value,
+                (_, Some(ch), None) if ch.is_alphabetic() => values.set(ch, value),
+                _ => return Err(err(format!("expected a letter, got {}", key))),
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn ascii_slot(ch: char) -> Option<usize> {
+    ch.is_ascii_lowercase().then(|| (ch as u8 - b'a') as usize)
+}
+
+// Words of five letters or more earn an extra 5 points per letter beyond
+// the fourth: +5 for 5 letters, +10 for 6, +15 for 7 and so on.
+pub fn length_bonus(len: usize) -> u32 {
+    5 * len.saturating_sub(4) as u32
+}
+
+// Boggle ignores letters and bonuses, and scores by word length alone.
+pub fn boggle_score(len: usize) -> u32 {
+    match len {
+        0..=2 => 0,
+        3 | 4 => 1,
+        5 => 2,
+        6 => 3,
+        7 => 5,
+        _ => 11,
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Scoring {
+    #[default]
+    Ruzzle, // score_word(), with letter values and bonuses.
+    Boggle, // boggle_score() of the word length.
+}
+
+impl Scoring {
+    pub fn score_path<const W: usize, const H: usize>(
+        self,
+        values: &LetterValues,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+    ) -> u32 {
+        match self {
+            Scoring::Ruzzle => score_word(values, board, bonuses, path),
+            Scoring::Boggle => {
+                boggle_score(path.iter().map(|&idx| board[idx].letter_count()).sum())
+            }
+        }
+    }
+
+    pub fn explain_path<const W: usize, const H: usize>(
+        self,
+        values: &LetterValues,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+    ) -> ScoreBreakdown {
+        match self {
+            Scoring::Ruzzle => explain_word(values, board, bonuses, path),
+            Scoring::Boggle => {
+                let letters = path.iter().map(|&idx| board[idx].letter_count()).sum();
+                ScoreBreakdown {
+                    letters,
+                    length_points: boggle_score(letters),
+                    total: boggle_score(letters),
+                    ..ScoreBreakdown::default()
+                }
+            }
+        }
+    }
+}
+
+// Rules for what a word is worth, for house rules or games other than
+// Ruzzle. Given to a solve as SolveOptions::scorer.
+pub trait Scorer<const W: usize = 4, const H: usize = 4>: fmt::Debug + Send + Sync {
+    // word is spelled along path, with any wildcards bound.
+    fn score(
+        &self,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+        word: &str,
+    ) -> u32;
+
+    // The same score, taken apart.
+    fn explain(
+        &self,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+        word: &str,
+    ) -> ScoreBreakdown;
+}
+
+// A Scorer shared between options and threads. Equal only to its clones.
+#[derive(Debug, Clone)]
+pub struct SharedScorer<const W: usize = 4, const H: usize = 4>(pub Arc<dyn Scorer<W, H>>);
+
+impl<const W: usize, const H: usize> SharedScorer<W, H> {
+    pub fn new(scorer: impl Scorer<W, H> + 'static) -> Self {
+        SharedScorer(Arc::new(scorer))
+    }
+}
+
+impl<const W: usize, const H: usize> PartialEq for SharedScorer<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<const W: usize, const H: usize> Eq for SharedScorer<W, H> {}
+
+// score_word() with these letter values.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RuzzleScorer {
+    pub values: LetterValues,
+}
+
+impl<const W: usize, const H: usize> Scorer<W, H> for RuzzleScorer {
+    fn score(
+        &self,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+        _word: &str,
+    ) -> u32 {
+        score_word(&self.values, board, bonuses, path)
+    }
+
+    fn explain(
+        &self,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+        _word: &str,
+    ) -> ScoreBreakdown {
+        explain_word(&self.values, board, bonuses, path)
+    }
+}
+
+// Points by word length alone: table[len], or the last entry for words
+// longer than the table. The default table is boggle_score()'s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoggleScorer {
+    pub table: Vec<u32>,
+}
+
+impl Default for BoggleScorer {
+    fn default() -> Self {
+        BoggleScorer {
+            table: (0..=8).map(boggle_score).collect(),
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> Scorer<W, H> for BoggleScorer {
+    fn score(
+        &self,
+        _board: &Board<Tile, W, H>,
+        _bonuses: &BonusBoard<W, H>,
+        _path: &Path<W, H>,
+        word: &str,
+    ) -> u32 {
+        let len = word.chars().count();
+        match self.table.get(len) {
+            Some(&points) => points,
+            None => self.table.last().copied().unwrap_or(0),
+        }
+    }
+
+    fn explain(
+        &self,
+        board: &Board<Tile, W, H>,
+        bonuses: &BonusBoard<W, H>,
+        path: &Path<W, H>,
+        word: &str,
+    ) -> ScoreBreakdown {
+        let points = self.score(board, bonuses, path, word);
+        ScoreBreakdown {
+            letters: word.chars().count(),
+            length_points: points,
+            total: points,
+            ..ScoreBreakdown::default()
+        }
+    }
+}
+
+pub fn score_word<const W: usize, const H: usize>(
+    values: &LetterValues,
+    board: &Board<Tile, W, H>,
+    bonuses: &BonusBoard<W, H>,
+    path: &Path<W, H>,
+) -> u32 {
+    // Letter multipliers apply to their own tile, word multipliers to the
+    // sum of all (multiplied) letters. The length bonus is never multiplied.
+    let letters: u32 = path
+        .iter()
+        .map(|&idx| values.tile_value(board[idx]) * bonuses[idx].letter_multiplier())
+        .sum();
+    let word_multiplier: u32 = path
+        .iter()
+        .map(|&idx| bonuses[idx].word_multiplier())
+        .product();
+    let len = path.iter().map(|&idx| board[idx].letter_count()).sum();
+    letters * word_multiplier + length_bonus(len)
+}
+
+// How score_word() arrives at a score, for showing to people.
+pub fn explain_word<const W: usize, const H: usize>(
+    values: &LetterValues,
+    board: &Board<Tile, W, H>,
+    bonuses: &BonusBoard<W, H>,
+    path: &Path<W, H>,
+) -> ScoreBreakdown {
+    let letters = path.iter().map(|&idx| board[idx].letter_count()).sum();
+    ScoreBreakdown {
+        tiles: path
+            .iter()
+            .map(|&idx| TileScore {
+                tile: board[idx],
+                value: values.tile_value(board[idx]),
+                bonus: bonuses[idx],
+            })
+            .collect(),
+        word_bonuses: path
+            .iter()
+            .map(|&idx| bonuses[idx])
+            .filter(|bonus| bonus.word_multiplier() > 1)
+            .collect(),
+        letters,
+        length_points: length_bonus(letters),
+        total: score_word(values, board, bonuses, path),
+    }
+}
+
+// A score taken apart. Displays as e.g.
+// "(q(10)×TL + u(1) + i(1) + z(10)) ×2(DW) + 5(len 5) = 89", leaving out
+// what adds nothing, and as "2(len 5) = 2" for length alone.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScoreBreakdown {
+    pub tiles: Vec<TileScore>, // Along the path; empty if letters don't count.
+    pub word_bonuses: Vec<Bonus>, // Those multiplying the word, along the path.
+    pub letters: usize,
+    pub length_points: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TileScore {
+    pub tile: Tile,
+    pub value: u32, // Before the bonus.
+    pub bonus: Bonus,
+}
+
+impl fmt::Display for TileScore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.tile, self.value)?;
+        if self.bonus.letter_multiplier() > 1 {
+            write!(f, "×{}", self.bonus.abbreviation())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+        if !self.tiles.is_empty() {
+            let tiles: Vec<String> = self.tiles.iter().map(ToString::to_string).collect();
+            let tiles = tiles.join(" + ");
+            parts.push(match self.word_bonuses.is_empty() {
+                true => tiles,
+                false => {
+                    let bonuses: Vec<String> = self
+                        .word_bonuses
+                        .iter()
+                        .map(|bonus| {
+                            format!("×{}({})", bonus.word_multiplier(), bonus.abbreviation())
+                        })
+                        .collect();
+                    format!("({}) {}", tiles, bonuses.join(" "))
+                }
+            });
+        }
+        if self.length_points > 0 || self.tiles.is_empty() {
+            parts.push(format!("{}(len {})", self.length_points, self.letters));
+        }
+        write!(f, "{} = {}", parts.join(" + "), self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Index4x4, RuzzleBoard};
+
+    // Flat index order. Not a traceable path past the first row,
+    // but scoring doesn't care about adjacency.
+    fn row_path(len: usize) -> Path {
+        (0..len).map(|x| Index4x4::from_xy(x % 4, x / 4)).collect()
+    }
+
+    #[test]
+    fn short_word() {
+        let board = RuzzleBoard::try_from("catsxxxxxxxxxxxx").unwrap();
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(3)
+            ),
+            4 + 1 + 1
+        );
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(4)
+            ),
+            4 + 1 + 1 + 1
+        );
+    }
+
+    #[test]
+    fn long_word_length_bonus() {
+        let board = RuzzleBoard::try_from("planetsxxxxxxxxx").unwrap();
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(5)
+            ),
+            4 + 1 + 1 + 1 + 1 + 5
+        );
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(6)
+            ),
+            4 + 1 + 1 + 1 + 1 + 1 + 10
+        );
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(7)
+            ),
+            4 + 1 + 1 + 1 + 1 + 1 + 1 + 15
+        );
+    }
+
+    #[test]
+    fn high_value_letters() {
+        let board = RuzzleBoard::try_from("quizxxxxxxxxxxxx").unwrap();
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(4)
+            ),
+            10 + 2 + 1 + 10
+        );
+    }
+
+    #[test]
+    fn multipliers() {
+        let board = RuzzleBoard::try_from("quizxxxxxxxxxxxx").unwrap();
+        let bonuses = BonusBoard::default()
+            .with_at(Bonus::TripleLetter, Index4x4::from_xy(0, 0))
+            .with_at(Bonus::DoubleWord, Index4x4::from_xy(3, 0));
+        assert_eq!(
+            score_word(&LetterValues::default(), &board, &bonuses, &row_path(4)),
+            (30 + 2 + 1 + 10) * 2
+        );
+
+        // Word multipliers stack, and skip the length bonus.
+        let board = RuzzleBoard::try_from("planetsxxxxxxxxx").unwrap();
+        let bonuses = BonusBoard::default()
+            .with_at(Bonus::DoubleWord, Index4x4::from_xy(0, 0))
+            .with_at(Bonus::TripleWord, Index4x4::from_xy(1, 0));
+        assert_eq!(
+            score_word(&LetterValues::default(), &board, &bonuses, &row_path(5)),
+            8 * 6 + 5
+        );
+    }
+
+    #[test]
+    fn digraph_counts_both_letters() {
+        let board = RuzzleBoard::try_from("q(u)eenxxxxxxxxxxxx").unwrap();
+        // Five letters on four tiles still earns the length bonus.
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(4)
+            ),
+            10 + 2 + 1 + 1 + 1 + 5
+        );
+    }
+
+    #[test]
+    fn explains_scores() {
+        let board = RuzzleBoard::try_from("planetsxxxxxxxxx").unwrap();
+        let bonuses = BonusBoard::default()
+            .with_at(Bonus::TripleLetter, Index4x4::from_xy(0, 0))
+            .with_at(Bonus::DoubleWord, Index4x4::from_xy(2, 0));
+        let values = LetterValues::default();
+        let breakdown = explain_word(&values, &board, &bonuses, &row_path(5));
+        assert_eq!(
+            breakdown.tiles[..2],
+            [
+                TileScore {
+                    tile: Tile::Letter('p'),
+                    value: 4,
+                    bonus: Bonus::TripleLetter,
+                },
+                TileScore {
+                    tile: Tile::Letter('l'),
+                    value: 1,
+                    bonus: Bonus::None,
+                },
+            ]
+        );
+        assert_eq!(breakdown.word_bonuses, [Bonus::DoubleWord]);
+        assert_eq!((breakdown.letters, breakdown.length_points), (5, 5));
+        assert_eq!(
+            breakdown.total,
+            score_word(&values, &board, &bonuses, &row_path(5))
+        );
+        assert_eq!(
+            breakdown.to_string(),
+            "(p(4)×TL + l(1) + a(1) + n(1) + e(1)) ×2(DW) + 5(len 5) = 37"
+        );
+
+        let board = RuzzleBoard::try_from("q(u)izxxxxxxxxxxxxx").unwrap();
+        let breakdown = explain_word(&values, &board, &BonusBoard::default(), &row_path(3));
+        assert_eq!(breakdown.to_string(), "qu(12) + i(1) + z(10) = 23");
+        let scorer: &dyn Scorer = &BoggleScorer::default();
+        let breakdown = scorer.explain(&board, &bonuses, &row_path(3), "quiz");
+        assert_eq!(breakdown.to_string(), "1(len 4) = 1");
+        assert_eq!(
+            Scoring::Boggle
+                .explain_path(&values, &board, &bonuses, &row_path(3))
+                .total,
+            1
+        );
+    }
+
+    #[test]
+    fn other_letters() {
+        let board = RuzzleBoard::try_from("båtöäéxxxxxxxxxx").unwrap();
+        // b 4 + å 4 + t 1, ö 4 + ä 3, and nothing for letters without a value.
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &row_path(3)
+            ),
+            9
+        );
+        let path = (3..6).map(|x| Index4x4::from_xy(x % 4, x / 4)).collect();
+        assert_eq!(
+            score_word(
+                &LetterValues::default(),
+                &board,
+                &BonusBoard::default(),
+                &path
+            ),
+            7
+        );
+    }
+
+    #[test]
+    fn languages() {
+        let board = RuzzleBoard::try_from("catsxxxxxxxxxxxx").unwrap();
+        let score = |language| {
+            let values = LetterValues::for_language(language);
+            score_word(&values, &board, &BonusBoard::default(), &row_path(4))
+        };
+        assert_eq!(score(Language::English), 4 + 1 + 1 + 1);
+        assert_eq!(score(Language::Swedish), 8 + 1 + 1 + 1);
+        assert_eq!(score(Language::Italian), 2 + 1 + 2 + 2);
+        assert_eq!(
+            LetterValues::default(),
+            LetterValues::for_language(Language::English)
+        );
+
+        // Italian has no k, w, x or y.
+        let board = RuzzleBoard::try_from("kwxyaaaaaaaaaaaa").unwrap();
+        let italian = LetterValues::for_language(Language::Italian);
+        assert_eq!(italian.unknown_letters(&board), ['k', 'w', 'x', 'y']);
+        assert!(LetterValues::default().unknown_letters(&board).is_empty());
+        for language in Language::ALL {
+            assert_eq!(Language::from_code(language.code()), Some(language));
+        }
+        assert_eq!(Language::from_code("xx"), None);
+    }
+
+    #[test]
+    fn parses_letter_values() {
+        let toml = "# Mostly ones\n\
+                    default = 1\n\
+                    \n\
+                    q = 10 # and u\n\
+                    \"Ä\" = 5\n";
+        let values = LetterValues::parse(toml).unwrap();
+        assert_eq!(
+            (values.get('q'), values.get('ä'), values.get('b')),
+            (Some(10), Some(5), None)
+        );
+        assert_eq!(values.letter_value('b'), 1);
+        let json = "{\n  \"default\": 1,\n  \"q\": 10,\n  \"ä\": 5\n}\n";
+        assert_eq!(LetterValues::parse(json), Ok(values));
+        assert_eq!(LetterValues::parse("").unwrap().letter_value('a'), 0);
+
+        for (text, err) in [
+            ("a = 1\nb", (2, "expected letter = points, got b")),
+            ("ab = 1", (1, "expected a letter, got ab")),
+            ("1 = 1", (1, "expected a letter, got 1")),
+            ("a = -1", (1, "expected points, got -1")),
+            ("{\"a\": 1,\n\"b\": x}", (2, "expected points, got x")),
+            ("{\"a\": 1", (1, "expected a JSON object")),
+        ] {
+            assert_eq!(LetterValues::parse(text), Err((err.0, err.1.to_string())));
+        }
+    }
+
+    #[test]
+    fn parses_bonuses() {
+        let bonuses: BonusBoard = "t..D\n.d..\n....\n...T".parse().unwrap();
+        assert_eq!(bonuses[Index4x4::from_xy(0, 0)], Bonus::TripleLetter);
+        assert_eq!(bonuses[Index4x4::from_xy(3, 0)], Bonus::DoubleWord);
+        assert_eq!(bonuses[Index4x4::from_xy(1, 1)], Bonus::DoubleLetter);
+        assert_eq!(bonuses[Index4x4::from_xy(3, 3)], Bonus::TripleWord);
+        assert_eq!(bonuses.iter().filter(|&&b| b == Bonus::None).count(), 12);
+        for bonus in bonuses.iter() {
+            assert_eq!(Bonus::from_notation(bonus.notation()), Some(*bonus));
+        }
+
+        // As in multipliers().
+        let board = RuzzleBoard::try_from("quizxxxxxxxxxxxx").unwrap();
+        let values = LetterValues::default();
+        assert_eq!(
+            score_word(&values, &board, &bonuses, &row_path(4)),
+            (30 + 2 + 1 + 10) * 2
+        );
+
+        assert_eq!(
+            BonusBoard::<4, 4>::try_from("....d......T..."),
+            Err(BoardParseError::WrongLength {
+                expected: 16,
+                got: 15
+            })
+        );
+        assert_eq!(
+            "....d...x...T...".parse::<BonusBoard>(),
+            Err(BoardParseError::InvalidChar { ch: 'x', pos: 8 })
+        );
+        assert_eq!(
+            "....D....."
+                .parse::<BonusBoard<3, 3>>()
+                .unwrap_err()
+                .to_string(),
+            "expected 9 tiles, got 10"
+        );
+    }
+
+    #[test]
+    fn boggle_scoring() {
+        let scores: Vec<u32> = (0..=9).map(boggle_score).collect();
+        assert_eq!(scores, [0, 0, 0, 1, 1, 2, 3, 5, 11, 11]);
+
+        // Qu counts as two letters, and bonuses are ignored.
+        let board = RuzzleBoard::try_from("q(u)eenxxxxxxxxxxxx").unwrap();
+        let bonuses = BonusBoard::default().with_at(Bonus::TripleWord, Index4x4::from_xy(0, 0));
+        assert_eq!(
+            Scoring::Boggle.score_path(&LetterValues::default(), &board, &bonuses, &row_path(4)),
+            2
+        );
+        assert_eq!(
+            Scoring::Ruzzle.score_path(&LetterValues::default(), &board, &bonuses, &row_path(4)),
+            15 * 3 + 5
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use crate::trie::{Payload, TrieNode};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// Binary trie format:
+//   magic bytes "RZTRIE", then a version byte,
+//   then (from version 2) the word list the trie was compiled from:
+//     path_len  varint  length of its UTF-8 path, 0 if unknown, then
+//     path      bytes   and, unless path_len is 0,
+//     len       u64 LE  its size in bytes
+//     modified  u64 LE  its mtime in nanoseconds since the Unix epoch
+//   then the nodes in pre-order, each written as
+//     char      varint  the node's char as a code point (omitted for the root)
+//     flags     u8      bit 0 set for terminal nodes, and from version 3
+//                       bit 1 if a frequency follows, bit 2 if a definition does
+//     frequency u64 LE  the word's frequency, if flagged
+//     def_len   varint  length of the word's UTF-8 definition, if flagged, then
+//     def       bytes
+//     children  varint  number of children, which follow directly
+// Varints are LEB128: 7 bits per byte, least significant first.
+// Version 1 files, without the source, and version 2 files, without
+// payloads, can still be read.
+pub const MAGIC: &[u8; 6] = b"RZTRIE";
+pub const VERSION: u8 = 3;
+
+// The longest word a trie file may hold, in chars. Far past any real word,
+// but it keeps a corrupt file from claiming a chain of nodes as long as
+// memory allows.
+pub const MAX_DEPTH: usize = 1 << 20;
+
+const TERMINAL: u8 = 1;
+const FREQUENCY: u8 = 2;
+const DEFINITION: u8 = 4;
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_varint<W: Write>(w: &mut W, mut n: u32) -> io::Result<()> {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            return w.write_all(&[byte]);
+        }
+        w.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+// len bytes, read as they come rather than allocated up front, since a
+// corrupt length could be anything up to 4 GiB.
+fn read_bytes<R: Read>(r: &mut R, len: u32) -> io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    r.by_ref().take(u64::from(len)).read_to_end(&mut buf)?;
+    if buf.len() != len as usize {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+    Ok(buf)
+}
+
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut n: u32 = 0;
+    for shift in (0..32).step_by(7) {
+        let byte = read_u8(r)?;
+        n |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(invalid_data("varint too long".to_string()))
+}
+
+// The word list a compiled trie was built from, as it was at the time, so
+// that a trie can be found to be out of date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub path: PathBuf,
+    pub len: u64,
+    pub modified: u64, // Nanoseconds since the Unix epoch.
+}
+
+impl Source {
+    pub fn of(path: &Path) -> io::Result<Source> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        Ok(Source {
+            path: fs::canonicalize(path)?,
+            len: metadata.len(),
+            modified,
+        })
+    }
+
+    // Whether the word list has changed since. A word list which can't be
+    // found any more isn't considered changed.
+    pub fn is_stale(&self) -> bool {
+        Source::of(&self.path).is_ok_and(|now| now != *self)
+    }
+
+    fn write<W: Write>(source: Option<&Source>, w: &mut W) -> io::Result<()> {
+        let Some(source) = source else {
+            return write_varint(w, 0);
+        };
+        let path = source.path.to_string_lossy();
+        write_varint(w, path.len() as u32)?;
+        w.write_all(path.as_bytes())?;
+        w.write_all(&source.len.to_le_bytes())?;
+        w.write_all(&source.modified.to_le_bytes())
+    }
+
+    fn read<R: Read>(r: &mut R) -> io::Result<Option<Source>> {
+        let path_len = read_varint(r)?;
+        if path_len == 0 {
+            return Ok(None);
+        }
+        let path = String::from_utf8(read_bytes(r, path_len)?)
+            .map_err(|_| invalid_data("source path is not UTF-8".to_string()))?;
+        Ok(Some(Source {
+            path: PathBuf::from(path),
+            len: read_u64(r)?,
+            modified: read_u64(r)?,
+        }))
+    }
+}
+
+impl TrieNode {
+    pub fn write_to<W: Write>(&self, w: W) -> io::Result<()> {
+        self.write_with_source(w, None)
+    }
+
+    pub fn write_with_source<W: Write>(&self, mut w: W, source: Option<&Source>) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        Source::write(source, &mut w)?;
+        self.write_node(&mut w)?;
+        w.flush()
+    }
+
+    // The nodes in pre-order, from a stack rather than by recursion, which a
+    // deep enough trie would overflow.
+    fn write_node<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            node.write_fields(w)?;
+            stack.extend(node.children.iter().rev());
+        }
+        Ok(())
+    }
+
+    // Everything of a node but its children themselves.
+    fn write_fields<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if let Some(ch) = self.ch {
+            write_varint(w, ch as u32)?;
+        }
+        let payload = self.payload.as_deref();
+        let frequency = payload.and_then(|payload| payload.frequency);
+        let definition = payload.and_then(|payload| payload.definition.as_deref());
+        let mut flags = if self.is_terminal { TERMINAL } else { 0 };
+        if frequency.is_some() {
+            flags |= FREQUENCY;
+        }
+        if definition.is_some() {
+            flags |= DEFINITION;
+        }
+        w.write_all(&[flags])?;
+        if let Some(frequency) = frequency {
+            w.write_all(&frequency.to_le_bytes())?;
+        }
+        if let Some(definition) = definition {
+            write_varint(w, definition.len() as u32)?;
+            w.write_all(definition.as_bytes())?;
+        }
+        write_varint(w, self.children.len() as u32)
+    }
+
+    pub fn read_from<R: Read>(r: R) -> io::Result<TrieNode> {
+        Ok(TrieNode::read_with_source(r)?.0)
+    }
+
+    pub fn read_with_source<R: Read>(mut r: R) -> io::Result<(TrieNode, Option<Source>)> {
+        let mut magic = [0u8; 6];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data(
+                "not a trie file (bad magic bytes)".to_string(),
+            ));
+        }
+        let source = match read_u8(&mut r)? {
+            1 => None,
+            2..=VERSION => Source::read(&mut r)?,
+            version => {
+                return Err(invalid_data(format!(
+                    "unsupported trie format version {} (expected at most {})",
+                    version, VERSION
+                )));
+            }
+        };
+        let root = TrieNode::read_nodes(&mut r)?;
+        Ok((root, source))
+    }
+
+    // The root and the nodes below it, each node kept on a stack with how
+    // many of its children are still to be read until they all are, rather
+    // than read by recursion, which a deep enough trie would overflow.
+    fn read_nodes<R: Read>(r: &mut R) -> io::Result<TrieNode> {
+        let mut root = TrieNode::new_root();
+        let child_count = root.read_fields(r)?;
+        let mut stack = vec![(root, child_count)];
+        loop {
+            let (node, remaining) = stack.last_mut().expect("the root is popped last");
+            if *remaining == 0 {
+                let (mut node, _) = stack.pop().expect("the root is popped last");
+                node.index_children();
+                node.update_terminal_descendant();
+                match stack.last_mut() {
+                    Some((parent, _)) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+                continue;
+            }
+            *remaining -= 1;
+            let code = read_varint(r)?;
+            let ch = char::from_u32(code)
+                .ok_or_else(|| invalid_data(format!("invalid char code {:#x}", code)))?;
+            // Lookups binary search the children, so they must be sorted.
+            if node.children.last().is_some_and(|prev| prev.ch >= Some(ch)) {
+                return Err(invalid_data(format!("child '{}' out of order", ch)));
+            }
+            if stack.len() > MAX_DEPTH {
+                return Err(invalid_data(format!(
+                    "trie deeper than {} letters",
+                    MAX_DEPTH
+                )));
+            }
+            let mut child = TrieNode::from_char(ch);
+            let child_count = child.read_fields(r)?;
+            stack.push((child, child_count));
+        }
+    }
+
+    // Reads flags and payload into self, whose char has already been read,
+    // and returns the number of children that follow.
+    fn read_fields<R: Read>(&mut self, r: &mut R) -> io::Result<u32> {
+        let flags = read_u8(r)?;
+        if flags & !(TERMINAL | FREQUENCY | DEFINITION) != 0 {
+            return Err(invalid_data(format!("invalid node flags {:#04x}", flags)));
+        }
+        self.is_terminal = flags & TERMINAL != 0;
+        if flags & (FREQUENCY | DEFINITION) != 0 {
+            let mut payload = Payload::default();
+            if flags & FREQUENCY != 0 {
+                payload.frequency = Some(read_u64(r)?);
+            }
+            if flags & DEFINITION != 0 {
+                let len = read_varint(r)?;
+                let definition = read_bytes(r, len)?;
+                payload.definition = Some(
+                    String::from_utf8(definition)
+                        .map_err(|_| invalid_data("definition is not UTF-8".to_string()))?,
+                );
+            }
+            self.payload = Some(Box::new(payload));
+        }
+        read_varint(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trie() -> TrieNode {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "hello", "he", "hell", "rust", "rusty", "trie", "tree", "åsna", "ö",
+        ] {
+            root.add_word(word);
+        }
+        root
+    }
+
+    #[test]
+    fn round_trip() {
+        let root = sample_trie();
+        let mut bytes = vec![];
+        root.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..6], MAGIC);
+        assert_eq!(bytes[6], VERSION);
+
+        let read = TrieNode::read_from(&bytes[..]).unwrap();
+        assert_eq!(read, root);
+        assert!(read.has_prefix("ru"));
+        assert!(!read.has_prefix("rusts"));
+    }
+
+    fn read_err(bytes: &[u8]) -> io::Error {
+        match TrieNode::read_from(bytes) {
+            Ok(_) => panic!("expected an error"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let err = read_err(b"NOTATRIE\x00");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("bad magic"));
+
+        let err = read_err(b"RZTRIE\x07\x00\x00");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "unsupported trie format version 7 (expected at most 3)"
+        );
+
+        let mut bytes = vec![];
+        sample_trie().write_to(&mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 3);
+        let err = read_err(&bytes);
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_unsorted_children() {
+        // Root with children 'b' then 'a', both terminal leaves.
+        let err = read_err(b"RZTRIE\x01\x00\x02b\x01\x00a\x01\x00");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "child 'a' out of order");
+
+        let root = TrieNode::read_from(&b"RZTRIE\x01\x00\x02a\x01\x00b\x01\x00"[..]).unwrap();
+        assert!(root.contains_word("a"));
+        assert!(root.contains_word("b"));
+    }
+
+    #[test]
+    fn rejects_deep_tries() {
+        // A root, then one 'a' under another, the last a word.
+        let chain = |depth: usize| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend([VERSION, 0, 0, 1]);
+            for _ in 1..depth {
+                bytes.extend([b'a', 0, 1]);
+            }
+            bytes.extend([b'a', TERMINAL, 0]);
+            bytes
+        };
+        let root = TrieNode::read_from(&chain(1000)[..]).unwrap();
+        assert!(root.contains_word(&"a".repeat(1000)));
+        let err = TrieNode::read_from(&chain(MAX_DEPTH + 1)[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "trie deeper than 1048576 letters");
+    }
+
+    #[test]
+    fn round_trips_payloads() {
+        let mut root = sample_trie();
+        root.add_word_with(
+            "rust",
+            Payload {
+                frequency: Some(u64::MAX),
+                definition: Some("järnoxid".to_string()),
+            },
+        );
+        let common = Payload {
+            frequency: Some(300),
+            definition: None,
+        };
+        root.add_word_with("he", common.clone());
+        let mut bytes = vec![];
+        root.write_to(&mut bytes).unwrap();
+
+        let read = TrieNode::read_from(&bytes[..]).unwrap();
+        assert_eq!(read, root);
+        assert_eq!(read.get("he"), Some(&common));
+
+        // Version 2 files have no payloads, but read the same otherwise.
+        let v2 = b"RZTRIE\x02\x00\x00\x01a\x01\x00";
+        let read = TrieNode::read_from(&v2[..]).unwrap();
+        assert!(read.contains_word("a") && read.get("a").is_none());
+
+        // A definition claiming 4 GiB, in a file of a few bytes.
+        let corrupt = b"RZTRIE\x03\x00\x00\x01a\x05\xff\xff\xff\xff\x0fdef";
+        let err = TrieNode::read_from(&corrupt[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let corrupt = b"RZTRIE\x03\xff\xff\xff\xff\x0f/words.txt";
+        let err = TrieNode::read_from(&corrupt[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn records_source() {
+        let path = std::env::temp_dir().join(format!("ruzzle-source-{}.txt", std::process::id()));
+        fs::write(&path, "hello\nrust\n").unwrap();
+        let source = Source::of(&path).unwrap();
+        assert_eq!(source.len, 11);
+        assert!(!source.is_stale());
+
+        let mut bytes = vec![];
+        sample_trie()
+            .write_with_source(&mut bytes, Some(&source))
+            .unwrap();
+        let (read, read_source) = TrieNode::read_with_source(&bytes[..]).unwrap();
+        assert_eq!(read, sample_trie());
+        assert_eq!(read_source.as_ref(), Some(&source));
+
+        fs::write(&path, "hello\nrust\ntrie\n").unwrap();
+        assert!(source.is_stale());
+        fs::remove_file(&path).unwrap();
+        assert!(!source.is_stale());
+
+        let mut bytes = vec![];
+        sample_trie().write_to(&mut bytes).unwrap();
+        assert_eq!(TrieNode::read_with_source(&bytes[..]).unwrap().1, None);
+    }
+}
@@ -0,0 +1,2438 @@
+use crate::board::{Board, BoardIndex};
+use crate::dictionary::{Cursor, Dictionary};
+use crate::mask::BoardMask;
+use crate::score::{
+    BonusBoard, LetterValues, ScoreBreakdown, Scoring, SharedScorer, boggle_score, length_bonus,
+};
+use crate::tile::Tile;
+use crate::trie::{Payload, TrieNode};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicBool};
+use std::time::{Duration, Instant};
+
+pub type Path<const W: usize = 4, const H: usize = 4> = Vec<BoardIndex<W, H>>;
+
+// The nodes reached by stepping onto a board tile: every child for a
+// wildcard, otherwise at most one node, which is a grandchild for digraphs.
+pub(crate) fn descend_tile<C: Cursor>(cursor: C, tile: Tile) -> impl Iterator<Item = C> {
+    let (all, found) = match tile {
+        Tile::Wildcard => (Some(cursor.children()), None),
+        Tile::Letter(ch) => (None, cursor.descend(ch)),
+        Tile::Digraph(first, second) => (
+            None,
+            cursor
+                .descend(first)
+                .and_then(|child| child.descend(second)),
+        ),
+    };
+    all.into_iter().flatten().chain(found)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SolveResult<const W: usize = 4, const H: usize = 4> {
+    pub word: String,
+    pub path: Path<W, H>,
+    pub score: u32,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub wildcard_bindings: Vec<(BoardIndex<W, H>, char)>, // The letter each wildcard tile on the path stands for.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub payload: Option<Payload>, // What the dictionary has stored with the word, if anything.
+}
+
+impl<const W: usize, const H: usize> SolveResult<W, H> {
+    // The tiles the word is spelled on.
+    pub fn mask(&self) -> BoardMask<W, H> {
+        BoardMask::from_indices(self.path.iter().copied())
+    }
+
+    pub fn frequency(&self) -> Option<u64> {
+        self.payload.as_ref().and_then(|payload| payload.frequency)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveOptions<const W: usize = 4, const H: usize = 4> {
+    pub min_len: usize, // Shorter words are not reported, but are still searched through.
+    pub max_len: Option<usize>, // No paths longer than this are searched. None for unlimited.
+    pub bonuses: BonusBoard<W, H>,
+    pub sort: Sort,
+    pub allowed: BoardMask<W, H>, // Tiles the search may use. All of them by default.
+    pub must_include: Option<BoardIndex<W, H>>, // Only report words whose path uses this tile.
+    pub limit: Option<usize>,     // Only the first this many results, after sorting.
+    pub scoring: Scoring,
+    pub letter_values: LetterValues, // What each letter is worth with Scoring::Ruzzle.
+    // Scores results instead of scoring and letter_values, if set. Anagrams
+    // have no board to score on, so solve_anagram() ignores it.
+    pub scorer: Option<SharedScorer<W, H>>,
+    // The search stops at the deadline, or once cancel is set, leaving the
+    // results found so far. count_words() doesn't stop for either.
+    pub deadline: Option<Instant>,
+    pub cancel: Option<CancelFlag>,
+    pub path_policy: PathPolicy, // Which paths are reported when a word has several.
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PathPolicy {
+    #[default]
+    AllPaths, // Each as a result of its own; group_paths() gathers them by word.
+    FirstOnly, // The first found in DFS order, as solve_unique_words().
+    BestScore, // The highest scoring, as solve_best_words().
+}
+
+// Set from anywhere (e.g. another thread) to stop the solves given it in
+// their SolveOptions. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(atomic::Ordering::Relaxed)
+    }
+}
+
+// The same flag, not just the same state.
+impl PartialEq for CancelFlag {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancelFlag {}
+
+// How many tiles the search steps onto between looks at the deadline and
+// cancel flag, so that reading the clock doesn't slow it down.
+const STOP_CHECK_INTERVAL: u32 = 1024;
+
+impl<const W: usize, const H: usize> SolveOptions<W, H> {
+    // What word, spelled along path, is worth by these options.
+    pub fn score(&self, board: &Board<Tile, W, H>, path: &Path<W, H>, word: &str) -> u32 {
+        match &self.scorer {
+            Some(scorer) => scorer.0.score(board, &self.bonuses, path, word),
+            None => self
+                .scoring
+                .score_path(&self.letter_values, board, &self.bonuses, path),
+        }
+    }
+
+    // How score() arrives at its score.
+    pub fn explain(
+        &self,
+        board: &Board<Tile, W, H>,
+        path: &Path<W, H>,
+        word: &str,
+    ) -> ScoreBreakdown {
+        match &self.scorer {
+            Some(scorer) => scorer.0.explain(board, &self.bonuses, path, word),
+            None => self
+                .scoring
+                .explain_path(&self.letter_values, board, &self.bonuses, path),
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+            || self.cancel.as_ref().is_some_and(CancelFlag::is_cancelled)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Sort {
+    // By path, then word: first by start tile in row order, then by the
+    // next tile and so on, each path before those extending it, and the
+    // same however the dictionary was built. Mostly the order the DFS finds
+    // them in, but not on a wildcard, whose every letter is tried before the
+    // next tile.
+    #[default]
+    ByPath,
+    ByScore,  // Highest score first.
+    ByLength, // Longest word first, in letters rather than tiles.
+    Alphabetical,
+    // Most frequent first, then highest score. Words without a frequency
+    // come after all those with one.
+    ByFrequency,
+}
+
+impl Sort {
+    // What ByPath was called before it was guaranteed an order.
+    #[deprecated(note = "use Sort::ByPath")]
+    #[allow(non_upper_case_globals)]
+    pub const Unsorted: Sort = Sort::ByPath;
+
+    // Ties are broken by word, then by path, so that results always
+    // come out in the same order.
+    pub fn compare<const W: usize, const H: usize>(
+        self,
+        a: &SolveResult<W, H>,
+        b: &SolveResult<W, H>,
+    ) -> Ordering {
+        let primary = match self {
+            Sort::ByPath => return a.path.cmp(&b.path).then_with(|| a.word.cmp(&b.word)),
+            Sort::Alphabetical => Ordering::Equal,
+            Sort::ByScore => b.score.cmp(&a.score),
+            Sort::ByLength => b.word.chars().count().cmp(&a.word.chars().count()),
+            Sort::ByFrequency => b
+                .frequency()
+                .cmp(&a.frequency())
+                .then_with(|| b.score.cmp(&a.score)),
+        };
+        primary
+            .then_with(|| a.word.cmp(&b.word))
+            .then_with(|| a.path.cmp(&b.path))
+    }
+
+    // Also for ByPath, since the DFS order differs from it on wildcards,
+    // and solve_best_words() and the like keep a word where its first path
+    // was found, whichever path they keep.
+    pub fn sort<const W: usize, const H: usize>(self, results: &mut [SolveResult<W, H>]) {
+        results.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+// Keeps the paths of the path policy, then sorts results and applies the
+// limit. results must be in DFS order, for the policy's ties.
+fn finish<const W: usize, const H: usize>(
+    opts: &SolveOptions<W, H>,
+    results: &mut Vec<SolveResult<W, H>>,
+) {
+    keep_paths(opts.path_policy, results);
+    opts.sort.sort(results);
+    if let Some(limit) = opts.limit {
+        results.truncate(limit);
+    }
+}
+
+// Keeps only the paths policy asks for of results, which must be in DFS
+// order. Each word kept stays where its first path was.
+fn keep_paths<const W: usize, const H: usize>(
+    policy: PathPolicy,
+    results: &mut Vec<SolveResult<W, H>>,
+) {
+    match policy {
+        PathPolicy::AllPaths => {}
+        PathPolicy::FirstOnly => {
+            let mut seen = HashSet::new();
+            results.retain(|result| seen.insert(result.word.clone()));
+        }
+        PathPolicy::BestScore => {
+            let mut best: Vec<SolveResult<W, H>> = vec![];
+            let mut positions: HashMap<String, usize> = HashMap::new();
+            for result in std::mem::take(results) {
+                match positions.get(&result.word) {
+                    Some(&pos) => {
+                        if result.score > best[pos].score {
+                            best[pos] = result;
+                        }
+                    }
+                    None => {
+                        positions.insert(result.word.clone(), best.len());
+                        best.push(result);
+                    }
+                }
+            }
+            *results = best;
+        }
+    }
+}
+
+// Gathers results as they are found. With a limit, only the best results so
+// far are kept, in a heap whose top is the worst of them, so that memory
+// stays proportional to the limit rather than to the number of paths. Which
+// paths a path policy keeps can't be known until all are in, so with one
+// every result is kept.
+struct Collector<const W: usize, const H: usize> {
+    sort: Sort,
+    limit: Option<usize>,
+    path_policy: PathPolicy,
+    results: Vec<SolveResult<W, H>>,
+    heap: BinaryHeap<Ranked<W, H>>,
+}
+
+// A result ordered by where it ends up after sorting.
+struct Ranked<const W: usize, const H: usize>(Sort, SolveResult<W, H>);
+
+impl<const W: usize, const H: usize> Ord for Ranked<W, H> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.compare(&self.1, &other.1)
+    }
+}
+
+impl<const W: usize, const H: usize> PartialOrd for Ranked<W, H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const W: usize, const H: usize> PartialEq for Ranked<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<const W: usize, const H: usize> Eq for Ranked<W, H> {}
+
+impl<const W: usize, const H: usize> Collector<W, H> {
+    fn new(opts: &SolveOptions<W, H>) -> Self {
+        Collector {
+            sort: opts.sort,
+            limit: opts.limit,
+            path_policy: opts.path_policy,
+            results: vec![],
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn push(&mut self, result: SolveResult<W, H>) {
+        match self.limit {
+            Some(limit) if self.path_policy == PathPolicy::AllPaths => {
+                self.heap.push(Ranked(self.sort, result));
+                if self.heap.len() > limit {
+                    self.heap.pop();
+                }
+            }
+            _ => self.results.push(result),
+        }
+    }
+
+    fn finish(mut self) -> Vec<SolveResult<W, H>> {
+        if self.heap.is_empty() {
+            keep_paths(self.path_policy, &mut self.results);
+            self.sort.sort(&mut self.results);
+            if let Some(limit) = self.limit {
+                self.results.truncate(limit);
+            }
+            self.results
+        } else {
+            let ranked = self.heap.into_sorted_vec();
+            ranked.into_iter().map(|Ranked(_, result)| result).collect()
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> Default for SolveOptions<W, H> {
+    fn default() -> Self {
+        // Ruzzle only accepts words of two letters or more.
+        SolveOptions {
+            min_len: 2,
+            max_len: None,
+            bonuses: BonusBoard::default(),
+            sort: Sort::ByPath,
+            allowed: BoardMask::full(),
+            must_include: None,
+            limit: None,
+            scoring: Scoring::Ruzzle,
+            letter_values: LetterValues::default(),
+            scorer: None,
+            deadline: None,
+            cancel: None,
+            path_policy: PathPolicy::AllPaths,
+        }
+    }
+}
+
+pub fn solve<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Vec<SolveResult<W, H>> {
+    let mut out = Collector::new(opts);
+    search_all(dict, board, opts, |word, path, node| {
+        out.push(make_result(board, opts, word, path, node.payload()))
+    });
+    out.finish()
+}
+
+// Calls f with each accepted word and its path, in DFS order, instead of
+// collecting results. Both are borrowed from the search and only valid for
+// the duration of the call.
+pub fn solve_with<D: Dictionary, const W: usize, const H: usize, F: FnMut(&str, &Path<W, H>)>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    mut f: F,
+) {
+    search_all(dict, board, opts, |word, path, _| f(word, path));
+}
+
+// Like solve_with(), also passing f the dictionary cursor of the word.
+fn search_all<'d, D: Dictionary, const W: usize, const H: usize>(
+    dict: &'d D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    mut f: impl FnMut(&str, &Path<W, H>, D::Cursor<'d>),
+) {
+    for idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+        search_from::<_, W, H, _, false>(dict.root(), board, opts, idx, &mut f);
+    }
+}
+
+fn search_from<
+    C: Cursor,
+    const W: usize,
+    const H: usize,
+    F: FnMut(&str, &Path<W, H>, C),
+    const COUNT: bool,
+>(
+    root: C,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    idx: BoardIndex<W, H>,
+    mut emit: F,
+) -> Counters {
+    let starts = BoardMask::default().with_at(true, idx) & opts.allowed;
+    let mut walker = Walker::<C, W, H, COUNT>::new(root, board, opts, starts);
+    while walker.advance() {
+        emit(&walker.word, &walker.path, walker.node());
+    }
+    walker.counters
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct Counters {
+    nodes_expanded: u64,
+    trie_descents: u64,
+    stopped: bool, // Counted or not: whether the deadline or cancel flag cut the search short.
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveReport<const W: usize = 4, const H: usize = 4> {
+    pub results: Vec<SolveResult<W, H>>, // As from solve().
+    pub nodes_expanded: u64,             // Trie nodes stepped onto, i.e. DFS calls.
+    pub trie_descents: u64,              // Tiles looked up in the trie, found or not.
+    pub elapsed: Duration,
+    pub unique_words: usize,
+    pub total_score: u64, // Best score of each unique word, summed.
+    pub completed: bool,  // False if the deadline or cancel flag stopped the search early.
+}
+
+// Like solve(), but also counts how much work the search did.
+pub fn solve_report<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> SolveReport<W, H> {
+    let start = Instant::now();
+    let mut collector = Collector::new(opts);
+    let mut counters = Counters::default();
+    for idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+        let from = search_from::<_, W, H, _, true>(
+            dict.root(),
+            board,
+            opts,
+            idx,
+            |word: &str, path: &Path<W, H>, node: D::Cursor<'_>| {
+                collector.push(make_result(board, opts, word, path, node.payload()))
+            },
+        );
+        counters.nodes_expanded += from.nodes_expanded;
+        counters.trie_descents += from.trie_descents;
+        counters.stopped |= from.stopped;
+    }
+    let results = collector.finish();
+    let elapsed = start.elapsed();
+
+    let mut best: HashMap<&str, u32> = HashMap::new();
+    for result in &results {
+        let score = best.entry(&result.word).or_default();
+        *score = (*score).max(result.score);
+    }
+    SolveReport {
+        unique_words: best.len(),
+        total_score: best.values().map(|&score| u64::from(score)).sum(),
+        nodes_expanded: counters.nodes_expanded,
+        trie_descents: counters.trie_descents,
+        completed: !counters.stopped,
+        elapsed,
+        results,
+    }
+}
+
+// What stepping onto a tile adds to the word. node is the trie node reached,
+// which tells what letter a wildcard was bound to.
+pub(crate) fn push_tile<C: Cursor>(word: &mut String, tile: Tile, node: C) {
+    match tile {
+        Tile::Wildcard => word.extend(node.ch()),
+        tile => word.extend(tile.letters()),
+    }
+}
+
+// Whether a word ending on the visited tiles is reported.
+// Ruzzle's minimum word length counts letters, so a "Qu" tile counts twice.
+fn accepted<const W: usize, const H: usize>(
+    letter_count: usize,
+    visited: BoardMask<W, H>,
+    opts: &SolveOptions<W, H>,
+) -> bool {
+    letter_count >= opts.min_len && opts.must_include.is_none_or(|req| visited.get(req))
+}
+
+// Whether a path of path_len tiles ending on idx is worth extending: it has
+// to stay within max_len, and still be able to reach the required tile.
+fn can_extend<const W: usize, const H: usize>(
+    opts: &SolveOptions<W, H>,
+    visited: BoardMask<W, H>,
+    idx: BoardIndex<W, H>,
+    path_len: usize,
+) -> bool {
+    let reachable = match opts.must_include {
+        Some(req) if !visited.get(req) => {
+            opts.allowed.get(req)
+                && opts
+                    .max_len
+                    .is_none_or(|max_len| path_len + idx.chebyshev_distance(req) <= max_len)
+        }
+        _ => true,
+    };
+    reachable && opts.max_len.is_none_or(|max_len| path_len < max_len)
+}
+
+fn make_result<const W: usize, const H: usize>(
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    word: &str,
+    path: &Path<W, H>,
+    payload: Option<Payload>,
+) -> SolveResult<W, H> {
+    let mut wildcard_bindings = vec![];
+    let mut chars = word.chars();
+    for &idx in path {
+        match board[idx] {
+            Tile::Wildcard => wildcard_bindings.extend(chars.next().map(|ch| (idx, ch))),
+            tile => {
+                chars.nth(tile.letter_count() - 1);
+            }
+        }
+    }
+    SolveResult {
+        word: word.to_string(),
+        path: path.clone(),
+        score: opts.score(board, path, word),
+        wildcard_bindings,
+        payload,
+    }
+}
+
+// Like solve(), but each starting tile is searched on its own rayon task.
+// The results come back in the same order as from solve().
+#[cfg(feature = "parallel")]
+pub fn solve_parallel<D: Dictionary + Sync, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Vec<SolveResult<W, H>> {
+    use rayon::prelude::*;
+
+    let starts: Vec<BoardIndex<W, H>> = BoardIndex::<W, H>::all_indices_within_bounds().collect();
+    let per_start: Vec<Vec<SolveResult<W, H>>> = starts
+        .into_par_iter()
+        .map(|idx| {
+            let mut out = Collector::new(opts);
+            search_from::<_, W, H, _, false>(
+                dict.root(),
+                board,
+                opts,
+                idx,
+                |word: &str, path: &Path<W, H>, node: D::Cursor<'_>| {
+                    out.push(make_result(board, opts, word, path, node.payload()))
+                },
+            );
+            match opts.path_policy {
+                PathPolicy::AllPaths => out.finish(),
+                // Left in DFS order, since a word's paths can start anywhere.
+                _ => out.results,
+            }
+        })
+        .collect();
+    // The best results overall are among the best from each start.
+    let mut out = per_start.concat();
+    finish(opts, &mut out);
+    out
+}
+
+// Lazily yields the same results as solve(), in DFS order. The sort, limit
+// and path policy options are ignored, since they would need every result
+// up front.
+pub fn solve_iter<'a, D: Dictionary, const W: usize, const H: usize>(
+    dict: &'a D,
+    board: &'a Board<Tile, W, H>,
+    opts: &'a SolveOptions<W, H>,
+) -> SolveIter<'a, D::Cursor<'a>, W, H> {
+    SolveIter {
+        walker: Walker::new(dict.root(), board, opts, opts.allowed),
+    }
+}
+
+pub struct SolveIter<'a, C = &'a TrieNode, const W: usize = 4, const H: usize = 4> {
+    walker: Walker<'a, C, W, H, false>,
+}
+
+impl<C: Cursor, const W: usize, const H: usize> Iterator for SolveIter<'_, C, W, H> {
+    type Item = SolveResult<W, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let walker = &mut self.walker;
+        if walker.advance() {
+            Some(make_result(
+                walker.board,
+                walker.opts,
+                &walker.word,
+                &walker.path,
+                walker.node().payload(),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+// The depth first search behind every solver, with an explicit stack instead
+// of recursion. advance() runs it until the next accepted word.
+// COUNT turns on the SolveReport<W, H> counters. It's a const parameter so that
+// the uncounted search compiles without them.
+struct Walker<'a, C, const W: usize, const H: usize, const COUNT: bool> {
+    board: &'a Board<Tile, W, H>,
+    opts: &'a SolveOptions<W, H>,
+    stack: Vec<Frame<C, W, H>>,
+    bindings: Vec<C>, // Children a wildcard on the stack is still to be bound to.
+    path: Path<W, H>,
+    word: String,
+    counters: Counters,
+    looked_at: BoardMask<W, H>, // Every tile the search has read, or queued to read.
+    steps: u32,                 // Tiles stepped onto, for STOP_CHECK_INTERVAL.
+}
+
+// One step of the DFS.
+struct Frame<C, const W: usize, const H: usize> {
+    node: C,
+    visited: BoardMask<W, H>,
+    candidates: BoardMask<W, H>, // Unvisited neighbours not yet stepped onto.
+    wildcard: Option<(BoardIndex<W, H>, usize)>, // Wildcard neighbour, and where its bindings start.
+    word_len: usize,                             // Length of the word before this step.
+}
+
+impl<'a, C: Cursor, const W: usize, const H: usize, const COUNT: bool> Walker<'a, C, W, H, COUNT> {
+    fn new(
+        root: C,
+        board: &'a Board<Tile, W, H>,
+        opts: &'a SolveOptions<W, H>,
+        starts: BoardMask<W, H>,
+    ) -> Self {
+        // The bottom frame stands for the root, with the starting tiles as candidates.
+        let root_frame = Frame {
+            node: root,
+            visited: BoardMask::default(),
+            candidates: starts,
+            wildcard: None,
+            word_len: 0,
+        };
+        Walker {
+            board,
+            opts,
+            stack: vec![root_frame],
+            bindings: vec![],
+            path: vec![],
+            word: String::new(),
+            counters: Counters::default(),
+            looked_at: starts,
+            steps: 0,
+        }
+    }
+
+    // Steps onto idx, reaching node. Returns whether that spells a word.
+    fn enter(&mut self, node: C, visited: BoardMask<W, H>, idx: BoardIndex<W, H>) -> bool {
+        if COUNT {
+            self.counters.nodes_expanded += 1;
+        }
+        // The first step looks too, so that a search given a passed deadline
+        // stops straight away.
+        if self.steps.is_multiple_of(STOP_CHECK_INTERVAL) && self.opts.should_stop() {
+            self.counters.stopped = true;
+        }
+        self.steps = self.steps.wrapping_add(1);
+        let visited = visited.with_at(true, idx);
+        let word_len = self.word.len();
+        self.path.push(idx);
+        push_tile(&mut self.word, self.board[idx], node);
+        let candidates = if can_extend(self.opts, visited, idx, self.path.len()) {
+            let neighbours = idx
+                .get_neighbouring()
+                .fold(BoardMask::default(), |mask, n_idx| {
+                    mask.with_at(true, n_idx)
+                });
+            neighbours & !visited & self.opts.allowed
+        } else {
+            BoardMask::default()
+        };
+        self.looked_at = self.looked_at | candidates;
+        self.stack.push(Frame {
+            node,
+            visited,
+            candidates,
+            wildcard: None,
+            word_len,
+        });
+        node.is_terminal() && accepted(self.word.chars().count(), visited, self.opts)
+    }
+
+    // The trie node of the word just reached.
+    fn node(&self) -> C {
+        self.stack.last().expect("advance() returned true").node
+    }
+
+    // Runs the search until it reaches the next word, which is then in word
+    // and path, and node(). Returns false once the search is done.
+    fn advance(&mut self) -> bool {
+        loop {
+            if self.counters.stopped {
+                self.stack.clear();
+            }
+            let Some(frame) = self.stack.last_mut() else {
+                return false;
+            };
+            let (node, visited) = (frame.node, frame.visited);
+            if let Some((n_idx, start)) = frame.wildcard {
+                if self.bindings.len() > start {
+                    let child = self.bindings.pop().unwrap();
+                    if self.enter(child, visited, n_idx) {
+                        return true;
+                    }
+                } else {
+                    frame.wildcard = None;
+                }
+                continue;
+            }
+            // Neighbours are stepped onto in reading order, like get_neighbouring().
+            match frame.candidates.first_set() {
+                Some(n_idx) => {
+                    frame.candidates.set(n_idx, false);
+                    if COUNT {
+                        self.counters.trie_descents += 1;
+                    }
+                    if self.board[n_idx] == Tile::Wildcard {
+                        let start = self.bindings.len();
+                        frame.wildcard = Some((n_idx, start));
+                        self.bindings.extend(node.children());
+                        // Reversed, so that popping binds them in order.
+                        self.bindings[start..].reverse();
+                    } else if let Some(child) = descend_tile(node, self.board[n_idx]).next()
+                        && self.enter(child, visited, n_idx)
+                    {
+                        return true;
+                    }
+                }
+                None => {
+                    let word_len = frame.word_len;
+                    self.stack.pop();
+                    // The root frame has no tile of its own.
+                    if !self.stack.is_empty() {
+                        self.path.pop();
+                        self.word.truncate(word_len);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Solves a board once, then again after each change of a tile, re-running
+// only the part of the search the change could affect. The results of each
+// starting tile are kept along with the tiles its search read; a change
+// re-runs just the starts that read the changed tile. (The tiles their words
+// use would not do: a new letter can spell a word through a tile no old word
+// went through.)
+pub struct Solver<'a, D, const W: usize = 4, const H: usize = 4> {
+    dict: &'a D,
+    board: Board<Tile, W, H>,
+    opts: SolveOptions<W, H>,
+    starts: Vec<Start<W, H>>, // In BoardIndex order.
+}
+
+struct Start<const W: usize, const H: usize> {
+    results: Vec<SolveResult<W, H>>, // In DFS order, and unlimited.
+    looked_at: BoardMask<W, H>,
+}
+
+impl<'a, D: Dictionary, const W: usize, const H: usize> Solver<'a, D, W, H> {
+    pub fn new(dict: &'a D, board: Board<Tile, W, H>) -> Self {
+        Self::with_options(dict, board, SolveOptions::default())
+    }
+
+    pub fn with_options(dict: &'a D, board: Board<Tile, W, H>, opts: SolveOptions<W, H>) -> Self {
+        let mut solver = Solver {
+            dict,
+            board,
+            opts,
+            starts: vec![],
+        };
+        solver.starts = BoardIndex::<W, H>::all_indices_within_bounds()
+            .map(|idx| solver.search_start(idx))
+            .collect();
+        solver
+    }
+
+    pub fn board(&self) -> &Board<Tile, W, H> {
+        &self.board
+    }
+
+    // The same as solve() of the board as it is now.
+    pub fn results(&self) -> Vec<SolveResult<W, H>> {
+        let mut results: Vec<SolveResult<W, H>> = self
+            .starts
+            .iter()
+            .flat_map(|start| start.results.iter().cloned())
+            .collect();
+        finish(&self.opts, &mut results);
+        results
+    }
+
+    // Puts tile on the board at idx, and returns how many starting tiles had
+    // to be searched again.
+    pub fn update_tile(&mut self, idx: BoardIndex<W, H>, tile: impl Into<Tile>) -> usize {
+        let tile = tile.into();
+        if self.board[idx] == tile {
+            return 0;
+        }
+        self.board[idx] = tile;
+        let mut searched = 0;
+        for start_idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+            if self.starts[start_idx.flattened].looked_at.get(idx) {
+                self.starts[start_idx.flattened] = self.search_start(start_idx);
+                searched += 1;
+            }
+        }
+        searched
+    }
+
+    fn search_start(&self, idx: BoardIndex<W, H>) -> Start<W, H> {
+        let mut unlimited = self.opts.clone();
+        unlimited.sort = Sort::ByPath;
+        unlimited.limit = None;
+        let starts = BoardMask::default().with_at(true, idx) & self.opts.allowed;
+        let mut walker =
+            Walker::<_, W, H, false>::new(self.dict.root(), &self.board, &unlimited, starts);
+        let mut results = vec![];
+        while walker.advance() {
+            results.push(make_result(
+                walker.board,
+                walker.opts,
+                &walker.word,
+                &walker.path,
+                walker.node().payload(),
+            ));
+        }
+        Start {
+            results,
+            looked_at: walker.looked_at,
+        }
+    }
+}
+
+// Every path of each word, in the order of results. With results from
+// solve() under PathPolicy::AllPaths, that's every distinct path, in DFS
+// order.
+pub fn group_paths<const W: usize, const H: usize>(
+    results: &[SolveResult<W, H>],
+) -> BTreeMap<String, Vec<Path<W, H>>> {
+    let mut paths: BTreeMap<String, Vec<Path<W, H>>> = BTreeMap::new();
+    for result in results {
+        paths
+            .entry(result.word.clone())
+            .or_default()
+            .push(result.path.clone());
+    }
+    paths
+}
+
+// Moves each result into the group of the tile its path starts on, for
+// showing the words starting at a tile. Each group is sorted by score.
+pub fn group_by_start<const W: usize, const H: usize>(
+    results: Vec<SolveResult<W, H>>,
+) -> Board<Vec<SolveResult<W, H>>, W, H> {
+    let mut groups: Board<Vec<SolveResult<W, H>>, W, H> = Board::from_fn(|_| vec![]);
+    for result in results {
+        groups[result.path[0]].push(result);
+    }
+    for idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+        Sort::ByScore.sort(&mut groups[idx]);
+    }
+    groups
+}
+
+// solve(), grouped by group_by_start().
+pub fn solve_grouped<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Board<Vec<SolveResult<W, H>>, W, H> {
+    group_by_start(solve(dict, board, opts))
+}
+
+// Like solve(), but only the first path found (in DFS order) is kept for
+// each word, as with PathPolicy::FirstOnly.
+pub fn solve_unique_words<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Vec<SolveResult<W, H>> {
+    let opts = SolveOptions {
+        path_policy: PathPolicy::FirstOnly,
+        ..opts.clone()
+    };
+    solve(dict, board, &opts)
+}
+
+// Like solve(), but only the highest scoring path is kept for each word, as
+// with PathPolicy::BestScore. Ties go to the path found first in DFS order.
+pub fn solve_best_words<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Vec<SolveResult<W, H>> {
+    let opts = SolveOptions {
+        path_policy: PathPolicy::BestScore,
+        ..opts.clone()
+    };
+    solve(dict, board, &opts)
+}
+
+// Ratings of a player's score on a board, as the percentage of the total
+// possible score each takes, lowest first.
+pub const RATINGS: [(&str, u32); 3] = [("good", 25), ("great", 50), ("excellent", 75)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolveSummary {
+    pub unique_words: usize,
+    pub total_paths: usize,
+    pub total_score: u32, // Best score of each unique word, summed.
+    // None without words, or from count_words(), which builds no words.
+    pub longest_word: Option<String>, // The first found of the longest.
+    pub best_word: Option<(String, u32)>, // The first found of the highest scoring.
+}
+
+impl SolveSummary {
+    // The summary of solve()'s results, or any of its variants'.
+    pub fn from_results<const W: usize, const H: usize>(results: &[SolveResult<W, H>]) -> Self {
+        let mut best: HashMap<&str, u32> = HashMap::new();
+        let mut longest: Option<&SolveResult<W, H>> = None;
+        let mut best_word: Option<&SolveResult<W, H>> = None;
+        for result in results {
+            let score = best.entry(&result.word).or_default();
+            *score = (*score).max(result.score);
+            let len = |result: &SolveResult<W, H>| result.word.chars().count();
+            if longest.is_none_or(|longest| len(result) > len(longest)) {
+                longest = Some(result);
+            }
+            if best_word.is_none_or(|best| result.score > best.score) {
+                best_word = Some(result);
+            }
+        }
+        SolveSummary {
+            unique_words: best.len(),
+            total_paths: results.len(),
+            total_score: best.values().sum(),
+            longest_word: longest.map(|result| result.word.clone()),
+            best_word: best_word.map(|result| (result.word.clone(), result.score)),
+        }
+    }
+
+    // The score each of RATINGS starts at on this board.
+    pub fn rating_scores(&self) -> Vec<(&'static str, u32)> {
+        RATINGS
+            .iter()
+            .map(|&(name, percent)| (name, (self.total_score * percent).div_ceil(100)))
+            .collect()
+    }
+
+    // The best of RATINGS that score reaches, if any.
+    pub fn rating(&self, score: u32) -> Option<&'static str> {
+        self.rating_scores()
+            .into_iter()
+            .rev()
+            .find(|&(_, needed)| score >= needed)
+            .map(|(name, _)| name)
+    }
+}
+
+// "12 words for 150 points", then the longest and best words and the
+// scores needed for each rating, a line each.
+impl fmt::Display for SolveSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} words for {} points",
+            self.unique_words, self.total_score
+        )?;
+        if let Some(word) = &self.longest_word {
+            writeln!(
+                f,
+                "Longest word: {} ({} letters)",
+                word,
+                word.chars().count()
+            )?;
+        }
+        if let Some((word, score)) = &self.best_word {
+            writeln!(f, "Best word: {} ({} points)", word, score)?;
+        }
+        let ratings: Vec<String> = self
+            .rating_scores()
+            .iter()
+            .map(|(name, score)| format!("{} {}", name, score))
+            .collect();
+        writeln!(f, "Ratings: {}", ratings.join(", "))
+    }
+}
+
+// Counts what solve() would find without building any words or paths, for
+// when only the totals matter (e.g. rating boards). A word is identified by
+// its terminal node, so this only works on a TrieNode. A custom scorer
+// needs every word and path, so with one this just sums up solve().
+pub fn count_words<const W: usize, const H: usize>(
+    root: &TrieNode,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> SolveSummary {
+    if opts.scorer.is_some() {
+        let all = SolveOptions {
+            sort: Sort::ByPath,
+            limit: None,
+            deadline: None,
+            cancel: None,
+            path_policy: PathPolicy::AllPaths,
+            ..opts.clone()
+        };
+        return SolveSummary {
+            longest_word: None,
+            best_word: None,
+            ..SolveSummary::from_results(&solve(root, board, &all))
+        };
+    }
+    let mut counter = Counter {
+        board,
+        opts,
+        best: HashMap::new(),
+        total_paths: 0,
+    };
+    let start = Tally {
+        tiles: 0,
+        letters: 0,
+        letter_score: 0,
+        word_multiplier: 1,
+    };
+    for idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+        if opts.allowed.get(idx) {
+            for child in descend_tile(root, board[idx]) {
+                counter.dfs(child, BoardMask::default(), idx, start);
+            }
+        }
+    }
+    SolveSummary {
+        unique_words: counter.best.len(),
+        total_paths: counter.total_paths,
+        total_score: counter.best.values().sum(),
+        longest_word: None,
+        best_word: None,
+    }
+}
+
+// What score_word() would add up over the path so far.
+#[derive(Copy, Clone)]
+struct Tally {
+    tiles: usize,
+    letters: usize,
+    letter_score: u32,
+    word_multiplier: u32,
+}
+
+struct Counter<'a, const W: usize, const H: usize> {
+    board: &'a Board<Tile, W, H>,
+    opts: &'a SolveOptions<W, H>,
+    best: HashMap<*const TrieNode, u32>, // Best score per terminal node.
+    total_paths: usize,
+}
+
+impl<const W: usize, const H: usize> Counter<'_, W, H> {
+    fn dfs(
+        &mut self,
+        node: &TrieNode,
+        visited: BoardMask<W, H>,
+        idx: BoardIndex<W, H>,
+        tally: Tally,
+    ) {
+        let visited = visited.with_at(true, idx);
+        let (tile, bonus) = (self.board[idx], self.opts.bonuses[idx]);
+        let tally = Tally {
+            tiles: tally.tiles + 1,
+            letters: tally.letters + tile.letter_count(),
+            letter_score: tally.letter_score
+                + self.opts.letter_values.tile_value(tile) * bonus.letter_multiplier(),
+            word_multiplier: tally.word_multiplier * bonus.word_multiplier(),
+        };
+        if node.is_terminal && accepted(tally.letters, visited, self.opts) {
+            let score = match self.opts.scoring {
+                Scoring::Ruzzle => {
+                    tally.letter_score * tally.word_multiplier + length_bonus(tally.letters)
+                }
+                Scoring::Boggle => boggle_score(tally.letters),
+            };
+            let best = self.best.entry(node).or_default();
+            *best = (*best).max(score);
+            self.total_paths += 1;
+        }
+        if can_extend(self.opts, visited, idx, tally.tiles) {
+            for n_idx in idx.get_neighbouring() {
+                if !visited.get(n_idx) && self.opts.allowed.get(n_idx) {
+                    for child in descend_tile(node, self.board[n_idx]) {
+                        self.dfs(child, visited, n_idx, tally);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The recursive search that Walker replaced, kept to check it against.
+#[cfg(test)]
+struct Search<'a, F, const W: usize, const H: usize> {
+    board: &'a Board<Tile, W, H>,
+    opts: &'a SolveOptions<W, H>,
+    path: Path<W, H>,
+    word: String,
+    emit: F,
+}
+
+#[cfg(test)]
+impl<F: FnMut(&str, &Path<W, H>, Option<Payload>), const W: usize, const H: usize>
+    Search<'_, F, W, H>
+{
+    fn dfs<C: Cursor>(&mut self, node: C, visited: BoardMask<W, H>, idx: BoardIndex<W, H>) {
+        let new_visited = visited.with_at(true, idx);
+        let neighbours = idx
+            .get_neighbouring()
+            .filter(|&n_idx| !new_visited.get(n_idx) && self.opts.allowed.get(n_idx));
+        let word_len = self.word.len();
+        self.path.push(idx);
+        push_tile(&mut self.word, self.board[idx], node);
+        if node.is_terminal() && accepted(self.word.chars().count(), new_visited, self.opts) {
+            (self.emit)(&self.word, &self.path, node.payload());
+        }
+        if can_extend(self.opts, new_visited, idx, self.path.len()) {
+            for n_idx in neighbours {
+                for child in descend_tile(node, self.board[n_idx]) {
+                    self.dfs(child, new_visited, n_idx);
+                }
+            }
+        }
+        self.word.truncate(word_len);
+        self.path.pop();
+    }
+}
+
+#[cfg(test)]
+fn solve_recursive<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+) -> Vec<SolveResult<W, H>> {
+    let mut out = vec![];
+    let mut search = Search {
+        board,
+        opts,
+        path: vec![],
+        word: String::new(),
+        emit: |word: &str, path: &Path<W, H>, payload| {
+            out.push(make_result(board, opts, word, path, payload))
+        },
+    };
+    for idx in BoardIndex::<W, H>::all_indices_within_bounds() {
+        if opts.allowed.get(idx) {
+            for child in descend_tile(dict.root(), board[idx]) {
+                search.dfs(child, BoardMask::default(), idx);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Index4x4, RuzzleBoard};
+    use crate::score::{BoggleScorer, Bonus, RuzzleScorer, score_word};
+
+    // Results in DFS order.
+    fn search<D: Dictionary, const W: usize, const H: usize>(
+        dict: &D,
+        board: &Board<Tile, W, H>,
+        opts: &SolveOptions<W, H>,
+    ) -> Vec<SolveResult<W, H>> {
+        let mut out = vec![];
+        search_all(dict, board, opts, |word, path, node| {
+            out.push(make_result(board, opts, word, path, node.payload()))
+        });
+        out
+    }
+
+    #[test]
+    fn result_words_match_paths() {
+        let mut root = TrieNode::new_root();
+        root.add_word("abc");
+        root.add_word("fab");
+        root.add_word("pip");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+
+        let results = solve(&root, &board, &SolveOptions::default());
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["abc", "fab"]);
+        for result in &results {
+            let traced = board.spell(&result.path);
+            assert_eq!(traced, result.word);
+            assert_eq!(
+                result.score,
+                score_word(
+                    &LetterValues::default(),
+                    &board,
+                    &BonusBoard::default(),
+                    &result.path
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn unique_words_collapse_duplicates() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ata");
+        let board = RuzzleBoard::try_from("aataxxxxxxxxxxxx").unwrap();
+
+        let all = solve(&root, &board, &SolveOptions::default());
+        assert!(all.len() > 1);
+
+        let unique = solve_unique_words(&root, &board, &SolveOptions::default());
+        assert_eq!(unique.len(), 1);
+        assert_eq!(unique[0], all[0]);
+    }
+
+    #[test]
+    fn min_len_excludes_short_words() {
+        let mut root = TrieNode::new_root();
+        root.add_word("a");
+        root.add_word("ab");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+
+        let default = solve(&root, &board, &SolveOptions::default());
+        let words: Vec<&str> = default.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["ab"]);
+
+        let opts = SolveOptions {
+            min_len: 1,
+            ..SolveOptions::default()
+        };
+        let results = solve(&root, &board, &opts);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["a", "ab"]);
+    }
+
+    #[test]
+    fn max_len_caps_path_depth() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ab");
+        root.add_word("abc");
+        root.add_word("abcd");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+
+        let opts = SolveOptions {
+            max_len: Some(3),
+            ..SolveOptions::default()
+        };
+        let results = solve(&root, &board, &opts);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["ab", "abc"]);
+    }
+
+    #[test]
+    fn best_words_prefer_double_word_path() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ab");
+        // Two 'b's next to the 'a', the second of which sits on a double word tile.
+        let board = RuzzleBoard::try_from("abxxbxxxxxxxxxxx").unwrap();
+        let opts = SolveOptions {
+            bonuses: BonusBoard::default().with_at(Bonus::DoubleWord, Index4x4::from_xy(0, 1)),
+            ..SolveOptions::default()
+        };
+
+        let all = solve(&root, &board, &opts);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].score, 5);
+        assert_eq!(all[1].score, 10);
+
+        let best = solve_best_words(&root, &board, &opts);
+        assert_eq!(best, vec![all[1].clone()]);
+    }
+
+    #[test]
+    fn sorts_by_frequency() {
+        let mut root = TrieNode::new_root();
+        let frequency = |frequency| Payload {
+            frequency: Some(frequency),
+            definition: None,
+        };
+        // By score: fab 9, abe 6, ab 5. By frequency: ab, abe, then fab.
+        root.add_word_with("ab", frequency(5000));
+        root.add_word_with("abe", frequency(40));
+        root.add_word("fab");
+        root.add_word("be");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let words_sorted_by = |sort| {
+            let opts = SolveOptions {
+                sort,
+                ..SolveOptions::default()
+            };
+            solve_best_words(&root, &board, &opts)
+                .into_iter()
+                .map(|result| result.word)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(words_sorted_by(Sort::ByScore), ["fab", "abe", "ab", "be"]);
+        // Without frequencies, fab 9 before be 5.
+        assert_eq!(
+            words_sorted_by(Sort::ByFrequency),
+            ["ab", "abe", "fab", "be"]
+        );
+    }
+
+    #[test]
+    fn sort_orders() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fa", "fab", "jin"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let words_sorted_by = |sort| {
+            let opts = SolveOptions {
+                sort,
+                ..SolveOptions::default()
+            };
+            solve_unique_words(&root, &board, &opts)
+                .into_iter()
+                .map(|result| result.word)
+                .collect::<Vec<String>>()
+        };
+
+        // "ab", "ba" and "fa" tie on 5 points, and "fab" ties with "jin" on length.
+        assert_eq!(
+            words_sorted_by(Sort::ByPath),
+            ["ab", "ba", "fa", "fab", "jin"]
+        );
+        assert_eq!(
+            words_sorted_by(Sort::ByScore),
+            ["jin", "fab", "ab", "ba", "fa"]
+        );
+        assert_eq!(
+            words_sorted_by(Sort::ByLength),
+            ["fab", "jin", "ab", "ba", "fa"]
+        );
+        assert_eq!(
+            words_sorted_by(Sort::Alphabetical),
+            ["ab", "ba", "fa", "fab", "jin"]
+        );
+
+        #[allow(deprecated)]
+        let unsorted = Sort::Unsorted;
+        assert_eq!(unsorted, Sort::ByPath);
+
+        // "quits" is five letters on four tiles.
+        let mut root = TrieNode::new_root();
+        root.add_word("abcd");
+        root.add_word("quits");
+        let board = RuzzleBoard::try_from("abcdq(u)itsxxxxxxxx").unwrap();
+        let opts = SolveOptions {
+            sort: Sort::ByLength,
+            ..SolveOptions::default()
+        };
+        let words: Vec<String> = solve(&root, &board, &opts)
+            .into_iter()
+            .map(|result| result.word)
+            .collect();
+        assert_eq!(words, ["quits", "abcd"]);
+    }
+
+    #[test]
+    fn sort_ties_broken_by_path() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ata");
+        let board = RuzzleBoard::try_from("aataxxxxxxxxxxxx").unwrap();
+        let opts = SolveOptions {
+            sort: Sort::ByScore,
+            ..SolveOptions::default()
+        };
+
+        let results = solve(&root, &board, &opts);
+        assert!(results.len() > 1);
+        assert!(results.windows(2).all(|pair| pair[0].path < pair[1].path));
+    }
+
+    #[test]
+    fn mixed_case_dictionary() {
+        let mut root = TrieNode::new_root();
+        root.add_word("FAB");
+        root.add_word("Jin");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+
+        let results = solve(&root, &board, &SolveOptions::default());
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["fab", "jin"]);
+    }
+
+    #[test]
+    fn counts_every_simple_path() {
+        // With 16 distinct letters and every 1-3 letter string in the
+        // dictionary, each simple path of up to 3 tiles is one result.
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let letters: Vec<char> = ('a'..='p').collect();
+        let mut root = TrieNode::new_root();
+        for &a in &letters {
+            root.add_word(&a.to_string());
+            for &b in &letters {
+                root.add_word(&format!("{a}{b}"));
+                for &c in &letters {
+                    root.add_word(&format!("{a}{b}{c}"));
+                }
+            }
+        }
+        let opts = SolveOptions {
+            min_len: 1,
+            ..SolveOptions::default()
+        };
+
+        let results = solve(&root, &board, &opts);
+        let count_of_len = |len| results.iter().filter(|r| r.path.len() == len).count();
+        assert_eq!(count_of_len(1), 16);
+        assert_eq!(count_of_len(2), 84); // Twice the number of edges.
+        assert_eq!(count_of_len(3), 408); // Sum of deg * (deg - 1) over middle tiles.
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_sequential() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "jin", "kop", "abfe", "ata", "plonk"] {
+            root.add_word(word);
+        }
+        for board in ["abcdefghijklmnop", "aataxxxxxxxxxxxx", "plonkabfeatajinx"] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            let opts = SolveOptions::default();
+            let as_set = |results: Vec<SolveResult>| {
+                results
+                    .into_iter()
+                    .map(|r| (r.word, r.path))
+                    .collect::<HashSet<_>>()
+            };
+            let sequential = as_set(solve(&root, &board, &opts));
+            let parallel = as_set(solve_parallel(&root, &board, &opts));
+            assert!(!sequential.is_empty());
+            assert_eq!(sequential, parallel);
+        }
+    }
+
+    #[test]
+    fn iter_matches_eager_solve() {
+        let mut root = TrieNode::new_root();
+        for word in ["a", "ab", "ba", "fab", "jin", "kop", "abfe", "ata", "plonk"] {
+            root.add_word(word);
+        }
+        for board in ["abcdefghijklmnop", "aataxxxxxxxxxxxx", "plonkabfeatajinx"] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            for opts in [
+                SolveOptions::default(),
+                SolveOptions {
+                    min_len: 1,
+                    max_len: Some(3),
+                    ..SolveOptions::default()
+                },
+            ] {
+                let eager = solve(&root, &board, &opts);
+                let lazy: Vec<SolveResult> = solve_iter(&root, &board, &opts).collect();
+                assert_eq!(lazy, eager);
+            }
+        }
+    }
+
+    #[test]
+    fn iter_stops_early() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ab");
+        root.add_word("fab");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let opts = SolveOptions::default();
+
+        let mut iter = solve_iter(&root, &board, &opts);
+        assert_eq!(iter.next().map(|r| r.word), Some("ab".to_string()));
+        // Only the first starting tile has been taken off the queue.
+        assert_eq!(iter.walker.stack[0].candidates.count_set(), 15);
+
+        let taken: Vec<SolveResult> = solve_iter(&root, &board, &opts).take(1).collect();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(solve_iter(&root, &board, &opts).take(10).count(), 2);
+    }
+
+    #[test]
+    fn callback_sees_every_result() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "jin", "ata", "abfe"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("aataabfejinxxxxx").unwrap();
+        let opts = SolveOptions::default();
+        let eager = solve(&root, &board, &opts);
+
+        let mut count = 0;
+        solve_with(&root, &board, &opts, |_, _| count += 1);
+        assert_eq!(count, eager.len());
+
+        let mut words = HashSet::new();
+        solve_with(&root, &board, &opts, |word, path| {
+            assert_eq!(path.len(), word.chars().count());
+            words.insert(word.to_string());
+        });
+        let eager_words: HashSet<String> = eager.into_iter().map(|r| r.word).collect();
+        assert_eq!(words, eager_words);
+    }
+
+    #[test]
+    fn wildcards_bind_to_letters() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "cab", "zap", "xyz"] {
+            root.add_word(word);
+        }
+        // ? a b x
+        // x x x x
+        let board = RuzzleBoard::try_from("?abxxxxxxxxxxxxx").unwrap();
+        let opts = SolveOptions {
+            sort: Sort::Alphabetical,
+            ..SolveOptions::default()
+        };
+        let results = solve(&root, &board, &opts);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        // "ab" is found a second time by binding the wildcard to 'b'.
+        assert_eq!(words, ["ab", "ab", "cab"]);
+        assert_eq!(
+            results[0].wildcard_bindings,
+            [(Index4x4::from_xy(0, 0), 'b')]
+        );
+        assert!(results[1].wildcard_bindings.is_empty());
+
+        let cab = &results[2];
+        assert_eq!(cab.wildcard_bindings, [(Index4x4::from_xy(0, 0), 'c')]);
+        assert_eq!(cab.score, 1 + 4); // The wildcard is worth nothing.
+
+        let lazy: Vec<SolveResult> = solve_iter(&root, &board, &SolveOptions::default()).collect();
+        assert_eq!(lazy, solve(&root, &board, &SolveOptions::default()));
+    }
+
+    #[test]
+    fn adjacent_wildcards_stress() {
+        // A pseudo random dictionary of 50k words, to stand in for a real one.
+        let mut root = TrieNode::new_root();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % n
+        };
+        for _ in 0..50_000 {
+            let len = 2 + next(9);
+            let word: String = (0..len).map(|_| (b'a' + next(26) as u8) as char).collect();
+            root.add_word(&word);
+        }
+        let board = RuzzleBoard::try_from("??stelanrotidemu").unwrap();
+
+        let results = solve(&root, &board, &SolveOptions::default());
+        assert!(!results.is_empty());
+        for result in &results {
+            assert!(root.contains_word(&result.word));
+            assert!(result.wildcard_bindings.len() <= 2);
+        }
+        let lazy = solve_iter(&root, &board, &SolveOptions::default()).count();
+        assert_eq!(lazy, results.len());
+    }
+
+    #[test]
+    fn qu_tile_spans_two_letters() {
+        let mut root = TrieNode::new_root();
+        for word in ["queen", "quee", "qeen", "quit", "een"] {
+            root.add_word(word);
+        }
+        // Qu e e n
+        // x  x x x
+        let board = RuzzleBoard::try_from("q(u)eenxxxxxxxxxxxx").unwrap();
+        let opts = SolveOptions::default();
+
+        let results = solve(&root, &board, &opts);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, ["quee", "queen", "een"]);
+        let queen = &results[1];
+        assert_eq!(queen.path.len(), 4);
+        assert_eq!(queen.score, 10 + 2 + 1 + 1 + 1 + 5);
+
+        let lazy: Vec<SolveResult> = solve_iter(&root, &board, &opts).collect();
+        assert_eq!(lazy, results);
+
+        // Two tiles, three letters, so still long enough at min_len 3.
+        root.add_word("qua");
+        let board = RuzzleBoard::try_from("q(u)axxxxxxxxxxxxxx").unwrap();
+        let opts = SolveOptions {
+            min_len: 3,
+            ..SolveOptions::default()
+        };
+        let results = solve(&root, &board, &opts);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].word, "qua");
+    }
+
+    #[test]
+    fn pruned_trie_solves_the_same() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "jin", "kop", "abfe", "ata", "plonk", "zzz", "quiz",
+        ] {
+            root.add_word(word);
+        }
+        for board in [
+            "abcdefghijklmnop",
+            "aataxxxxxxxxxxxx",
+            "plonkabfeatajinz",
+            "?ataq(u)izxxxxxxxxx",
+        ] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            let pruned = root.prune_to_board(&board);
+            assert!(pruned.node_count() < root.node_count());
+            let opts = SolveOptions::default();
+            assert_eq!(solve(&pruned, &board, &opts), solve(&root, &board, &opts));
+        }
+    }
+
+    #[test]
+    fn arena_solves_the_same() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "jin", "kop", "abfe", "ata", "plonk", "quiz", "zat",
+        ] {
+            root.add_word(word);
+        }
+        let arena = root.to_arena();
+        for board in [
+            "abcdefghijklmnop",
+            "aataxxxxxxxxxxxx",
+            "plonkabfeatajinz",
+            "?ataq(u)izxxxxxxxxx",
+        ] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            let opts = SolveOptions::default();
+            let results = solve(&root, &board, &opts);
+            assert!(!results.is_empty());
+            assert_eq!(solve(&arena, &board, &opts), results);
+            assert_eq!(
+                solve_iter(&arena, &board, &opts).collect::<Vec<_>>(),
+                search(&root, &board, &opts)
+            );
+        }
+    }
+
+    #[test]
+    fn report_counts_work() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "aba", "abe", "bab"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abababefabababef").unwrap();
+        let opts = SolveOptions {
+            sort: Sort::ByScore,
+            ..SolveOptions::default()
+        };
+
+        let report = solve_report(&root, &board, &opts);
+        assert_eq!(report.results, solve(&root, &board, &opts));
+        assert!(report.nodes_expanded >= report.results.len() as u64);
+        assert!(report.trie_descents >= report.nodes_expanded);
+        assert!(report.unique_words <= report.results.len());
+        assert_eq!(
+            report.unique_words,
+            solve_unique_words(&root, &board, &opts).len()
+        );
+        let best = solve_best_words(&root, &board, &opts);
+        assert_eq!(
+            report.total_score,
+            best.iter().map(|r| u64::from(r.score)).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn result_masks() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "kop", "plonk"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let results = solve(&root, &board, &SolveOptions::default());
+        for result in &results {
+            assert_eq!(result.mask().count_set() as usize, result.path.len());
+            assert!(result.path.iter().all(|&idx| result.mask().get(idx)));
+        }
+
+        let find = |word: &str| results.iter().find(|r| r.word == word).unwrap().mask();
+        assert_eq!(find("ab").intersection(find("kop")), BoardMask::default());
+        assert_ne!(find("ab").intersection(find("fab")), BoardMask::default());
+        assert!(find("ab").is_subset_of(find("fab")));
+    }
+
+    #[test]
+    fn allowed_tiles_only() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "abe", "jin", "be"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let corner = BoardMask::from_indices(
+            [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Index4x4::from_xy(x, y)),
+        );
+        let opts = SolveOptions {
+            allowed: corner,
+            ..SolveOptions::default()
+        };
+
+        let results = solve(&root, &board, &opts);
+        for result in &results {
+            assert!(result.mask().is_subset_of(corner));
+        }
+        let mut words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        words.sort();
+        assert_eq!(words, ["ab", "abe", "be", "fab"]);
+        assert!(
+            solve(&root, &board, &SolveOptions::default())
+                .iter()
+                .any(|r| r.word == "jin")
+        );
+
+        assert_eq!(
+            solve_iter(&root, &board, &opts).collect::<Vec<_>>(),
+            results
+        );
+        assert_eq!(solve_report(&root, &board, &opts).results, results);
+        let nothing = SolveOptions {
+            allowed: BoardMask::default(),
+            ..SolveOptions::default()
+        };
+        assert_eq!(solve(&root, &board, &nothing), []);
+        assert_eq!(solve_iter(&root, &board, &nothing).count(), 0);
+    }
+
+    #[test]
+    fn must_include_tile() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "abe", "jin", "be", "kop", "plonk", "glop",
+        ] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        for max_len in [None, Some(3)] {
+            let all = solve(
+                &root,
+                &board,
+                &SolveOptions {
+                    max_len,
+                    ..SolveOptions::default()
+                },
+            );
+            let mut union = HashSet::new();
+            for req in Index4x4::all_indices_within_bounds() {
+                let opts = SolveOptions {
+                    max_len,
+                    must_include: Some(req),
+                    ..SolveOptions::default()
+                };
+                let results = solve(&root, &board, &opts);
+                assert!(results.iter().all(|r| r.path.contains(&req)));
+                assert_eq!(
+                    solve_iter(&root, &board, &opts).collect::<Vec<_>>(),
+                    results
+                );
+                union.extend(results.into_iter().map(|r| (r.word, r.path)));
+            }
+            let all: HashSet<(String, Path)> = all.into_iter().map(|r| (r.word, r.path)).collect();
+            assert_eq!(union, all);
+        }
+
+        let corner = Index4x4::from_xy(0, 0);
+        let opts = SolveOptions {
+            must_include: Some(corner),
+            ..SolveOptions::default()
+        };
+        let mut words: Vec<String> = solve(&root, &board, &opts)
+            .into_iter()
+            .map(|r| r.word)
+            .collect();
+        words.sort();
+        assert_eq!(words, ["ab", "abe", "ba", "fab"]);
+    }
+
+    #[test]
+    fn limit_keeps_the_best() {
+        let mut root = TrieNode::new_root();
+        let mut rng = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..5000 {
+            let word: String = (0..2 + rng % 5)
+                .map(|_| {
+                    rng ^= rng << 13;
+                    rng ^= rng >> 7;
+                    rng ^= rng << 17;
+                    "aeinrstlodbm".as_bytes()[(rng % 12) as usize] as char
+                })
+                .collect();
+            root.add_word(&word);
+        }
+        // On a wildcard the DFS tries each letter before the next tile, out
+        // of Unsorted order.
+        for (board, sort) in ["sera tino lsed ramt", "se?a tino lsed r?mt"]
+            .into_iter()
+            .flat_map(|board| {
+                [
+                    Sort::ByScore,
+                    Sort::ByLength,
+                    Sort::Alphabetical,
+                    Sort::ByFrequency,
+                    Sort::ByPath,
+                ]
+                .map(|sort| (board, sort))
+            })
+        {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            let full = solve(
+                &root,
+                &board,
+                &SolveOptions {
+                    sort,
+                    ..SolveOptions::default()
+                },
+            );
+            assert!(full.len() > 100);
+            let opts = SolveOptions {
+                sort,
+                limit: Some(20),
+                ..SolveOptions::default()
+            };
+            assert_eq!(solve(&root, &board, &opts), full[..20]);
+            assert_eq!(solve_report(&root, &board, &opts).results, full[..20]);
+            let best = solve_best_words(&root, &board, &opts);
+            assert_eq!(best.len(), 20);
+            #[cfg(feature = "parallel")]
+            assert_eq!(solve_parallel(&root, &board, &opts), full[..20]);
+        }
+
+        let board = RuzzleBoard::try_from("sera tino lsed ramt").unwrap();
+        let opts = SolveOptions {
+            limit: Some(0),
+            sort: Sort::ByScore,
+            ..SolveOptions::default()
+        };
+        assert_eq!(solve(&root, &board, &opts), []);
+    }
+
+    #[test]
+    fn count_words_agrees_with_solve() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "abe", "jin", "be", "kop", "plonk", "ata", "tat", "quiz", "zata",
+        ] {
+            root.add_word(word);
+        }
+        let bonuses = BonusBoard::default()
+            .with_at(Bonus::TripleLetter, Index4x4::from_xy(0, 0))
+            .with_at(Bonus::DoubleWord, Index4x4::from_xy(1, 1));
+        for board in [
+            "abcdefghijklmnop",
+            "aataxxxxxxxxxxxx",
+            "?ataq(u)izxxxxxxxxx",
+            "ataq(u)iz?a?tabe?xs",
+        ] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            for opts in [
+                SolveOptions::default(),
+                SolveOptions {
+                    bonuses,
+                    min_len: 3,
+                    ..SolveOptions::default()
+                },
+                SolveOptions {
+                    max_len: Some(3),
+                    must_include: Some(Index4x4::from_xy(1, 0)),
+                    ..SolveOptions::default()
+                },
+                SolveOptions {
+                    bonuses,
+                    scoring: Scoring::Boggle,
+                    ..SolveOptions::default()
+                },
+            ] {
+                let all = solve(&root, &board, &opts);
+                let best = solve_best_words(&root, &board, &opts);
+                let summary = count_words(&root, &board, &opts);
+                assert_eq!(summary.total_paths, all.len());
+                assert_eq!(summary.unique_words, best.len());
+                assert_eq!(
+                    summary.total_score,
+                    best.iter().map(|r| r.score).sum::<u32>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scorers_rank_differently() {
+        let mut root = TrieNode::new_root();
+        root.add_word("fab");
+        root.add_word("aeino");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let bonuses = BonusBoard::default().with_at(Bonus::DoubleWord, Index4x4::from_xy(1, 1));
+        let ranking = |scorer: SharedScorer| {
+            let opts = SolveOptions {
+                bonuses,
+                sort: Sort::ByScore,
+                scorer: Some(scorer),
+                ..SolveOptions::default()
+            };
+            let results = solve_best_words(&root, &board, &opts);
+            let summary = count_words(&root, &board, &opts);
+            assert_eq!(
+                summary.total_score,
+                results.iter().map(|r| r.score).sum::<u32>()
+            );
+            results
+                .into_iter()
+                .map(|r| (r.word, r.score))
+                .collect::<Vec<_>>()
+        };
+        // fab's f is on the double word, which Boggle doesn't care about.
+        assert_eq!(
+            ranking(SharedScorer::new(RuzzleScorer::default())),
+            [("fab".to_string(), 18), ("aeino".to_string(), 10)]
+        );
+        assert_eq!(
+            ranking(SharedScorer::new(BoggleScorer::default())),
+            [("aeino".to_string(), 2), ("fab".to_string(), 1)]
+        );
+        let flat = BoggleScorer {
+            table: vec![0, 0, 7],
+        };
+        assert_eq!(
+            ranking(SharedScorer::new(flat)),
+            [("aeino".to_string(), 7), ("fab".to_string(), 7)]
+        );
+
+        // The same as the built in rules.
+        let opts = SolveOptions {
+            bonuses,
+            ..SolveOptions::default()
+        };
+        let boggle = SolveOptions {
+            scoring: Scoring::Boggle,
+            ..opts.clone()
+        };
+        for (builtin, scorer) in [
+            (&opts, SharedScorer::new(RuzzleScorer::default())),
+            (&boggle, SharedScorer::new(BoggleScorer::default())),
+        ] {
+            let custom = SolveOptions {
+                scorer: Some(scorer),
+                ..opts.clone()
+            };
+            assert_eq!(solve(&root, &board, builtin), solve(&root, &board, &custom));
+        }
+    }
+
+    #[test]
+    fn summarizes_results() {
+        let result = |word: &str, score| SolveResult::<4, 4> {
+            word: word.to_string(),
+            path: vec![],
+            score,
+            wildcard_bindings: vec![],
+            payload: None,
+        };
+        // "plonk" by two paths, the second scoring more.
+        let results = [
+            result("plonk", 14),
+            result("quiz", 23),
+            result("plonk", 16),
+            result("ab", 5),
+            result("glops", 10),
+        ];
+        let summary = SolveSummary::from_results(&results);
+        assert_eq!(
+            summary,
+            SolveSummary {
+                unique_words: 4,
+                total_paths: 5,
+                total_score: 16 + 23 + 5 + 10,
+                longest_word: Some("plonk".to_string()),
+                best_word: Some(("quiz".to_string(), 23)),
+            }
+        );
+        assert_eq!(RATINGS, [("good", 25), ("great", 50), ("excellent", 75)]);
+        // 54 points: 25% is 13.5, 50% 27 and 75% 40.5, rounded up.
+        assert_eq!(
+            summary.rating_scores(),
+            [("good", 14), ("great", 27), ("excellent", 41)]
+        );
+        let ratings: Vec<_> = [0, 13, 14, 26, 27, 41, 54]
+            .into_iter()
+            .map(|score| summary.rating(score))
+            .collect();
+        assert_eq!(
+            ratings,
+            [
+                None,
+                None,
+                Some("good"),
+                Some("good"),
+                Some("great"),
+                Some("excellent"),
+                Some("excellent")
+            ]
+        );
+        assert_eq!(
+            summary.to_string(),
+            "4 words for 54 points\n\
+             Longest word: plonk (5 letters)\n\
+             Best word: quiz (23 points)\n\
+             Ratings: good 14, great 27, excellent 41\n"
+        );
+        assert_eq!(
+            SolveSummary::from_results::<4, 4>(&[]).to_string(),
+            "0 words for 0 points\nRatings: good 0, great 0, excellent 0\n"
+        );
+    }
+
+    #[test]
+    fn order_independent_of_dictionary() {
+        let words = [
+            "ab", "ba", "fab", "abe", "be", "jin", "kop", "plonk", "glop", "ponk", "ea", "ae",
+            "bag", "fae",
+        ];
+        // The same words in three orders: as listed, reversed, and by their
+        // last letter.
+        let mut by_last = words;
+        by_last.sort_by_key(|word| word.chars().rev().collect::<String>());
+        let orders: Vec<Vec<&str>> = vec![
+            words.to_vec(),
+            words.iter().rev().copied().collect(),
+            by_last.to_vec(),
+        ];
+        let format = |results: &[SolveResult]| -> String {
+            results
+                .iter()
+                .map(|r| {
+                    let path: Vec<String> = r.path.iter().map(ToString::to_string).collect();
+                    format!("{} {} {}\n", r.score, r.word, path.join(" "))
+                })
+                .collect()
+        };
+        for board in ["abcdefghijklmnop", "a?cdefghijklmnop"] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            for opts in [
+                SolveOptions::default(),
+                SolveOptions {
+                    limit: Some(5),
+                    ..SolveOptions::default()
+                },
+                SolveOptions {
+                    path_policy: PathPolicy::BestScore,
+                    ..SolveOptions::default()
+                },
+                SolveOptions {
+                    sort: Sort::ByScore,
+                    ..SolveOptions::default()
+                },
+            ] {
+                let outputs: Vec<String> = orders
+                    .iter()
+                    .map(|order| {
+                        let mut root = TrieNode::new_root();
+                        for word in order {
+                            root.add_word(word);
+                        }
+                        format(&solve(&root, &board, &opts))
+                    })
+                    .collect();
+                assert!(!outputs[0].is_empty());
+                assert_eq!(outputs[0], outputs[1]);
+                assert_eq!(outputs[0], outputs[2]);
+            }
+
+            // By path, then word.
+            let mut root = TrieNode::new_root();
+            root.add_words(words);
+            let results = solve(&root, &board, &SolveOptions::default());
+            assert!(
+                results.windows(2).all(|pair| {
+                    (&pair[0].path, &pair[0].word) < (&pair[1].path, &pair[1].word)
+                })
+            );
+            let best = solve_best_words(&root, &board, &SolveOptions::default());
+            assert!(best.windows(2).all(|pair| pair[0].path < pair[1].path));
+        }
+    }
+
+    #[test]
+    fn iterative_matches_recursive() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "abe", "jin", "be", "kop", "plonk", "ata", "tat", "quiz", "zata",
+            "äta",
+        ] {
+            root.add_word(word);
+        }
+        let arena = root.to_arena();
+        for board in [
+            "abcdefghijklmnop",
+            "aataxxxxxxxxxxxx",
+            "?ataq(u)izxxxxxxxxx",
+            "ataq(u)iz?a?tabe?xs",
+            "????????????????",
+        ] {
+            let board = RuzzleBoard::try_from(board).unwrap();
+            for opts in [
+                SolveOptions::default(),
+                SolveOptions {
+                    max_len: Some(3),
+                    must_include: Some(Index4x4::from_xy(1, 0)),
+                    ..SolveOptions::default()
+                },
+            ] {
+                let recursive = solve_recursive(&root, &board, &opts);
+                assert_eq!(search(&root, &board, &opts), recursive);
+                assert_eq!(search(&arena, &board, &opts), recursive);
+                assert_eq!(
+                    solve_iter(&root, &board, &opts).collect::<Vec<_>>(),
+                    recursive
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn other_board_sizes() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "abc", "abdc", "cab", "bad", "dab", "abcde", "ya", "fly", "zebra",
+        ] {
+            root.add_word(word);
+        }
+
+        let board: Board<Tile, 2, 2> = "ab cd".parse().unwrap();
+        let opts = SolveOptions::default();
+        let mut words: Vec<String> = solve(&root, &board, &opts)
+            .into_iter()
+            .map(|r| r.word)
+            .collect();
+        words.sort();
+        assert_eq!(words, ["ab", "abc", "abdc", "bad", "cab", "dab"]);
+        assert_eq!(solve_iter(&root, &board, &opts).count(), words.len());
+
+        let board: Board<Tile, 5, 5> = "abcde fghij klmno pqrst uvwxy".parse().unwrap();
+        let opts = SolveOptions {
+            sort: Sort::Alphabetical,
+            ..SolveOptions::default()
+        };
+        let results = solve(&root, &board, &opts);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, ["ab", "abc", "abcde"]);
+        for result in &results {
+            assert!(board.is_valid_path(&result.path, &result.word));
+        }
+        assert_eq!(
+            results[1].path,
+            [(0, 0), (1, 0), (2, 0)].map(|(x, y)| BoardIndex::from_xy(x, y))
+        );
+        // a b c d e, plus the length bonus.
+        assert_eq!(results[2].score, 1 + 4 + 4 + 2 + 1 + 5);
+        assert_eq!(count_words(&root, &board, &opts).total_paths, 3);
+        assert_eq!(solve_recursive(&root, &board, &opts).len(), 3);
+
+        assert!(Board::<Tile, 5, 5>::try_from("abcd").is_err());
+    }
+
+    #[test]
+    fn incremental_matches_from_scratch() {
+        let mut root = TrieNode::new_root();
+        for word in [
+            "ab", "ba", "fab", "abe", "jin", "be", "kop", "plonk", "ata", "tat", "quiz", "zata",
+            "tab", "bat", "beat", "abate", "at", "ta",
+        ] {
+            root.add_word(word);
+        }
+        let tiles = [
+            Tile::from('a'),
+            Tile::from('b'),
+            Tile::from('e'),
+            Tile::from('t'),
+            Tile::from('z'),
+            Tile::Wildcard,
+            Tile::QU,
+        ];
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        for opts in [
+            SolveOptions::default(),
+            SolveOptions {
+                sort: Sort::ByScore,
+                limit: Some(5),
+                min_len: 3,
+                ..SolveOptions::default()
+            },
+        ] {
+            let mut solver = Solver::with_options(&root, board, opts.clone());
+            assert_eq!(solver.results(), solve(&root, &board, &opts));
+            // A fixed xorshift sequence of updates.
+            let mut state: u32 = 0x2545_f491;
+            for _ in 0..200 {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let idx = Index4x4::try_from_flat(state as usize % 16).unwrap();
+                let tile = tiles[(state >> 8) as usize % tiles.len()];
+                solver.update_tile(idx, tile);
+                assert_eq!(solver.board()[idx], tile);
+                assert_eq!(solver.results(), solve(&root, solver.board(), &opts));
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_searches_only_what_changed() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ab");
+        root.add_word("op");
+        let board = RuzzleBoard::try_from("abxxxxxxxxxxxxop").unwrap();
+        let mut solver = Solver::new(&root, board);
+        // Only the searches from the 'o' and from the corner itself read it.
+        assert_eq!(solver.update_tile(Index4x4::from_xy(3, 3), 'b'), 2);
+        assert_eq!(solver.update_tile(Index4x4::from_xy(3, 3), 'b'), 0);
+        let words: Vec<String> = solver.results().into_iter().map(|r| r.word).collect();
+        assert_eq!(words, ["ab"]);
+        // No word from the 'a' went through (0,1), but its search read it.
+        assert_eq!(solver.update_tile(Index4x4::from_xy(0, 1), 'b'), 2);
+        let words: Vec<String> = solver.results().into_iter().map(|r| r.word).collect();
+        assert_eq!(words, ["ab", "ab"]);
+    }
+
+    #[test]
+    fn groups_by_start() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "abe", "be", "bef", "jin", "kop", "plonk"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let opts = SolveOptions::default();
+        let groups = solve_grouped(&root, &board, &opts);
+
+        for (idx, group) in groups.enumerate_cells() {
+            assert!(group.iter().all(|result| result.path[0] == idx));
+            assert!(group.is_sorted_by(|a, b| a.score >= b.score));
+        }
+        let words: Vec<&str> = groups[Index4x4::from_xy(1, 0)]
+            .iter()
+            .map(|result| result.word.as_str())
+            .collect();
+        assert_eq!(words, ["bef", "ba", "be"]);
+
+        let mut grouped: Vec<SolveResult> = groups.into_iter().flatten().cloned().collect();
+        let mut flat = solve(&root, &board, &opts);
+        Sort::Alphabetical.sort(&mut grouped);
+        Sort::Alphabetical.sort(&mut flat);
+        assert_eq!(grouped, flat);
+    }
+
+    #[test]
+    fn stops_at_deadline() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "ba", "fab", "abe", "be", "jin", "kop", "plonk"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let report = solve_report(&root, &board, &SolveOptions::default());
+        assert!(report.completed);
+        assert!(!report.results.is_empty());
+
+        let passed = SolveOptions {
+            deadline: Some(Instant::now()),
+            ..SolveOptions::default()
+        };
+        let started = Instant::now();
+        let report = solve_report(&root, &board, &passed);
+        assert!(!report.completed);
+        assert!(report.results.is_empty());
+        assert!(solve(&root, &board, &passed).is_empty());
+        assert_eq!(solve_iter(&root, &board, &passed).count(), 0);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let future = SolveOptions {
+            deadline: Some(Instant::now() + Duration::from_secs(60)),
+            ..SolveOptions::default()
+        };
+        assert!(solve_report(&root, &board, &future).completed);
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        // Enough steps on a board of wildcards for several checks.
+        let mut root = TrieNode::new_root();
+        for word in ["abcde", "abcdf", "bcdef", "cdefg"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("????????????????").unwrap();
+        let cancel = CancelFlag::new();
+        let opts = SolveOptions {
+            cancel: Some(cancel.clone()),
+            ..SolveOptions::default()
+        };
+        let all = solve(&root, &board, &SolveOptions::default()).len();
+        let mut found = 0;
+        for (n, _) in solve_iter(&root, &board, &opts).enumerate() {
+            if n == 10 {
+                cancel.cancel();
+            }
+            found += 1;
+        }
+        assert!(found > 10 && found < all, "{} of {}", found, all);
+        assert!(!solve_report(&root, &board, &opts).completed);
+        assert_eq!(opts.clone(), opts);
+        assert_ne!(
+            opts,
+            SolveOptions {
+                cancel: Some(CancelFlag::new()),
+                ..SolveOptions::default()
+            }
+        );
+    }
+
+    #[test]
+    fn path_policies() {
+        let mut root = TrieNode::new_root();
+        root.add_word("aaa");
+        root.add_word("ax");
+        let board = RuzzleBoard::try_from("aaxx aaxx xxxx xxxx").unwrap();
+        let opts = |path_policy| SolveOptions {
+            path_policy,
+            bonuses: BonusBoard::default().with_at(Bonus::DoubleLetter, Index4x4::from_xy(1, 1)),
+            ..SolveOptions::default()
+        };
+        let path = |xys: &[(usize, usize)]| -> Path {
+            xys.iter().map(|&(x, y)| Index4x4::from_xy(x, y)).collect()
+        };
+
+        // Any three of the four tiles of the square, in any order.
+        let all = solve(&root, &board, &opts(PathPolicy::AllPaths));
+        let paths = group_paths(&all);
+        assert_eq!(paths["aaa"].len(), 4 * 3 * 2);
+        assert_eq!(paths["aaa"].iter().collect::<HashSet<_>>().len(), 24);
+        // (1,0) and (0,1) are next to two 'x's each, (1,1) to five.
+        assert_eq!(paths["ax"].len(), 9);
+        assert_eq!(all, solve(&root, &board, &opts(PathPolicy::AllPaths)));
+
+        let first = solve(&root, &board, &opts(PathPolicy::FirstOnly));
+        let words: Vec<&str> = first.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, ["aaa", "ax"]);
+        assert_eq!(first[0].path, path(&[(0, 0), (1, 0), (0, 1)]));
+        assert_eq!(first[0].score, 3);
+
+        // The first path through the double letter.
+        let best = solve(&root, &board, &opts(PathPolicy::BestScore));
+        assert_eq!(best[0].path, path(&[(0, 0), (1, 0), (1, 1)]));
+        assert_eq!(best[0].score, 4);
+        assert_eq!(best, solve(&root, &board, &opts(PathPolicy::BestScore)));
+
+        // Every way of solving keeps the same paths, limited or not.
+        for policy in [PathPolicy::FirstOnly, PathPolicy::BestScore] {
+            for limit in [None, Some(1)] {
+                let opts = SolveOptions {
+                    limit,
+                    ..opts(policy)
+                };
+                let expected = solve(&root, &board, &opts);
+                assert_eq!(expected.len(), limit.unwrap_or(2));
+                let solver = Solver::with_options(&root, board, opts.clone());
+                assert_eq!(solver.results(), expected);
+                assert_eq!(solve_report(&root, &board, &opts).results, expected);
+                #[cfg(feature = "parallel")]
+                assert_eq!(solve_parallel(&root, &board, &opts), expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn decomposed_words_match_composed_tiles() {
+        let mut root = TrieNode::new_root();
+        // "båt" and "Ö" + "l" as a letter and a combining mark each.
+        root.add_word("ba\u{30a}t");
+        root.add_word("O\u{308}l");
+        let board = RuzzleBoard::try_from("båtx ölxx xxxx xxxx").unwrap();
+        let results = solve_unique_words(&root, &board, &SolveOptions::default());
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, ["båt", "öl"]);
+        // b 4 + å 4 + t 1, ö 4 + l 1.
+        assert_eq!(results[0].score, 9);
+        assert_eq!(results[1].score, 5);
+        assert!(root.contains_word("BÅT") && root.contains_word("ba\u{30a}t"));
+    }
+
+    #[test]
+    fn results_carry_payloads() {
+        let mut root = TrieNode::new_root();
+        let frequency = |frequency| Payload {
+            frequency: Some(frequency),
+            definition: None,
+        };
+        root.add_word_with("ab", frequency(900));
+        root.add_word_with("fab", frequency(12));
+        root.add_word("abe");
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let opts = SolveOptions::default();
+        let payloads = |results: Vec<SolveResult>| -> Vec<(String, Option<u64>)> {
+            results
+                .into_iter()
+                .map(|r| (r.word, r.payload.and_then(|p| p.frequency)))
+                .collect()
+        };
+        let expected = [("ab", Some(900)), ("abe", None), ("fab", Some(12))]
+            .map(|(word, frequency)| (word.to_string(), frequency));
+        assert_eq!(payloads(solve(&root, &board, &opts)), expected);
+        assert_eq!(
+            payloads(solve_iter(&root, &board, &opts).collect()),
+            expected
+        );
+        assert_eq!(payloads(solve_recursive(&root, &board, &opts)), expected);
+        let mut solver = Solver::new(&root, board);
+        assert_eq!(payloads(solver.results()), expected);
+        solver.update_tile(Index4x4::from_xy(1, 1), 'x');
+        assert_eq!(payloads(solver.results()), expected[..2]);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::board::{Board, BoardIndex};
+use crate::solver::{SolveOptions, count_words};
+use crate::tile::Tile;
+use crate::trie::TrieNode;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Change<const W: usize, const H: usize> {
+    Replace {
+        at: BoardIndex<W, H>,
+        old: Tile,
+        new: Tile,
+    },
+    Swap {
+        at: (BoardIndex<W, H>, BoardIndex<W, H>),
+        tiles: (Tile, Tile), // As they were.
+    },
+}
+
+impl<const W: usize, const H: usize> Change<W, H> {
+    pub fn apply(&self, board: &Board<Tile, W, H>) -> Board<Tile, W, H> {
+        match *self {
+            Change::Replace { at, new, .. } => board.with_at(new, at),
+            Change::Swap { at: (a, b), tiles } => board.with_at(tiles.1, a).with_at(tiles.0, b),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Suggestion<const W: usize, const H: usize> {
+    pub change: Change<W, H>,
+    pub score: u32, // The total score of the changed board.
+    pub gain: i64,  // Over the total score of the board as it was.
+}
+
+// As "+12 a -> e at (1,2)", or "+5 a <-> e at (1,2) (3,0)" for a swap of
+// the 'a' at (1,2) with the 'e' at (3,0).
+impl<const W: usize, const H: usize> fmt::Display for Suggestion<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let xy = |idx: BoardIndex<W, H>| {
+            let (x, y) = idx.to_xy();
+            format!("({},{})", x, y)
+        };
+        write!(f, "{:+} ", self.gain)?;
+        match self.change {
+            Change::Replace { at, old, new } => write!(f, "{} -> {} at {}", old, new, xy(at)),
+            Change::Swap { at, tiles } => {
+                write!(
+                    f,
+                    "{} <-> {} at {} {}",
+                    tiles.0,
+                    tiles.1,
+                    xy(at.0),
+                    xy(at.1)
+                )
+            }
+        }
+    }
+}
+
+// Every change of one tile to another letter, and with swaps every swap of
+// two different tiles, scored with count_words() and best first. Changes
+// gaining the same come in board order, replacements before swaps.
+pub fn suggest_changes<const W: usize, const H: usize>(
+    root: &TrieNode,
+    board: &Board<Tile, W, H>,
+    opts: &SolveOptions<W, H>,
+    swaps: bool,
+) -> Vec<Suggestion<W, H>> {
+    let mut changes = vec![];
+    for (at, &old) in board.enumerate_cells() {
+        for ch in 'a'..='z' {
+            if old != ch {
+                let new = Tile::Letter(ch);
+                changes.push(Change::Replace { at, old, new });
+            }
+        }
+    }
+    if swaps {
+        for (a, &tile) in board.enumerate_cells() {
+            for (b, &other) in board.enumerate_cells().skip(a.flattened + 1) {
+                if tile != other {
+                    changes.push(Change::Swap {
+                        at: (a, b),
+                        tiles: (tile, other),
+                    });
+                }
+            }
+        }
+    }
+
+    let before = count_words(root, board, opts).total_score as i64;
+    let suggest = |change: &Change<W, H>| {
+        let score = count_words(root, &change.apply(board), opts).total_score;
+        Suggestion {
+            change: *change,
+            score,
+            gain: score as i64 - before,
+        }
+    };
+    #[cfg(feature = "parallel")]
+    let mut suggestions: Vec<Suggestion<W, H>> = {
+        use rayon::prelude::*;
+        changes.par_iter().map(suggest).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let mut suggestions: Vec<Suggestion<W, H>> = changes.iter().map(suggest).collect();
+    suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.gain));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Index4x4, RuzzleBoard};
+
+    fn idx(x: usize, y: usize) -> Index4x4 {
+        Index4x4::try_from_xy(x, y).unwrap()
+    }
+
+    #[test]
+    fn best_replacement() {
+        let mut root = TrieNode::new_root();
+        root.add_word("zax");
+        // Only "za" is missing its 'x', and (2,0) is next to the 'a'.
+        let board: RuzzleBoard = "zaqqqqqqqqqqqqqq".parse().unwrap();
+        let suggestions = suggest_changes(&root, &board, &SolveOptions::default(), false);
+        assert_eq!(suggestions.len(), 16 * 25);
+
+        let best = suggestions[0];
+        assert_eq!(
+            best.change,
+            Change::Replace {
+                at: idx(2, 0),
+                old: Tile::Letter('q'),
+                new: Tile::Letter('x'),
+            }
+        );
+        // z 10 + a 1 + x 8.
+        assert_eq!((best.score, best.gain), (19, 19));
+        assert_eq!(best.to_string(), "+19 q -> x at (2,0)");
+        // (0,1), (1,1) and (2,1) are next to the 'a' too.
+        assert!(suggestions[1..4].iter().all(|s| s.gain == 19));
+        assert!(suggestions[4..].iter().all(|s| s.gain == 0));
+    }
+
+    #[test]
+    fn best_swap() {
+        let mut root = TrieNode::new_root();
+        root.add_word("ab");
+        let board: RuzzleBoard = "aqqqqqqqqqqqqqqb".parse().unwrap();
+        let suggestions = suggest_changes(&root, &board, &SolveOptions::default(), true);
+        let swaps: Vec<_> = suggestions
+            .iter()
+            .filter(|s| matches!(s.change, Change::Swap { .. }))
+            .collect();
+        // Each of 'a' and 'b' with each of the 14 'q's, and with each other.
+        assert_eq!(swaps.len(), 2 * 14 + 1);
+
+        let best = swaps[0];
+        assert_eq!(best.change.apply(&board)[idx(2, 2)], 'a');
+        assert_eq!((best.score, best.gain), (5, 5));
+        assert_eq!(best.to_string(), "+5 a <-> q at (0,0) (2,2)");
+    }
+}
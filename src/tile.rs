@@ -0,0 +1,82 @@
+use std::fmt;
+
+// A blank tile, matching any letter.
+pub const WILDCARD: char = '?';
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Letter(char),
+    Digraph(char, char), // Two letters on one tile, like Boggle's "Qu".
+    Wildcard,
+}
+
+impl Tile {
+    pub const QU: Tile = Tile::Digraph('q', 'u');
+
+    // The letters traced when stepping onto the tile. A wildcard yields
+    // WILDCARD, since the letter it stands for depends on the word.
+    pub fn letters(self) -> impl Iterator<Item = char> {
+        let (first, second) = match self {
+            Tile::Letter(ch) => (ch, None),
+            Tile::Digraph(first, second) => (first, Some(second)),
+            Tile::Wildcard => (WILDCARD, None),
+        };
+        std::iter::once(first).chain(second)
+    }
+
+    pub fn letter_count(self) -> usize {
+        match self {
+            Tile::Digraph(..) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl From<char> for Tile {
+    fn from(ch: char) -> Self {
+        if ch == WILDCARD {
+            Tile::Wildcard
+        } else {
+            Tile::Letter(ch)
+        }
+    }
+}
+
+impl PartialEq<char> for Tile {
+    fn eq(&self, other: &char) -> bool {
+        *self == Tile::from(*other)
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.letters() {
+            write!(f, "{}", ch)?;
+        }
+        Ok(())
+    }
+}
+
+// As its letters, "qu" for Qu and "?" for a wildcard.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tile {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_letters() {
+        assert_eq!(Tile::from('a').letters().collect::<String>(), "a");
+        assert_eq!(Tile::QU.letters().collect::<String>(), "qu");
+        assert_eq!(Tile::from(WILDCARD), Tile::Wildcard);
+        assert_eq!(Tile::QU.letter_count(), 2);
+        assert_eq!(Tile::Wildcard.to_string(), "?");
+        assert_eq!(Tile::Letter('x'), 'x');
+        assert_ne!(Tile::QU, 'q');
+    }
+}
@@ -1,15 +1,389 @@
+use crate::board::Board;
+use crate::tile::Tile;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+const GZIP_MAGIC: &[u8; 2] = b"\x1f\x8b";
+
+// A word list for reading line by line. Gzipped lists, with a .gz extension
+// or the gzip magic bytes, are decompressed as they are read (with the
+// flate2 feature).
+pub fn open_word_file(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let mut file = BufReader::new(File::open(path)?);
+    let gzipped =
+        path.extension().is_some_and(|ext| ext == "gz") || file.fill_buf()?.starts_with(GZIP_MAGIC);
+    if !gzipped {
+        return Ok(Box::new(file));
+    }
+    #[cfg(feature = "flate2")]
+    return Ok(Box::new(BufReader::new(
+        flate2::bufread::MultiGzDecoder::new(file),
+    )));
+    #[cfg(not(feature = "flate2"))]
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "gzipped word lists need the flate2 feature",
+    ))
+}
+
+// Lines longer than this are skipped when reading word lists, as they're
+// unlikely to be words and would only make the trie deeper.
+pub const DEFAULT_MAX_WORD_LEN: usize = 64;
+
+// Nodes with at least this many children also index their a-z children by
+// letter, so find_in_children() is one array lookup instead of a binary
+// search. That's most of the nodes the solver visits near the root, at 104
+// bytes each, while the many sparse nodes deeper down pay 8 for the None.
+pub const DENSE_CHILDREN: usize = 8;
+
+// A few thousand common English words, for trying the solver out without
+// a word list of one's own.
+#[cfg(feature = "embedded-dict")]
+const COMMON_WORDS: &str = include_str!("../data/common_words.txt");
+
+// The most edits near_matches() allows. The search grows quickly with each.
+pub const MAX_EDITS: usize = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LineFormat {
+    #[default]
+    Plain, // The whole line, trimmed, is the word.
+    // Whitespace separated columns, e.g. "word<TAB>frequency": the first is
+    // the word and the second, if any, a count weighting it. Any more are
+    // ignored. Lines starting with '#' are comments.
+    Columns,
+}
+
+// What to do with words that have chars other than letters, e.g. "don't",
+// "e-mail" or "abc123", which no board can spell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum WordFilter {
+    #[default]
+    KeepAll,
+    SkipNonAlphabetic,
+    StripAndKeep, // Take the other chars out, and keep what's left if anything.
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub format: LineFormat,
+    pub max_len: usize, // Longer words, in chars, are skipped.
+    pub filter: WordFilter,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            format: LineFormat::Plain,
+            max_len: DEFAULT_MAX_WORD_LEN,
+            filter: WordFilter::KeepAll,
+        }
+    }
+}
+
+// What reading a word list found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LoadStats {
+    pub lines: usize,
+    pub words: usize,   // Lines handed on as words, repeats included.
+    pub skipped: usize, // Words of more than max_len chars. Blank lines and comments aren't counted.
+    // Lines whose second column isn't a count. Their words are still
+    // handed on, without a weight.
+    pub malformed: usize,
+    pub rejected: usize, // Words left out by the filter, or left with nothing by StripAndKeep.
+    pub stripped: usize, // Words StripAndKeep took chars out of and kept.
+}
+
+// How far reading a word list has got, so that a caller can show it while
+// a long one loads.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LoadProgress {
+    pub lines: usize,
+    pub words: usize, // As in LoadStats.
+}
+
+// How many lines apart progress is reported. It's reported once more when
+// the list is finished, with the totals.
+pub const PROGRESS_LINES: usize = 10_000;
+
+// An estimate of the heap a trie takes, from the sizes of its nodes and of
+// what they point to. Allocator overhead isn't counted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct MemoryReport {
+    pub nodes: usize,
+    pub used_bytes: usize, // As if every Vec and String were exactly as long as it is.
+    pub allocated_bytes: usize, // What their capacities take.
+}
+
+// Newline separated word list, with the weight of each word if it has
+// one. Read line by line, since dictionaries can be hundreds of thousands
+// of lines long.
+pub fn read_word_list(
+    reader: impl BufRead,
+    opts: &LoadOptions,
+    f: impl FnMut(&str, Option<u64>),
+) -> io::Result<LoadStats> {
+    read_word_list_with_progress(reader, opts, f, |_| {})
+}
+
+// Like read_word_list(), calling on_progress every PROGRESS_LINES lines
+// and at the end.
+pub fn read_word_list_with_progress(
+    reader: impl BufRead,
+    opts: &LoadOptions,
+    mut f: impl FnMut(&str, Option<u64>),
+    mut on_progress: impl FnMut(LoadProgress),
+) -> io::Result<LoadStats> {
+    let mut stats = LoadStats::default();
+    let progress = |stats: &LoadStats| LoadProgress {
+        lines: stats.lines,
+        words: stats.words,
+    };
+    for line in reader.lines() {
+        let line = line?;
+        if stats.lines > 0 && stats.lines % PROGRESS_LINES == 0 {
+            on_progress(progress(&stats));
+        }
+        stats.lines += 1;
+        let (word, weight) = match opts.format {
+            LineFormat::Plain => (line.trim(), None),
+            LineFormat::Columns => {
+                let mut columns = line.split_whitespace();
+                let word = columns.next().unwrap_or_default();
+                if word.starts_with('#') {
+                    continue;
+                }
+                let weight = columns.next().and_then(|weight| {
+                    let weight = weight.parse().ok();
+                    stats.malformed += usize::from(weight.is_none());
+                    weight
+                });
+                (word, weight)
+            }
+        };
+        let Some(word) = filter_word(word, opts.filter, &mut stats) else {
+            continue;
+        };
+        if word.chars().count() > opts.max_len {
+            stats.skipped += 1;
+        } else if !word.is_empty() {
+            stats.words += 1;
+            f(&word, weight);
+        }
+    }
+    on_progress(progress(&stats));
+    Ok(stats)
+}
+
+// The word as filter has it, or None if it's left out. Letters are what
+// char::is_alphabetic() says, so 'å' and 'é' are as good as 'a'.
+fn filter_word<'a>(
+    word: &'a str,
+    filter: WordFilter,
+    stats: &mut LoadStats,
+) -> Option<Cow<'a, str>> {
+    if filter == WordFilter::KeepAll || word.chars().all(char::is_alphabetic) {
+        return Some(Cow::Borrowed(word));
+    }
+    let stripped: String = word.chars().filter(|ch| ch.is_alphabetic()).collect();
+    if filter == WordFilter::SkipNonAlphabetic || stripped.is_empty() {
+        stats.rejected += 1;
+        return None;
+    }
+    stats.stripped += 1;
+    Some(Cow::Owned(stripped))
+}
+
+// Words are stored lowercase, so that dictionaries and boards of mixed case
+// match. Some uppercase chars lowercase to several chars, e.g. 'İ' => "i̇".
+// They're also composed, see compose().
+pub fn normalize(word: &str) -> Cow<'_, str> {
+    if word.chars().any(char::is_uppercase) {
+        let lower: String = word.chars().flat_map(char::to_lowercase).collect();
+        Cow::Owned(compose(&lower).into_owned())
+    } else {
+        compose(word)
+    }
+}
+
+// With the unicode feature, letters written as a base letter and combining
+// marks, e.g. 'a' and a combining ring, are composed into one char where
+// there is one, here 'å' (Unicode NFC). Otherwise the two forms would be
+// different words, and a decomposed letter couldn't be a tile at all.
+// Without the feature, text is left as it is.
+pub fn compose(s: &str) -> Cow<'_, str> {
+    #[cfg(feature = "unicode")]
+    if !unicode_normalization::is_nfc(s) {
+        use unicode_normalization::UnicodeNormalization;
+        return Cow::Owned(s.nfc().collect());
+    }
+    Cow::Borrowed(s)
+}
+
+// Data kept with a word, e.g. from the columns of a word list.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Payload {
+    // How common the word is, e.g. a count in some corpus. Higher is more common.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub frequency: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub definition: Option<String>,
+}
+
 pub struct TrieNode {
-    ch: Option<char>, // Some(c) for nodes, None for root.
-    children: Vec<TrieNode>,
+    pub(crate) ch: Option<char>,        // Some(c) for nodes, None for root.
+    pub(crate) children: Vec<TrieNode>, // Sorted by char, for binary search.
+    // For dense nodes, 1 + the position in children of each of 'a' to 'z',
+    // or 0 if there's no such child. Call index_children() after changing
+    // children.
+    letter_index: Option<Box<[u32; 26]>>,
     pub is_terminal: bool,
+    pub(crate) payload: Option<Box<Payload>>, // Only ever on terminal nodes. Boxed, as most have none.
+    // Whether any node below this one is terminal, so that dead subtrees
+    // can be skipped without walking them. Kept up to date by every method
+    // that changes the trie; set is_terminal by hand and it's up to you.
+    has_terminal_descendant: bool,
+}
+
+// Copies from a stack of the nodes on the way down, each with the copies
+// of its children so far, rather than by recursion, which a deep enough
+// trie would overflow.
+impl Clone for TrieNode {
+    fn clone(&self) -> Self {
+        let mut stack: Vec<(&TrieNode, Vec<TrieNode>)> =
+            vec![(self, Vec::with_capacity(self.children.len()))];
+        loop {
+            let (node, copied) = stack.last().expect("the root is popped last");
+            if let Some(child) = node.children.get(copied.len()) {
+                stack.push((child, Vec::with_capacity(child.children.len())));
+                continue;
+            }
+            let (node, children) = stack.pop().expect("the root is popped last");
+            let copy = TrieNode {
+                ch: node.ch,
+                children,
+                letter_index: node.letter_index.clone(),
+                is_terminal: node.is_terminal,
+                payload: node.payload.clone(),
+                has_terminal_descendant: node.has_terminal_descendant,
+            };
+            match stack.last_mut() {
+                Some((_, siblings)) => siblings.push(copy),
+                None => return copy,
+            }
+        }
+    }
+}
+
+// Equal if they hold the same words with the same payloads, i.e. have the
+// same shape: children are always sorted, so they can be compared in order.
+// Compared pair by pair from a stack, as in clone().
+impl PartialEq for TrieNode {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((a, b)) = stack.pop() {
+            if a.ch != b.ch
+                || a.is_terminal != b.is_terminal
+                || a.payload != b.payload
+                || a.children.len() != b.children.len()
+            {
+                return false;
+            }
+            stack.extend(a.children.iter().zip(&b.children));
+        }
+        true
+    }
+}
+
+impl Eq for TrieNode {}
+
+// Dropping the children one by one from a stack of them, rather than by
+// the recursion of the default drop, which a deep enough trie would
+// overflow.
+impl Drop for TrieNode {
+    fn drop(&mut self) {
+        let mut stack = std::mem::take(&mut self.children);
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+// Both by way of add_words(), so sorted words are added the quicker way.
+impl<S: AsRef<str>> Extend<S> for TrieNode {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        self.add_words(words);
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for TrieNode {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> Self {
+        let mut root = TrieNode::new_root();
+        root.add_words(words);
+        root
+    }
+}
+
+// How deep Debug shows a trie. Below that, children are shown as "…".
+const DEBUG_DEPTH: usize = 4;
+
+// Nested maps of each letter to its children, with a '*' after the letter
+// of each word's end, as {a*: {t*}, b: {e*: {…}}}. The root's own '*' is
+// shown before its children.
+impl fmt::Debug for TrieNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ch.is_none() && self.is_terminal {
+            f.write_str("*")?;
+        }
+        self.fmt_children(f, DEBUG_DEPTH)
+    }
 }
 
 impl TrieNode {
-    fn from_char(character: char) -> TrieNode {
+    fn fmt_children(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        if depth == 0 {
+            return f.write_str("{…}");
+        }
+        f.write_str("{")?;
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if let Some(ch) = child.ch {
+                write!(f, "{}", ch)?;
+            }
+            if child.is_terminal {
+                f.write_str("*")?;
+            }
+            if !child.children.is_empty() {
+                f.write_str(": ")?;
+                child.fmt_children(f, depth - 1)?;
+            }
+        }
+        f.write_str("}")
+    }
+}
+
+// The parallel solver shares one trie between threads.
+const _: () = {
+    const fn assert_sync<T: Sync>() {}
+    assert_sync::<TrieNode>();
+};
+
+impl TrieNode {
+    pub(crate) fn from_char(character: char) -> TrieNode {
         TrieNode {
             ch: Some(character),
             children: Vec::new(),
+            letter_index: None,
             is_terminal: false,
+            payload: None,
+            has_terminal_descendant: false,
         }
     }
 
@@ -17,59 +391,682 @@ impl TrieNode {
         TrieNode {
             ch: None,
             children: Vec::new(),
+            letter_index: None,
             is_terminal: false,
+            payload: None,
+            has_terminal_descendant: false,
         }
     }
 
+    // The trie of COMMON_WORDS.
+    #[cfg(feature = "embedded-dict")]
+    pub fn default_dictionary() -> TrieNode {
+        COMMON_WORDS.lines().collect()
+    }
+
+    pub fn from_word_file(path: &Path) -> io::Result<TrieNode> {
+        TrieNode::from_word_file_with_progress(path, |_| {})
+    }
+
+    pub fn from_word_file_with_progress(
+        path: &Path,
+        on_progress: impl FnMut(LoadProgress),
+    ) -> io::Result<TrieNode> {
+        TrieNode::from_reader_with_progress(open_word_file(path)?, on_progress)
+    }
+
+    // A word list from anywhere, e.g. stdin or bytes in memory. Lines are
+    // trimmed, and blank lines and lines of more than DEFAULT_MAX_WORD_LEN
+    // chars skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<TrieNode> {
+        TrieNode::from_reader_with_progress(reader, |_| {})
+    }
+
+    // Like from_reader(), telling on_progress how far it's got as in
+    // read_word_list_with_progress().
+    pub fn from_reader_with_progress<R: BufRead>(
+        reader: R,
+        on_progress: impl FnMut(LoadProgress),
+    ) -> io::Result<TrieNode> {
+        let mut root = TrieNode::new_root();
+        root.add_reader_with_progress(reader, &LoadOptions::default(), on_progress)?;
+        Ok(root)
+    }
+
+    pub fn add_word_file(&mut self, path: &Path) -> io::Result<()> {
+        self.add_reader(open_word_file(path)?, &LoadOptions::default())?;
+        Ok(())
+    }
+
+    // Like from_reader(), adding to this trie, in the format and up to the
+    // length of opts. Weights are kept as the words' frequencies.
+    pub fn add_reader<R: BufRead>(
+        &mut self,
+        reader: R,
+        opts: &LoadOptions,
+    ) -> io::Result<LoadStats> {
+        self.add_reader_with_progress(reader, opts, |_| {})
+    }
+
+    pub fn add_reader_with_progress<R: BufRead>(
+        &mut self,
+        reader: R,
+        opts: &LoadOptions,
+        on_progress: impl FnMut(LoadProgress),
+    ) -> io::Result<LoadStats> {
+        let mut inserter = Inserter::default();
+        let add = |word: &str, weight| {
+            let node = inserter.insert(self, &normalize(word));
+            if let Some(weight) = weight {
+                node.payload.get_or_insert_default().frequency = Some(weight);
+            }
+        };
+        read_word_list_with_progress(reader, opts, add, on_progress)
+    }
+
+    // Removes every word in the file, e.g. a list of words the game
+    // doesn't accept. Words that weren't there are skipped.
+    pub fn remove_word_file(&mut self, path: &Path) -> io::Result<()> {
+        read_word_list(open_word_file(path)?, &LoadOptions::default(), |word, _| {
+            self.remove_word(word);
+        })?;
+        Ok(())
+    }
+
+    pub fn ch(&self) -> Option<char> {
+        self.ch
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = &TrieNode> {
+        self.children.iter()
+    }
+
+    fn child_position(&self, key: char) -> Result<usize, usize> {
+        self.children.binary_search_by_key(&Some(key), |c| c.ch)
+    }
+
     pub fn find_in_children(&self, key: char) -> Option<&TrieNode> {
-        self.children.iter().find(|c| c.ch == Some(key))
+        if let Some(index) = &self.letter_index
+            && key.is_ascii_lowercase()
+        {
+            return match index[(key as u8 - b'a') as usize] {
+                0 => None,
+                pos => Some(&self.children[pos as usize - 1]),
+            };
+        }
+        self.child_position(key).ok().map(|pos| &self.children[pos])
+    }
+
+    // Rebuilds letter_index for the children as they are now, dropping it if
+    // there are fewer than DENSE_CHILDREN.
+    pub(crate) fn index_children(&mut self) {
+        if self.children.len() < DENSE_CHILDREN {
+            self.letter_index = None;
+            return;
+        }
+        let index = self.letter_index.get_or_insert_default();
+        **index = [0; 26];
+        for (pos, child) in self.children.iter().enumerate() {
+            if let Some(ch) = child.ch.filter(char::is_ascii_lowercase) {
+                index[(ch as u8 - b'a') as usize] = pos as u32 + 1;
+            }
+        }
+    }
+
+    pub fn has_terminal_descendant(&self) -> bool {
+        self.has_terminal_descendant
+    }
+
+    // Whether this node or one below it is terminal, i.e. whether any word
+    // goes through it.
+    fn leads_to_word(&self) -> bool {
+        self.is_terminal || self.has_terminal_descendant
+    }
+
+    // Recomputes has_terminal_descendant from the children, which must be
+    // up to date themselves.
+    pub(crate) fn update_terminal_descendant(&mut self) {
+        self.has_terminal_descendant = self.children.iter().any(TrieNode::leads_to_word);
+    }
+
+    // The root's own size included, as if it were boxed.
+    pub fn memory_usage(&self) -> MemoryReport {
+        let mut report = MemoryReport {
+            nodes: 0,
+            used_bytes: size_of::<TrieNode>(),
+            allocated_bytes: size_of::<TrieNode>(),
+        };
+        for (node, _) in self.walk() {
+            report.nodes += 1;
+            report.used_bytes += node.children.len() * size_of::<TrieNode>();
+            report.allocated_bytes += node.children.capacity() * size_of::<TrieNode>();
+            if node.letter_index.is_some() {
+                report.used_bytes += size_of::<[u32; 26]>();
+                report.allocated_bytes += size_of::<[u32; 26]>();
+            }
+            if let Some(payload) = &node.payload {
+                let definition = payload.definition.as_ref();
+                report.used_bytes += size_of::<Payload>() + definition.map_or(0, String::len);
+                report.allocated_bytes +=
+                    size_of::<Payload>() + definition.map_or(0, String::capacity);
+            }
+        }
+        report
+    }
+
+    // Gives back the spare capacity of every node's children, which insertion
+    // leaves up to half of. Best done once loading is finished.
+    pub fn shrink_to_fit(&mut self) {
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            node.children.shrink_to_fit();
+            if let Some(definition) = node.payload.as_mut().and_then(|p| p.definition.as_mut()) {
+                definition.shrink_to_fit();
+            }
+            stack.extend(node.children.iter_mut());
+        }
     }
 
-    fn mut_find_in_children(&mut self, key: char) -> Option<&mut TrieNode> {
-        self.children.iter_mut().find(|c| c.ch == Some(key))
+    // This node and every one below it, depth first, each with its depth
+    // below this one. The trie statistics walk with this rather than by
+    // recursion, so that however deep a trie is they can't overflow the stack.
+    fn walk(&self) -> impl Iterator<Item = (&TrieNode, usize)> {
+        let mut stack = vec![(self, 0)];
+        std::iter::from_fn(move || {
+            let (node, depth) = stack.pop()?;
+            stack.extend(node.children.iter().rev().map(|child| (child, depth + 1)));
+            Some((node, depth))
+        })
     }
 
     pub fn node_count(&self) -> usize {
-        1 + self.children.iter().map(|c| c.node_count()).sum::<usize>()
+        self.walk().count()
     }
     pub fn leaf_count(&self) -> usize {
-        if self.children.is_empty() {
-            1
-        } else {
-            self.children.iter().map(|c| c.leaf_count()).sum::<usize>()
-        }
+        self.walk()
+            .filter(|(node, _)| node.children.is_empty())
+            .count()
     }
 
     pub fn max_depth(&self) -> usize {
-        let child_depth = self.children.iter().map(|c| c.max_depth()).max();
-        1 + child_depth.unwrap_or(0)
+        1 + self.walk().map(|(_, depth)| depth).max().unwrap_or(0)
+    }
+
+    // The number of words stored, "" included if it's one.
+    pub fn word_count(&self) -> usize {
+        self.walk().filter(|(node, _)| node.is_terminal).count()
+    }
+
+    // How many words there are of each length in chars: index 0 counts the
+    // empty word, index 1 words of one letter, and so on up to the longest.
+    // Lengths count from this node, whose own char isn't part of them, so
+    // for the root they're the lengths of the words. Empty if there are none.
+    pub fn word_length_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![];
+        for (_, len) in self.walk().filter(|(node, _)| node.is_terminal) {
+            if histogram.len() <= len {
+                histogram.resize(len + 1, 0);
+            }
+            histogram[len] += 1;
+        }
+        histogram
+    }
+
+    // One of the longest words, the first alphabetically of those, or None
+    // if there are no words.
+    pub fn longest_word(&self) -> Option<String> {
+        let mut longest: Option<(usize, String)> = None;
+        self.visit_words(&mut String::new(), 0, &mut |word, len| {
+            if longest.as_ref().is_none_or(|&(longest, _)| len > longest) {
+                longest = Some((len, word.to_string()));
+            }
+        });
+        longest.map(|(_, word)| word)
+    }
+
+    // The words of more than len chars, in alphabetical order.
+    pub fn words_longer_than(&self, len: usize) -> Vec<String> {
+        let mut words = vec![];
+        self.visit_words(&mut String::new(), 0, &mut |word, word_len| {
+            if word_len > len {
+                words.push(word.to_string());
+            }
+        });
+        words
+    }
+
+    // The words of this trie which other doesn't have, in alphabetical
+    // order. Both tries are walked together, so a subtree other has no
+    // part of is listed without looking anything up.
+    pub fn difference(&self, other: &TrieNode) -> impl Iterator<Item = String> {
+        let mut words = vec![];
+        self.difference_into(Some(other), &mut String::new(), &mut words);
+        words.into_iter()
+    }
+
+    fn difference_into(&self, other: Option<&TrieNode>, word: &mut String, out: &mut Vec<String>) {
+        let Some(other) = other else {
+            self.visit_words(word, 0, &mut |word, _| out.push(word.to_string()));
+            return;
+        };
+        if self.is_terminal && !other.is_terminal {
+            out.push(word.clone());
+        }
+        for child in &self.children {
+            let Some(ch) = child.ch else { continue };
+            word.push(ch);
+            child.difference_into(other.find_in_children(ch), word, out);
+            word.pop();
+        }
+    }
+
+    // Calls f with each word below this node and its length in chars, in
+    // alphabetical order. word is a buffer shared by the whole walk, so
+    // only the words f keeps are copied.
+    fn visit_words(&self, word: &mut String, len: usize, f: &mut impl FnMut(&str, usize)) {
+        if self.is_terminal {
+            f(word, len);
+        }
+        // The children left to visit of each node down to the current one.
+        let mut stack = vec![self.children.iter()];
+        while let Some(children) = stack.last_mut() {
+            match children.next() {
+                Some(child) => {
+                    word.extend(child.ch);
+                    if child.is_terminal {
+                        f(word, len + stack.len());
+                    }
+                    stack.push(child.children.iter());
+                }
+                None => {
+                    stack.pop();
+                    // This node's char; the bottom one's isn't in word.
+                    if !stack.is_empty() {
+                        word.pop();
+                    }
+                }
+            }
+        }
     }
 
     pub fn add_word(&mut self, word: &str) {
-        let mut chars: std::str::Chars = word.chars();
-        if let Some(head) = chars.next() {
-            let tail: &str = chars.as_str();
-            if let Some(child) = self.mut_find_in_children(head) {
-                child.add_word(tail);
-            } else {
-                let mut next_node = TrieNode::from_char(head);
-                next_node.add_word(tail);
-                self.children.push(next_node);
+        self.insert(&normalize(word));
+    }
+
+    // Adds word with payload, replacing any payload it had.
+    pub fn add_word_with(&mut self, word: &str, payload: Payload) {
+        self.insert(&normalize(word)).payload = Some(Box::new(payload));
+    }
+
+    // The payload of word, if it's a word and has one.
+    pub fn get(&self, word: &str) -> Option<&Payload> {
+        self.find_prefix(&normalize(word))
+            .filter(|node| node.is_terminal)
+            .and_then(|node| node.payload.as_deref())
+    }
+
+    // Like add_word() for each word, but quicker for sorted words: the
+    // nodes of the prefix a word shares with the one before it are found
+    // again by position rather than searched for. Words in any other order
+    // are added just the same, only without the speedup.
+    pub fn add_words<I>(&mut self, words: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut inserter = Inserter::default();
+        for word in words {
+            inserter.insert(self, &normalize(word.as_ref()));
+        }
+    }
+
+    // Returns the word's node.
+    fn insert(&mut self, word: &str) -> &mut TrieNode {
+        let mut node = self;
+        for ch in word.chars() {
+            let pos = match node.child_position(ch) {
+                Ok(pos) => pos,
+                Err(pos) => {
+                    node.children.insert(pos, TrieNode::from_char(ch));
+                    node.index_children();
+                    pos
+                }
+            };
+            node.has_terminal_descendant = true;
+            node = &mut node.children[pos];
+        }
+        node.is_terminal = true;
+        node
+    }
+
+    // Returns whether the word was present. Nodes left leading to no
+    // words are removed, back up towards the root.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        self.remove(&normalize(word))
+    }
+
+    fn remove(&mut self, word: &str) -> bool {
+        // First, without changing anything, the nodes down the word and the
+        // position of each in its parent's children.
+        let mut nodes: Vec<&TrieNode> = vec![self];
+        let mut positions = vec![];
+        for ch in word.chars() {
+            let node = nodes[nodes.len() - 1];
+            let Ok(pos) = node.child_position(ch) else {
+                return false;
+            };
+            nodes.push(&node.children[pos]);
+            positions.push(pos);
+        }
+        let end = nodes.len() - 1;
+        if !nodes[end].is_terminal {
+            return false;
+        }
+        // Then from the word's end up, which of them go, being left with
+        // no word and no children, and has_terminal_descendant for the rest.
+        let mut goes = vec![false; nodes.len()];
+        let mut descendant = vec![nodes[end].has_terminal_descendant; nodes.len()];
+        goes[end] = end > 0 && nodes[end].children.is_empty();
+        for depth in (0..end).rev() {
+            let node = nodes[depth];
+            let child = depth + 1;
+            let child_leads =
+                !goes[child] && ((child < end && nodes[child].is_terminal) || descendant[child]);
+            let others_lead = (node.children.iter().enumerate())
+                .any(|(pos, other)| pos != positions[depth] && other.leads_to_word());
+            descendant[depth] = child_leads || others_lead;
+            goes[depth] = depth > 0 && !node.is_terminal && node.children.len() == 1 && goes[child];
+        }
+        // Last, down again to make the changes.
+        let mut node = self;
+        for depth in 0..end {
+            node.has_terminal_descendant = descendant[depth];
+            if goes[depth + 1] {
+                node.children.remove(positions[depth]);
+                node.index_children();
+                return true;
+            }
+            node = &mut node.children[positions[depth]];
+        }
+        node.is_terminal = false;
+        node.payload = None;
+        true
+    }
+
+    // A copy holding only the words whose letters are all available on the
+    // board, counting repeats. Adjacency is not considered, so solving
+    // with the pruned trie gives the same results as with the original.
+    pub fn prune_to_board<const W: usize, const H: usize>(
+        &self,
+        board: &Board<Tile, W, H>,
+    ) -> TrieNode {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut wildcards = 0;
+        for &tile in board {
+            match tile {
+                Tile::Wildcard => wildcards += 1,
+                tile => tile
+                    .letters()
+                    .for_each(|ch| *counts.entry(ch).or_default() += 1),
+            }
+        }
+        let mut root = TrieNode::new_root();
+        root.is_terminal = self.is_terminal;
+        root.payload = self.payload.clone();
+        self.prune_children(&mut root, &mut counts, wildcards);
+        root.update_terminal_descendant();
+        root
+    }
+
+    fn prune_children(
+        &self,
+        into: &mut TrieNode,
+        counts: &mut HashMap<char, usize>,
+        wildcards: usize,
+    ) {
+        for child in &self.children {
+            let Some(ch) = child.ch else { continue };
+            // Spending a real letter is never worse than spending a wildcard.
+            let (from_counts, wildcards) = match counts.get(&ch) {
+                Some(&n) if n > 0 => (true, wildcards),
+                _ if wildcards > 0 => (false, wildcards - 1),
+                _ => continue,
+            };
+            if from_counts {
+                *counts.get_mut(&ch).unwrap() -= 1;
+            }
+            let mut kept = TrieNode::from_char(ch);
+            kept.is_terminal = child.is_terminal;
+            kept.payload = child.payload.clone();
+            child.prune_children(&mut kept, counts, wildcards);
+            kept.update_terminal_descendant();
+            if from_counts {
+                *counts.get_mut(&ch).unwrap() += 1;
+            }
+            if kept.is_terminal || !kept.children.is_empty() {
+                into.children.push(kept);
+            }
+        }
+        into.index_children();
+    }
+
+    // Every stored word, depth first. Since children are kept sorted,
+    // this is alphabetical order (by code point).
+    pub fn words(&self) -> Words<'_> {
+        Words::new(self, String::new())
+    }
+
+    // The stored words starting with prefix, including prefix itself.
+    pub fn words_with_prefix(&self, prefix: &str) -> Words<'_> {
+        let prefix = normalize(prefix);
+        match self.find_prefix(&prefix) {
+            Some(node) => Words::new(node, prefix.into_owned()),
+            None => Words::empty(),
+        }
+    }
+
+    // Up to limit of the words starting with prefix, including prefix itself,
+    // shortest first and alphabetical among the same length. Breadth first
+    // from the prefix, so that only as much of the subtree is visited as
+    // the shortest limit words need.
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = normalize(prefix);
+        let mut out = vec![];
+        let Some(node) = self.find_prefix(&prefix) else {
+            return out;
+        };
+        let mut queue = VecDeque::from([(node, prefix.into_owned())]);
+        while out.len() < limit
+            && let Some((node, word)) = queue.pop_front()
+        {
+            for child in &node.children {
+                let mut longer = word.clone();
+                longer.extend(child.ch);
+                queue.push_back((child, longer));
+            }
+            if node.is_terminal {
+                out.push(word);
+            }
+        }
+        out
+    }
+
+    // Whether any stored word starts with prefix, prefix itself included.
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.find_prefix(&normalize(prefix))
+            .is_some_and(TrieNode::leads_to_word)
+    }
+
+    // The length in chars of the longest prefix of word that has_prefix(),
+    // i.e. how far the trie follows it. 0 if no word starts with its first
+    // letter (or the trie is empty).
+    pub fn longest_prefix_of(&self, word: &str) -> usize {
+        let mut node = self;
+        let mut len = 0;
+        for ch in normalize(word).chars() {
+            match node.find_in_children(ch) {
+                Some(child) if child.leads_to_word() => node = child,
+                _ => break,
             }
-        } else {
-            // None case. i.e. Case for "".
-            self.is_terminal = true;
+            len += 1;
         }
+        len
+    }
+
+    // The stored words at most max_edits (capped at MAX_EDITS) letter
+    // substitutions, insertions and deletions away from word, in alphabetical
+    // order. Each node extends its parent's row of edit distances to the
+    // prefixes of word, and the walk stops below a row that's all too far.
+    pub fn near_matches(&self, word: &str, max_edits: usize) -> Vec<String> {
+        let max_edits = max_edits.min(MAX_EDITS);
+        let target: Vec<char> = normalize(word).chars().collect();
+        let row: Vec<usize> = (0..=target.len()).collect();
+        let mut out = vec![];
+        if self.is_terminal && target.len() <= max_edits {
+            out.push(String::new());
+        }
+        for child in &self.children {
+            child.near_matches_into(&target, &row, max_edits, &mut String::new(), &mut out);
+        }
+        out
+    }
+
+    fn near_matches_into(
+        &self,
+        target: &[char],
+        parent_row: &[usize],
+        max_edits: usize,
+        word: &mut String,
+        out: &mut Vec<String>,
+    ) {
+        let Some(ch) = self.ch else { return };
+        let mut row = vec![parent_row[0] + 1];
+        for (i, &target_ch) in target.iter().enumerate() {
+            let substitute = parent_row[i] + usize::from(target_ch != ch);
+            row.push(substitute.min(parent_row[i + 1] + 1).min(row[i] + 1));
+        }
+        word.push(ch);
+        if self.is_terminal && row[target.len()] <= max_edits {
+            out.push(word.clone());
+        }
+        if row.iter().any(|&edits| edits <= max_edits) {
+            for child in &self.children {
+                child.near_matches_into(target, &row, max_edits, word, out);
+            }
+        }
+        word.pop();
+    }
+
+    fn find_prefix(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut node = self;
+        for ch in prefix.chars() {
+            node = node.find_in_children(ch)?;
+        }
+        Some(node)
     }
 
     pub fn contains_word(&self, word: &str) -> bool {
-        let mut chars = word.chars();
-        match chars.next() {
-            Some(head) => match self.find_in_children(head) {
-                Some(child) => child.contains_word(chars.as_str()),
-                None => false,
-            },
-            None => self.is_terminal,
+        self.find_prefix(&normalize(word))
+            .is_some_and(|node| node.is_terminal)
+    }
+}
+
+// Inserts words one after the other, remembering where the last one went.
+// A word can only add children at or below the point where it leaves the
+// one before, so the positions of the nodes above stay valid.
+#[derive(Default)]
+struct Inserter {
+    last: String,
+    path: Vec<usize>, // The child position of each char of last, from the root.
+}
+
+impl Inserter {
+    // Returns the word's node.
+    fn insert<'t>(&mut self, root: &'t mut TrieNode, word: &str) -> &'t mut TrieNode {
+        let shared = self
+            .last
+            .chars()
+            .zip(word.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.path.truncate(shared);
+        let mut node = root;
+        for &pos in &self.path {
+            node.has_terminal_descendant = true;
+            node = &mut node.children[pos];
+        }
+        for ch in word.chars().skip(shared) {
+            node.has_terminal_descendant = true;
+            let pos = match node.child_position(ch) {
+                Ok(pos) => pos,
+                Err(pos) => {
+                    node.children.insert(pos, TrieNode::from_char(ch));
+                    node.index_children();
+                    pos
+                }
+            };
+            self.path.push(pos);
+            node = &mut node.children[pos];
+        }
+        node.is_terminal = true;
+        self.last.clear();
+        self.last.push_str(word);
+        node
+    }
+}
+
+// Lazy depth first enumeration of the words below a node.
+pub struct Words<'a> {
+    start: Option<&'a TrieNode>, // Not yet checked for being a word itself.
+    stack: Vec<std::slice::Iter<'a, TrieNode>>,
+    word: String,
+}
+
+impl<'a> Words<'a> {
+    fn new(start: &'a TrieNode, prefix: String) -> Self {
+        Words {
+            start: Some(start),
+            stack: vec![start.children.iter()],
+            word: prefix,
+        }
+    }
+
+    fn empty() -> Self {
+        Words {
+            start: None,
+            stack: vec![],
+            word: String::new(),
+        }
+    }
+}
+
+impl Iterator for Words<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start) = self.start.take()
+            && start.is_terminal
+        {
+            return Some(self.word.clone());
+        }
+        loop {
+            let children = self.stack.last_mut()?;
+            match children.next() {
+                Some(child) => {
+                    self.word.extend(child.ch);
+                    self.stack.push(child.children.iter());
+                    if child.is_terminal {
+                        return Some(self.word.clone());
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    // The start node's char belongs to the prefix.
+                    if !self.stack.is_empty() {
+                        self.word.pop();
+                    }
+                }
+            }
         }
     }
 }
@@ -77,6 +1074,7 @@ impl TrieNode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board::RuzzleBoard;
 
     #[test]
     fn overlapping_words() {
@@ -118,4 +1116,834 @@ mod tests {
         assert_eq!(root.leaf_count(), 3); // final 'e's in trie and tree, 'y' in rusty.
         assert_eq!(root.max_depth(), 6); // "rusty". root node counts.
     }
+
+    #[test]
+    fn from_word_file() {
+        let path = std::env::temp_dir().join(format!("ruzzle-words-{}.txt", std::process::id()));
+        std::fs::write(&path, "hello\r\nworld\n\n  \r\ntrie  \n\nrust").unwrap();
+        let root = TrieNode::from_word_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(root.contains_word("hello"));
+        assert!(root.contains_word("world"));
+        assert!(root.contains_word("trie"));
+        assert!(root.contains_word("rust"));
+        assert!(!root.contains_word(""));
+        assert_eq!(root.leaf_count(), 4);
+    }
+
+    #[test]
+    fn from_missing_word_file() {
+        let path = std::env::temp_dir().join("ruzzle-words-does-not-exist.txt");
+        assert!(TrieNode::from_word_file(&path).is_err());
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn from_gzipped_word_file() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut gz = GzEncoder::new(vec![], Compression::default());
+        gz.write_all(b"hello\nworld\n\n  trie  \nrust\n").unwrap();
+        let gz = gz.finish().unwrap();
+
+        // Found by extension, or by magic bytes without one.
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        for name in [
+            format!("ruzzle-words-{}.txt.gz", id),
+            format!("ruzzle-words-{}.dat", id),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, &gz).unwrap();
+            let root = TrieNode::from_word_file(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            let root = root.unwrap();
+            assert_eq!(root.leaf_count(), 4);
+            assert!(root.contains_word("trie"));
+        }
+
+        let path = dir.join(format!("ruzzle-words-{}-bad.gz", id));
+        std::fs::write(&path, b"hello\n").unwrap();
+        let result = TrieNode::from_word_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[cfg(not(feature = "flate2"))]
+    #[test]
+    fn gzipped_word_file_needs_flate2() {
+        let path = std::env::temp_dir().join(format!("ruzzle-words-{}-gz.dat", std::process::id()));
+        std::fs::write(&path, b"\x1f\x8b\x08\x00").unwrap();
+        let err = TrieNode::from_word_file(&path).map(|_| ()).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn case_insensitive_words() {
+        let mut root = TrieNode::new_root();
+        root.add_word("HELLO");
+        root.add_word("World");
+        root.add_word("ÅSNA");
+
+        assert!(root.contains_word("hello"));
+        assert!(root.contains_word("HeLLo"));
+        assert!(root.contains_word("world"));
+        assert!(root.contains_word("åsna"));
+        assert_eq!(root.node_count(), 1 + 5 + 5 + 4);
+
+        // Lowercases to two chars: 'i' followed by a combining dot above.
+        root.add_word("İ");
+        assert!(root.contains_word("i\u{307}"));
+    }
+
+    #[test]
+    fn prune_to_board_counts_letters() {
+        let mut root = TrieNode::new_root();
+        for word in ["zz", "zzz", "jazz", "buzz", "fizz", "quiz"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("xazj zbux xxxx xxxx").unwrap();
+        let pruned = root.prune_to_board(&board);
+
+        assert!(pruned.contains_word("zz"));
+        assert!(pruned.contains_word("jazz"));
+        assert!(pruned.contains_word("buzz"));
+        assert!(!pruned.contains_word("zzz")); // Only two 'z's on the board.
+        assert!(!pruned.contains_word("fizz"));
+        assert!(!pruned.contains_word("quiz"));
+        assert_eq!(pruned.node_count(), 1 + 2 + 4 + 4);
+
+        // A wildcard makes up for one missing letter.
+        let board = RuzzleBoard::try_from("xazj zbu? xxxx xxxx").unwrap();
+        let pruned = root.prune_to_board(&board);
+        assert!(pruned.contains_word("zzz"));
+        assert!(!pruned.contains_word("fizz"));
+    }
+
+    #[test]
+    fn words_iterator() {
+        let mut root = TrieNode::new_root();
+        for word in ["he", "hell", "hello", "tree"] {
+            root.add_word(word);
+        }
+        let words: Vec<String> = root.words().collect();
+        assert_eq!(words, ["he", "hell", "hello", "tree"]);
+
+        let words: Vec<String> = root.words_with_prefix("hel").collect();
+        assert_eq!(words, ["hell", "hello"]);
+        let words: Vec<String> = root.words_with_prefix("HELL").collect();
+        assert_eq!(words, ["hell", "hello"]);
+        assert_eq!(root.words_with_prefix("x").count(), 0);
+        assert_eq!(root.words_with_prefix("").count(), 4);
+
+        // The empty string is a word like any other.
+        root.add_word("");
+        assert_eq!(root.words().next(), Some(String::new()));
+        assert_eq!(TrieNode::new_root().words().count(), 0);
+    }
+
+    #[test]
+    fn completions() {
+        let mut root = TrieNode::new_root();
+        for word in ["car", "cart", "cab", "carpet", "cars", "cat", "dog"] {
+            root.add_word(word);
+        }
+        // The prefix is a word, and comes first as the shortest.
+        assert_eq!(root.complete("car", 10), ["car", "cars", "cart", "carpet"]);
+        assert_eq!(root.complete("CA", 10)[..3], ["cab", "car", "cat"]);
+        // Neither is a prefix of anything.
+        assert!(root.complete("cow", 10).is_empty());
+        assert!(root.complete("cap", 10).is_empty());
+
+        assert_eq!(root.complete("ca", 4), ["cab", "car", "cat", "cars"]);
+        assert_eq!(root.complete("", 1), ["cab"]);
+        assert!(root.complete("car", 0).is_empty());
+    }
+
+    #[test]
+    fn word_counts() {
+        let mut root = TrieNode::new_root();
+        assert_eq!(root.word_count(), 0);
+        assert!(root.word_length_histogram().is_empty());
+
+        for word in ["a", "at", "ate", "tea", "tee", "tea", "teapot"] {
+            root.add_word(word);
+        }
+        assert_eq!(root.word_count(), 6);
+        assert_eq!(root.word_length_histogram(), [0, 1, 1, 3, 0, 0, 1]);
+        // Lengths from the node on, without its own 't'.
+        let t = root.find_in_children('t').unwrap();
+        assert_eq!(t.word_count(), 3);
+        assert_eq!(t.word_length_histogram(), [0, 0, 2, 0, 0, 1]);
+
+        root.add_word("");
+        assert_eq!(root.word_count(), 7);
+        assert_eq!(root.word_length_histogram()[0], 1);
+        // Two chars, since "İ" lowercases to 'i' and a combining dot.
+        root.add_word("İ");
+        assert_eq!(root.word_length_histogram()[2], 2);
+    }
+
+    #[test]
+    fn longest_words() {
+        let mut root = TrieNode::new_root();
+        assert_eq!(root.longest_word(), None);
+        assert!(root.words_longer_than(0).is_empty());
+
+        for word in ["tea", "team", "teams", "teapot", "zealot", "tee", "a"] {
+            root.add_word(word);
+        }
+        // "teapot" and "zealot" tie, and "teapot" comes first.
+        assert_eq!(root.longest_word().as_deref(), Some("teapot"));
+        assert_eq!(root.max_depth(), 7);
+        assert_eq!(root.words_longer_than(4), ["teams", "teapot", "zealot"]);
+        assert_eq!(root.words_longer_than(5), ["teapot", "zealot"]);
+        assert!(root.words_longer_than(6).is_empty());
+        assert_eq!(root.words_longer_than(0).len(), 7);
+
+        root.add_word("teaspoonful");
+        assert_eq!(root.longest_word().as_deref(), Some("teaspoonful"));
+        root.remove_word("teaspoonful");
+        assert_eq!(root.longest_word().as_deref(), Some("teapot"));
+
+        let mut empty_word = TrieNode::new_root();
+        empty_word.add_word("");
+        assert_eq!(empty_word.longest_word().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn differences() {
+        let mut twl = TrieNode::new_root();
+        twl.add_words(["tea", "team", "teams", "zealot", "qi", "a"]);
+        let mut sowpods = TrieNode::new_root();
+        sowpods.add_words(["tea", "teams", "te", "zealots", "qi", "za", "zo"]);
+
+        let removed: Vec<String> = twl.difference(&sowpods).collect();
+        assert_eq!(removed, ["a", "team", "zealot"]);
+        let added: Vec<String> = sowpods.difference(&twl).collect();
+        assert_eq!(added, ["te", "za", "zealots", "zo"]);
+        assert_eq!(twl.difference(&twl).count(), 0);
+        assert_eq!(twl.difference(&TrieNode::new_root()).count(), 6);
+    }
+
+    // Every node's char, flags and children, depth first.
+    fn shape(node: &TrieNode) -> String {
+        let children: Vec<String> = node.children.iter().map(shape).collect();
+        format!(
+            "{:?}{}{}({})",
+            node.ch,
+            node.is_terminal,
+            node.has_terminal_descendant,
+            children.join(",")
+        )
+    }
+
+    #[test]
+    fn bulk_insert() {
+        let sorted = [
+            "a", "an", "and", "ant", "anti", "be", "bee", "been", "beet", "bo",
+        ];
+        let mut one_by_one = TrieNode::new_root();
+        for word in sorted {
+            one_by_one.add_word(word);
+        }
+        let mut bulk = TrieNode::new_root();
+        bulk.add_words(sorted);
+        assert_eq!(shape(&bulk), shape(&one_by_one));
+
+        // Out of order, repeated and mixed case, and on top of other words.
+        let mut unsorted = TrieNode::new_root();
+        unsorted.add_word("ant");
+        unsorted.add_words(["bo", "BEET", "a", "anti", "been", "and"]);
+        unsorted.add_words(["bee", "an", "be", "and", "a"].map(String::from));
+        assert_eq!(shape(&unsorted), shape(&one_by_one));
+        assert_eq!(unsorted.words().collect::<Vec<_>>(), sorted);
+
+        let mut empty = TrieNode::new_root();
+        empty.add_words(["", "a"]);
+        assert!(empty.is_terminal && empty.contains_word("a"));
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn default_dictionary() {
+        let root = TrieNode::default_dictionary();
+        assert!(root.word_count() > 2000);
+        for word in ["the", "water", "house", "friend", "quick", "zebra"] {
+            assert!(root.contains_word(word), "{}", word);
+        }
+        assert!(!root.contains_word("qwzx"));
+
+        let board = RuzzleBoard::try_from("catsxxxxdogsxxxx").unwrap();
+        let results = crate::solver::solve(&root, &board, &crate::solver::SolveOptions::default());
+        let words: Vec<&str> = results.iter().map(|result| result.word.as_str()).collect();
+        assert!(words.contains(&"cats") && words.contains(&"dogs"));
+    }
+
+    #[test]
+    fn collects() {
+        let words = ["tea", "Team", "teapot", "tea"];
+        let mut root: TrieNode = words.iter().collect();
+        assert_eq!(root.word_count(), 3);
+        assert!(root.contains_word("team") && !root.contains_word("te"));
+
+        root.extend(vec!["te".to_string(), "zoo".to_string()]);
+        assert_eq!(root.word_count(), 5);
+        assert!(root.contains_word("te") && root.contains_word("zoo"));
+        assert_eq!(
+            root,
+            ["te", "tea", "team", "teapot", "zoo"].into_iter().collect()
+        );
+
+        let empty: TrieNode = std::iter::empty::<&str>().collect();
+        assert_eq!(empty, TrieNode::new_root());
+    }
+
+    #[test]
+    fn reads_any_reader() {
+        let input = "  Tea\r\n\n\t\ntee \nxxxxxxxxxx\ntea\nlast-without-newline";
+        let mut root = TrieNode::new_root();
+        let opts = LoadOptions {
+            max_len: 8,
+            ..LoadOptions::default()
+        };
+        let stats = root
+            .add_reader(io::Cursor::new(input.as_bytes().to_vec()), &opts)
+            .unwrap();
+        assert_eq!(
+            stats,
+            LoadStats {
+                lines: 7,
+                words: 3,
+                skipped: 2,
+                malformed: 0,
+                rejected: 0,
+                stripped: 0,
+            }
+        );
+        assert_eq!(root.words().collect::<Vec<_>>(), ["tea", "tee"]);
+
+        // The default limit is well above any real word.
+        let root = TrieNode::from_reader(io::Cursor::new(input.as_bytes().to_vec())).unwrap();
+        assert_eq!(root.word_count(), 4);
+        assert!(root.contains_word("last-without-newline"));
+        let long = "x".repeat(DEFAULT_MAX_WORD_LEN + 1);
+        assert_eq!(
+            TrieNode::from_reader(long.as_bytes()).unwrap().word_count(),
+            0
+        );
+
+        let invalid = io::Cursor::new(b"tea\n\xff\xfe\n".to_vec());
+        let err = TrieNode::from_reader(invalid).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reports_progress() {
+        // Every third line blank, so that lines and words differ.
+        let input: String = (0..25_000)
+            .map(|n| match n % 3 {
+                0 => "\n".to_string(),
+                _ => format!("word{}\n", n),
+            })
+            .collect();
+        let mut reports = vec![];
+        let root = TrieNode::from_reader_with_progress(input.as_bytes(), |progress| {
+            reports.push(progress)
+        })
+        .unwrap();
+        assert_eq!(reports.len(), 3);
+        assert!(
+            reports
+                .windows(2)
+                .all(|pair| { pair[0].lines < pair[1].lines && pair[0].words < pair[1].words })
+        );
+        assert_eq!(reports[0].lines, PROGRESS_LINES);
+        assert_eq!(
+            reports.last(),
+            Some(&LoadProgress {
+                lines: 25_000,
+                words: root.word_count(),
+            })
+        );
+
+        // Short lists only report their totals.
+        let mut reports = vec![];
+        TrieNode::from_reader_with_progress("tea\n\ntee\n".as_bytes(), |progress| {
+            reports.push(progress)
+        })
+        .unwrap();
+        assert_eq!(reports, [LoadProgress { lines: 3, words: 2 }]);
+    }
+
+    #[test]
+    fn reads_columns() {
+        let input = "# word\tcount\n\
+                     tea\t120\n\
+                     tee 7 noun\n\
+                     \x20 # indented comment\n\
+                     teapot\n\
+                     \n\
+                     tease\tlots\n\
+                     te#a -3\n";
+        let columns = LoadOptions {
+            format: LineFormat::Columns,
+            ..LoadOptions::default()
+        };
+        let mut words = vec![];
+        let stats = read_word_list(input.as_bytes(), &columns, |word, weight| {
+            words.push((word.to_string(), weight))
+        })
+        .unwrap();
+        assert_eq!(
+            words,
+            [
+                ("tea".to_string(), Some(120)),
+                ("tee".to_string(), Some(7)),
+                ("teapot".to_string(), None),
+                ("tease".to_string(), None),
+                ("te#a".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            stats,
+            LoadStats {
+                lines: 8,
+                words: 5,
+                skipped: 0,
+                malformed: 2,
+                rejected: 0,
+                stripped: 0,
+            }
+        );
+
+        // Plain lines are words as they are, comments and columns included.
+        let mut root = TrieNode::new_root();
+        let stats = root
+            .add_reader(input.as_bytes(), &LoadOptions::default())
+            .unwrap();
+        assert_eq!((stats.words, stats.malformed), (7, 0));
+        assert!(root.contains_word("tea\t120") && !root.contains_word("tea"));
+    }
+
+    #[test]
+    fn filters_words() {
+        let input = include_str!("../testdata/punctuated.txt");
+        let load = |filter| {
+            let mut root = TrieNode::new_root();
+            let opts = LoadOptions {
+                filter,
+                ..LoadOptions::default()
+            };
+            let stats = root.add_reader(input.as_bytes(), &opts).unwrap();
+            let words: Vec<String> = root.words().collect();
+            (words, stats.words, stats.rejected, stats.stripped)
+        };
+        assert_eq!(
+            load(WordFilter::KeepAll),
+            (
+                [
+                    "42", "abc123", "café", "don't", "e-mail", "mail", "tea", "x-ray"
+                ]
+                .map(String::from)
+                .to_vec(),
+                8,
+                0,
+                0
+            )
+        );
+        assert_eq!(
+            load(WordFilter::SkipNonAlphabetic),
+            (["café", "mail", "tea"].map(String::from).to_vec(), 3, 5, 0)
+        );
+        // "e-mail" becomes "email"; "42" is left with nothing.
+        assert_eq!(
+            load(WordFilter::StripAndKeep),
+            (
+                ["abc", "café", "dont", "email", "mail", "tea", "xray"]
+                    .map(String::from)
+                    .to_vec(),
+                7,
+                1,
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn payloads() {
+        let payload = |frequency| Payload {
+            frequency: Some(frequency),
+            definition: None,
+        };
+        let mut root = TrieNode::new_root();
+        root.add_word_with("Tea", payload(50));
+        root.add_word("teapot");
+        assert_eq!(root.get("tea"), Some(&payload(50)));
+        assert_eq!(root.get("TEA"), Some(&payload(50)));
+        assert_eq!(root.get("teapot"), None);
+        assert_eq!(root.get("te"), None);
+
+        // Adding a word again keeps its payload, unless it comes with one.
+        root.add_word("tea");
+        assert_eq!(root.get("tea"), Some(&payload(50)));
+        root.add_word_with("tea", payload(7));
+        assert_eq!(root.get("tea"), Some(&payload(7)));
+        let board = RuzzleBoard::try_from("teaxxxxxxxxxxxxx").unwrap();
+        assert_eq!(root.prune_to_board(&board).get("tea"), Some(&payload(7)));
+
+        // The node stays for "teapot", but the payload goes with the word.
+        root.remove_word("tea");
+        root.add_word("tea");
+        assert_eq!(root.get("tea"), None);
+
+        let mut columns = TrieNode::new_root();
+        let opts = LoadOptions {
+            format: LineFormat::Columns,
+            ..LoadOptions::default()
+        };
+        columns
+            .add_reader("tea 50\nteapot\ntee x\n".as_bytes(), &opts)
+            .unwrap();
+        assert_eq!(columns.get("tea"), Some(&payload(50)));
+        assert_eq!(columns.get("teapot"), None);
+        assert_eq!(columns.get("tee"), None);
+    }
+
+    #[test]
+    fn near_matches() {
+        let mut root = TrieNode::new_root();
+        root.add_words([
+            "hello", "help", "hell", "helot", "hallo", "he", "yellow", "a",
+        ]);
+        assert_eq!(
+            root.near_matches("helo", 1),
+            ["hell", "hello", "helot", "help"]
+        );
+        assert_eq!(root.near_matches("HELO", 1), root.near_matches("helo", 1));
+        assert_eq!(
+            root.near_matches("helo", 2),
+            ["hallo", "he", "hell", "hello", "helot", "help"]
+        );
+        // Capped at MAX_EDITS.
+        assert_eq!(root.near_matches("helo", 9), root.near_matches("helo", 2));
+        assert_eq!(root.near_matches("", 1), ["a"]);
+        assert!(root.near_matches("xyzzy", 2).is_empty());
+
+        for word in ["hello", "hel", "yellow", "", "a", "b"] {
+            let exact = root.near_matches(word, 0);
+            assert_eq!(exact.len(), usize::from(root.contains_word(word)));
+        }
+        assert_eq!(root.near_matches("Yellow", 0), ["yellow"]);
+    }
+
+    #[test]
+    fn prefixes() {
+        let mut root = TrieNode::new_root();
+        assert!(!root.has_prefix(""));
+        for word in ["cart", "cab"] {
+            root.add_word(word);
+        }
+        assert!(root.has_prefix(""));
+        assert!(root.has_prefix("Car"));
+        assert!(root.has_prefix("cart"));
+        assert!(!root.has_prefix("carts"));
+        assert!(!root.has_prefix("d"));
+
+        assert_eq!(root.longest_prefix_of("carton"), 4);
+        assert_eq!(root.longest_prefix_of("cat"), 2);
+        assert_eq!(root.longest_prefix_of("CAB"), 3);
+        assert_eq!(root.longest_prefix_of("dog"), 0);
+        assert_eq!(root.longest_prefix_of(""), 0);
+    }
+
+    #[test]
+    fn terminal_descendants() {
+        let node = |root: &TrieNode, prefix: &str| -> (bool, bool) {
+            let node = root.find_prefix(prefix).unwrap();
+            (node.is_terminal, node.has_terminal_descendant())
+        };
+        let mut root = TrieNode::new_root();
+        assert!(!root.has_terminal_descendant());
+
+        root.add_word("abc");
+        assert_eq!(node(&root, ""), (false, true));
+        assert_eq!(node(&root, "ab"), (false, true));
+        assert_eq!(node(&root, "abc"), (true, false));
+
+        root.add_word("ab");
+        root.add_word("abcd");
+        assert_eq!(node(&root, "ab"), (true, true));
+        assert_eq!(node(&root, "abc"), (true, true));
+
+        root.remove_word("abcd");
+        assert_eq!(node(&root, "abc"), (true, false));
+        root.remove_word("abc");
+        assert_eq!(node(&root, "ab"), (true, false));
+        assert_eq!(node(&root, "a"), (false, true));
+        root.remove_word("ab");
+        assert!(!root.has_terminal_descendant());
+        assert!(!root.has_prefix("a"));
+
+        // Without a 'c' on the board, "ab" is kept but "abc" isn't.
+        let board = RuzzleBoard::try_from("abxxxxxxxxxxxxxx").unwrap();
+        root.add_word("abc");
+        assert!(!root.prune_to_board(&board).has_terminal_descendant());
+        root.add_word("ab");
+        let pruned = root.prune_to_board(&board);
+        assert_eq!(node(&pruned, "ab"), (true, false));
+        assert!(pruned.has_terminal_descendant());
+    }
+
+    #[test]
+    fn remove_word() {
+        let mut root = TrieNode::new_root();
+        for word in ["he", "hell", "hello", "tree"] {
+            root.add_word(word);
+        }
+        assert_eq!(root.node_count(), 10);
+
+        assert!(root.remove_word("hello"));
+        assert!(!root.contains_word("hello"));
+        assert!(root.contains_word("hell"));
+        assert!(root.contains_word("he"));
+        assert_eq!(root.node_count(), 9);
+
+        // A prefix of another word only loses its terminal flag.
+        assert!(root.remove_word("he"));
+        assert!(!root.contains_word("he"));
+        assert!(root.contains_word("hell"));
+        assert_eq!(root.node_count(), 9);
+
+        // Removing the last word of a branch removes the whole branch.
+        assert!(root.remove_word("HELL"));
+        assert_eq!(root.node_count(), 5);
+
+        assert!(!root.remove_word("hell"));
+        assert!(!root.remove_word("tre"));
+        assert!(!root.remove_word("trees"));
+        assert!(!root.remove_word(""));
+        assert_eq!(root.words().collect::<Vec<_>>(), ["tree"]);
+    }
+
+    #[test]
+    fn clones_and_compares() {
+        let mut root = TrieNode::new_root();
+        root.add_words(["at", "ate", "be"]);
+        let snapshot = root.clone();
+        assert_eq!(root, snapshot);
+
+        root.add_word("bee");
+        assert_ne!(root, snapshot);
+        root.remove_word("bee");
+        assert_eq!(root, snapshot);
+        root.add_word_with("at", Payload::default());
+        assert_ne!(root, snapshot);
+
+        // The same words added in another order, so with other capacities.
+        let mut other = TrieNode::new_root();
+        other.add_words(["be", "ate", "at"]);
+        assert_eq!(other, snapshot);
+    }
+
+    #[test]
+    fn debug_output() {
+        let mut root = TrieNode::new_root();
+        assert_eq!(format!("{:?}", root), "{}");
+        root.add_words(["at", "ate", "be", "teapot"]);
+        assert_eq!(
+            format!("{:?}", root),
+            "{a: {t*: {e*}}, b: {e*}, t: {e: {a: {p: {…}}}}}"
+        );
+        root.add_word("");
+        assert!(format!("{:?}", root).starts_with("*{a: "));
+    }
+
+    #[test]
+    fn very_long_word() {
+        // Deep enough that recursing once per letter would overflow the stack.
+        let word = "ab".repeat(50_000);
+        let mut root = TrieNode::new_root();
+        root.add_word(&word);
+        root.add_word(&word[..1000]);
+        assert!(root.contains_word(&word));
+        assert!(!root.contains_word(&word[..99_999]));
+        assert!(root.has_prefix(&word[..5000]));
+        assert_eq!(root.node_count(), 100_001);
+        assert_eq!(root.leaf_count(), 1);
+        assert_eq!(root.max_depth(), 100_001);
+        assert_eq!(root.word_count(), 2);
+        assert_eq!(root.word_length_histogram().len(), 100_001);
+        assert_eq!(root.longest_word().as_deref(), Some(word.as_str()));
+        assert_eq!(root.memory_usage().nodes, 100_001);
+        root.shrink_to_fit();
+
+        assert!(root.remove_word(&word));
+        assert!(!root.contains_word(&word));
+        assert_eq!(root.node_count(), 1001);
+        assert!(root.remove_word(&word[..1000]));
+        assert_eq!(root.node_count(), 1);
+        assert!(!root.has_terminal_descendant());
+
+        root.add_words([&word]);
+        assert_eq!(root.words().next().as_deref(), Some(word.as_str()));
+
+        let copy = root.clone();
+        assert_eq!(copy, root);
+        assert!(copy.contains_word(&word));
+        let mut other = TrieNode::new_root();
+        other.add_word(&word[..99_999]);
+        assert_ne!(other, root);
+
+        let mut bytes = vec![];
+        root.write_to(&mut bytes).unwrap();
+        assert_eq!(TrieNode::read_from(&bytes[..]).unwrap(), root);
+    }
+
+    #[test]
+    fn shrinks() {
+        let mut root = TrieNode::new_root();
+        for a in 'a'..='e' {
+            for b in 'a'..='e' {
+                root.add_word(&format!("{}{}", a, b));
+            }
+        }
+        root.add_word_with(
+            "ab",
+            Payload {
+                frequency: None,
+                definition: Some(String::with_capacity(100) + "a definition"),
+            },
+        );
+        let before = root.memory_usage();
+        assert_eq!(before.nodes, 31);
+        assert_eq!(before.nodes, root.node_count());
+        // Each of the 6 Vecs of 5 children grew to a capacity of 8.
+        assert!(before.allocated_bytes > before.used_bytes);
+
+        root.shrink_to_fit();
+        let after = root.memory_usage();
+        assert_eq!(after.used_bytes, before.used_bytes);
+        assert_eq!(after.allocated_bytes, after.used_bytes);
+        assert_eq!(
+            before.allocated_bytes - after.allocated_bytes,
+            6 * 3 * size_of::<TrieNode>() + 88
+        );
+        assert!(root.contains_word("ee"));
+    }
+
+    // Every child is found, by the letter index where there is one, and
+    // letters without a child aren't.
+    fn assert_lookups(node: &TrieNode) {
+        assert_eq!(
+            node.letter_index.is_some(),
+            node.children.len() >= DENSE_CHILDREN
+        );
+        for ch in ('a'..='z').chain(['\'', 'å', 'é', 'ö', 'ä']) {
+            let found = node
+                .find_in_children(ch)
+                .map(|child| child as *const TrieNode);
+            let searched = node
+                .child_position(ch)
+                .ok()
+                .map(|pos| &node.children[pos] as *const _);
+            assert_eq!(found, searched, "{:?} below {:?}", ch, node.ch);
+        }
+        node.children.iter().for_each(assert_lookups);
+    }
+
+    #[test]
+    fn dense_children() {
+        let mut root = TrieNode::new_root();
+        let words: Vec<String> = ('a'..='z').map(|ch| format!("{}x", ch)).collect();
+        root.add_words(&words);
+        root.add_words(["åsna", "éclair", "öl", "zzz"]);
+        // Before 'a', so every letter's position moves along one.
+        root.add_word("'tis");
+        assert!(root.letter_index.is_some());
+        assert_lookups(&root);
+        assert!(root.contains_word("'tis") && root.contains_word("éclair"));
+        assert!(root.contains_word("qx") && !root.contains_word("qy"));
+
+        let mut bytes = vec![];
+        root.write_to(&mut bytes).unwrap();
+        assert_lookups(&TrieNode::read_from(bytes.as_slice()).unwrap());
+        let board = RuzzleBoard::try_from("qxzzabcdefghijkl").unwrap();
+        assert_lookups(&root.prune_to_board(&board));
+
+        for word in &words[..24] {
+            root.remove_word(word);
+        }
+        // 'y', 'z', '\'', 'å', 'é' and 'ö' are left.
+        assert_eq!(root.children.len(), 6);
+        assert_lookups(&root);
+        assert!(root.contains_word("zzz") && !root.contains_word("ax"));
+
+        let mut bulk = TrieNode::new_root();
+        bulk.add_words(words.iter().chain([&"éclair".to_string()]));
+        assert_lookups(&bulk);
+    }
+
+    fn assert_children_sorted(node: &TrieNode) {
+        assert!(node.children.windows(2).all(|pair| pair[0].ch < pair[1].ch));
+        node.children.iter().for_each(assert_children_sorted);
+    }
+
+    #[test]
+    fn children_stay_sorted() {
+        let mut root = TrieNode::new_root();
+        let words = [
+            "tree", "apple", "zebra", "trie", "ant", "tea", "app", "zoo", "bee", "ä", "b",
+        ];
+        for (n, word) in words.iter().enumerate() {
+            root.add_word(word);
+            assert_children_sorted(&root);
+            if n % 3 == 2 {
+                root.remove_word(words[n - 1]);
+                assert_children_sorted(&root);
+                root.add_word(words[n - 1]);
+            }
+        }
+        for word in words {
+            assert!(root.contains_word(word));
+        }
+
+        let mut sorted = words.to_vec();
+        sorted.sort();
+        assert_eq!(root.words().collect::<Vec<_>>(), sorted);
+    }
+
+    #[test]
+    fn merged_word_files() {
+        let dir = std::env::temp_dir();
+        let write = |name: &str, contents: &str| {
+            let path = dir.join(format!("ruzzle-merge-{}-{}.txt", name, std::process::id()));
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let base = write("base", "hello\nworld\ntrie\nrust\n");
+        let additions = write("additions", "rust\nrusty\nzyzzyva\n");
+        let removals = write("removals", "world\nzyzzyva\nnever\n");
+
+        let mut root = TrieNode::from_word_file(&base).unwrap();
+        root.add_word_file(&additions).unwrap();
+        root.remove_word_file(&removals).unwrap();
+        for path in [base, additions, removals] {
+            std::fs::remove_file(path).unwrap();
+        }
+
+        // Only in the base, in both, only in the additions, removed.
+        assert!(root.contains_word("hello"));
+        assert!(root.contains_word("rust"));
+        assert!(root.contains_word("rusty"));
+        assert!(!root.contains_word("world"));
+        assert!(!root.contains_word("zyzzyva"));
+        assert!(!root.contains_word("never"));
+        assert_eq!(root.words().count(), 4);
+    }
 }
@@ -0,0 +1,129 @@
+use crate::board::Board;
+use crate::score::{BonusBoard, LetterValues, score_word};
+use crate::solver::SolveResult;
+use crate::tile::Tile;
+use crate::trie::TrieNode;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub index: usize, // Position of the result in the checked slice.
+    pub word: String,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    InvalidPath,
+    NotInDictionary,
+    WrongScore { expected: u32, got: u32 },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "result {} ({:?}): ", self.index, self.word)?;
+        match self.kind {
+            ValidationErrorKind::InvalidPath => write!(f, "path does not spell the word"),
+            ValidationErrorKind::NotInDictionary => write!(f, "not in the dictionary"),
+            ValidationErrorKind::WrongScore { expected, got } => {
+                write!(f, "scored {} but should be {}", got, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// Checks results from somewhere else (another solver, a reference file, a
+// human) against the board and dictionary. Scores are recomputed with
+// bonuses and letter values. Every problem is reported, not just the first
+// one.
+pub fn validate_solution<const W: usize, const H: usize>(
+    root: &TrieNode,
+    board: &Board<Tile, W, H>,
+    bonuses: &BonusBoard<W, H>,
+    values: &LetterValues,
+    results: &[SolveResult<W, H>],
+) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    for (index, result) in results.iter().enumerate() {
+        let mut report = |kind| {
+            errors.push(ValidationError {
+                index,
+                word: result.word.clone(),
+                kind,
+            })
+        };
+        if !board.is_valid_path(&result.path, &result.word) {
+            report(ValidationErrorKind::InvalidPath);
+        }
+        if !root.contains_word(&result.word) {
+            report(ValidationErrorKind::NotInDictionary);
+        }
+        // A score for an invalid path means nothing.
+        if board.is_traceable(&result.path) {
+            let expected = score_word(values, board, bonuses, &result.path);
+            if result.score != expected {
+                report(ValidationErrorKind::WrongScore {
+                    expected,
+                    got: result.score,
+                });
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Index4x4, RuzzleBoard};
+    use crate::score::Bonus;
+    use crate::solver::{SolveOptions, solve};
+
+    #[test]
+    fn reports_corrupted_results() {
+        let mut root = TrieNode::new_root();
+        for word in ["ab", "fab", "jin", "kop", "plonk"] {
+            root.add_word(word);
+        }
+        let board = RuzzleBoard::try_from("abcdefghijklmnop").unwrap();
+        let opts = SolveOptions {
+            bonuses: BonusBoard::default().with_at(Bonus::TripleWord, Index4x4::from_xy(1, 0)),
+            ..SolveOptions::default()
+        };
+        let mut results = solve(&root, &board, &opts);
+        assert!(results.len() >= 4);
+        assert_eq!(
+            validate_solution(&root, &board, &opts.bonuses, &opts.letter_values, &results),
+            []
+        );
+
+        results[1].path.swap(0, 1);
+        results[3].score += 1;
+        results.push(SolveResult {
+            word: "fa".to_string(),
+            ..results[0].clone()
+        });
+        let errors = validate_solution(&root, &board, &opts.bonuses, &opts.letter_values, &results);
+        let kinds: Vec<(usize, &ValidationErrorKind)> =
+            errors.iter().map(|err| (err.index, &err.kind)).collect();
+        let last = results.len() - 1;
+        assert_eq!(
+            kinds,
+            [
+                (1, &ValidationErrorKind::InvalidPath),
+                (
+                    3,
+                    &ValidationErrorKind::WrongScore {
+                        expected: results[3].score - 1,
+                        got: results[3].score
+                    }
+                ),
+                (last, &ValidationErrorKind::InvalidPath),
+                (last, &ValidationErrorKind::NotInDictionary),
+            ]
+        );
+        assert!(errors[0].to_string().starts_with("result 1 "));
+    }
+}
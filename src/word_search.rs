@@ -0,0 +1,128 @@
+use crate::board::{Board, BoardIndex, Direction};
+use crate::dictionary::{Cursor, Dictionary};
+use crate::solver::{Path, descend_tile, push_tile};
+use crate::tile::Tile;
+
+// The directions a word reads in without being backwards: left to right,
+// or straight down.
+pub const FORWARD: [Direction; 4] = [Direction::NE, Direction::E, Direction::SE, Direction::S];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StraightWord<const W: usize = 4, const H: usize = 4> {
+    pub word: String,
+    pub direction: Direction,
+    pub path: Path<W, H>, // From the first letter on, a step in direction each.
+}
+
+// Every word running in a straight line on the board, as in a classic word
+// search, rather than snaking from tile to tile. Words of a single tile
+// aren't reported, since they don't run anywhere. Only the FORWARD
+// directions are searched unless reversed is set, in which case all eight
+// are. Results come by start in board order, then by direction in
+// Direction::ALL order, shorter words first.
+pub fn solve_straight<D: Dictionary, const W: usize, const H: usize>(
+    dict: &D,
+    board: &Board<Tile, W, H>,
+    reversed: bool,
+) -> Vec<StraightWord<W, H>> {
+    let directions: &[Direction] = if reversed { &Direction::ALL } else { &FORWARD };
+    let mut out = vec![];
+    for start in BoardIndex::all_indices_within_bounds() {
+        for &direction in directions {
+            let mut ray = Ray {
+                board,
+                direction,
+                word: String::new(),
+                path: vec![],
+                out: &mut out,
+            };
+            ray.walk(dict.root(), start);
+        }
+    }
+    out
+}
+
+struct Ray<'a, const W: usize, const H: usize> {
+    board: &'a Board<Tile, W, H>,
+    direction: Direction,
+    word: String,
+    path: Path<W, H>,
+    out: &'a mut Vec<StraightWord<W, H>>,
+}
+
+impl<const W: usize, const H: usize> Ray<'_, W, H> {
+    // No visited mask: a straight line never comes back to a tile, and
+    // leaving the board ends it.
+    fn walk<C: Cursor>(&mut self, cursor: C, idx: BoardIndex<W, H>) {
+        let tile = self.board[idx];
+        for node in descend_tile(cursor, tile) {
+            let len = self.word.len();
+            push_tile(&mut self.word, tile, node);
+            self.path.push(idx);
+            if node.is_terminal() && self.path.len() > 1 {
+                self.out.push(StraightWord {
+                    word: self.word.clone(),
+                    direction: self.direction,
+                    path: self.path.clone(),
+                });
+            }
+            if let Some(next) = idx.neighbour(self.direction) {
+                self.walk(node, next);
+            }
+            self.word.truncate(len);
+            self.path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::TrieNode;
+
+    #[test]
+    fn finds_words_in_lines() {
+        let mut root = TrieNode::new_root();
+        for word in ["cat", "at", "dog", "rat", "tar", "god", "go"] {
+            root.add_word(word);
+        }
+        let board = Board::<Tile, 5, 5>::try_from(
+            "c a t q q \
+             d q q q q \
+             r o q q q \
+             a q g q q \
+             t q q q q",
+        )
+        .unwrap();
+        let found = |reversed| -> Vec<(String, Direction)> {
+            solve_straight(&root, &board, reversed)
+                .into_iter()
+                .map(|found| (found.word, found.direction))
+                .collect()
+        };
+        let forward = [
+            ("cat", Direction::E),
+            ("at", Direction::E),
+            ("dog", Direction::SE),
+            ("rat", Direction::S),
+            ("at", Direction::S),
+        ]
+        .map(|(word, dir)| (word.to_string(), dir));
+        assert_eq!(found(false), forward);
+
+        // "go" and "god" up to the left from the 'g', "tar" up the left edge.
+        let backwards = [
+            ("go", Direction::NW),
+            ("god", Direction::NW),
+            ("tar", Direction::N),
+        ]
+        .map(|(word, dir)| (word.to_string(), dir));
+        let mut all = forward.to_vec();
+        all.extend(backwards);
+        assert_eq!(found(true), all);
+
+        let dog = &solve_straight(&root, &board, false)[2];
+        let xy: Vec<(usize, usize)> = dog.path.iter().map(|idx| idx.to_xy()).collect();
+        assert_eq!(xy, [(0, 1), (1, 2), (2, 3)]);
+    }
+}